@@ -0,0 +1,370 @@
+use std::{collections::HashMap, process::ExitCode};
+
+use flying_goose::{
+    board::{Board, types::Side},
+    book::{BookEntry, encode_move, polyglot_key::Random64},
+    pgn::{self, Game, GameResult},
+};
+
+const DEFAULT_DEPTH: usize = 20;
+const DEFAULT_MIN_GAMES: u32 = 1;
+
+// Usage: book-build <output book> <pgn file>... [--depth plies] [--min-games n]
+//                   [--min-elo rating]
+//
+// Walks the mainline of every game up to the depth and counts each move played from each
+// position, weighted 2 for a win and 1 for a draw of the side that played it. Moves
+// played in fewer than --min-games games are dropped, and with --min-elo only moves by
+// players whose Elo tag reaches the rating are counted. Keys come from Polyglot's own
+// Random64 table, so the book works with any Polyglot reader.
+struct Config {
+    output_path: String,
+    pgn_paths: Vec<String>,
+    depth: usize,
+    min_games: u32,
+    min_elo: Option<u32>,
+}
+
+fn parse_args(args: &[String]) -> Result<Config, String> {
+    let mut positional = Vec::new();
+    let mut depth = DEFAULT_DEPTH;
+    let mut min_games = DEFAULT_MIN_GAMES;
+    let mut min_elo = None;
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let mut value = |name: &str| {
+            args.next()
+                .ok_or_else(|| format!("{name} needs a value"))?
+                .parse::<u32>()
+                .map_err(|err| format!("invalid value for {name}: {err}"))
+        };
+        match arg.as_str() {
+            "--depth" => depth = value("--depth")? as usize,
+            "--min-games" => min_games = value("--min-games")?,
+            "--min-elo" => min_elo = Some(value("--min-elo")?),
+            _ => positional.push(arg.clone()),
+        }
+    }
+    if positional.len() < 2 {
+        return Err("expected an output book and at least one PGN file".into());
+    }
+    Ok(Config {
+        output_path: positional[0].clone(),
+        pgn_paths: positional[1..].to_vec(),
+        depth,
+        min_games,
+        min_elo,
+    })
+}
+
+#[derive(Default)]
+struct MoveStats {
+    games: u32,
+    wins: u32,
+    draws: u32,
+}
+
+impl MoveStats {
+    fn weight(&self) -> u64 {
+        2 * self.wins as u64 + self.draws as u64
+    }
+}
+
+struct BookBuilder<'a> {
+    random: &'a Random64,
+    config: &'a Config,
+    stats: HashMap<(u64, u16), MoveStats>,
+    games: usize,
+    skipped: usize,
+}
+
+impl BookBuilder<'_> {
+    fn elo_ok(&self, game: &Game, side: Side) -> bool {
+        let Some(min_elo) = self.config.min_elo else {
+            return true;
+        };
+        let tag = match side {
+            Side::White => "WhiteElo",
+            Side::Black => "BlackElo",
+        };
+        game.tag(tag)
+            .and_then(|elo| elo.parse::<u32>().ok())
+            .is_some_and(|elo| elo >= min_elo)
+    }
+
+    fn add_game(&mut self, game: &Game) {
+        let Some(result) = game.result else {
+            self.skipped += 1;
+            return;
+        };
+        let mut board = Board::init();
+        let start = match game.tag("FEN") {
+            Some(fen) => board.fen_setup(Some(fen)),
+            None => board.fen_setup(None),
+        };
+        if start.is_err() {
+            self.skipped += 1;
+            return;
+        }
+        self.games += 1;
+        for san in game.moves.iter().take(self.config.depth) {
+            let Ok(chess_move) = board.parse_san(san) else {
+                eprintln!("stopping game {} at illegal move {san}", self.games);
+                break;
+            };
+            let side = board.get_current_player();
+            if self.elo_ok(game, side) {
+                let key = self.random.key(&board);
+                let stats = self
+                    .stats
+                    .entry((key, encode_move(chess_move)))
+                    .or_default();
+                stats.games += 1;
+                match (result, side) {
+                    (GameResult::Draw, _) => stats.draws += 1,
+                    (GameResult::WhiteWin, Side::White) | (GameResult::BlackWin, Side::Black) => {
+                        stats.wins += 1
+                    }
+                    _ => {}
+                }
+            }
+            board.make(chess_move);
+        }
+    }
+
+    // Sorted by key and then by weight, with the weights of each position scaled down
+    // together when the largest does not fit the 16 bits Polyglot gives them
+    fn entries(&self) -> Vec<BookEntry> {
+        let mut by_key: HashMap<u64, Vec<(u16, u64)>> = HashMap::new();
+        for (&(key, raw_move), stats) in &self.stats {
+            if stats.games >= self.config.min_games && stats.weight() > 0 {
+                by_key
+                    .entry(key)
+                    .or_default()
+                    .push((raw_move, stats.weight()));
+            }
+        }
+        let mut entries = Vec::new();
+        for (key, moves) in by_key {
+            let max = moves.iter().map(|&(_, weight)| weight).max().unwrap_or(1);
+            let divisor = max.div_ceil(u16::MAX as u64).max(1);
+            entries.extend(moves.into_iter().map(|(raw_move, weight)| BookEntry {
+                key,
+                raw_move,
+                weight: (weight / divisor).max(1) as u16,
+            }));
+        }
+        entries.sort_by(|a, b| {
+            a.key
+                .cmp(&b.key)
+                .then(b.weight.cmp(&a.weight))
+                .then(a.raw_move.cmp(&b.raw_move))
+        });
+        entries
+    }
+}
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let config = match parse_args(&args) {
+        Ok(config) => config,
+        Err(err) => {
+            eprintln!("{err}");
+            eprintln!(
+                "usage: book-build <output book> <pgn file>... [--depth plies] \
+                 [--min-games n] [--min-elo rating]"
+            );
+            return ExitCode::FAILURE;
+        }
+    };
+    let random = Random64::polyglot();
+    let start = std::time::Instant::now();
+    let mut builder = BookBuilder {
+        random: &random,
+        config: &config,
+        stats: HashMap::new(),
+        games: 0,
+        skipped: 0,
+    };
+    for path in &config.pgn_paths {
+        let text = match std::fs::read(path) {
+            // Plenty of PGN files in the wild are Latin-1, and only the tags suffer
+            Ok(bytes) => String::from_utf8_lossy(&bytes).into_owned(),
+            Err(err) => {
+                eprintln!("could not read {path}: {err}");
+                return ExitCode::FAILURE;
+            }
+        };
+        for game in pgn::parse_games(&text) {
+            builder.add_game(&game);
+        }
+        eprintln!(
+            "{path}: {} games so far ({} skipped)",
+            builder.games, builder.skipped
+        );
+    }
+
+    let entries = builder.entries();
+    let bytes: Vec<u8> = entries.iter().flat_map(|entry| entry.to_bytes()).collect();
+    if let Err(err) = std::fs::write(&config.output_path, bytes) {
+        eprintln!("could not write {}: {err}", config.output_path);
+        return ExitCode::FAILURE;
+    }
+    eprintln!(
+        "Wrote {} entries to {} in {:.2?}",
+        entries.len(),
+        config.output_path,
+        start.elapsed()
+    );
+    ExitCode::SUCCESS
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // White's 2200 wins the d4 game, so a 2500 Elo floor drops 1. d4 from the book
+    const GAMES: &str = r#"[WhiteElo "2600"]
+[BlackElo "2400"]
+
+1. e4 e5 2. Nf3 1-0
+
+[WhiteElo "2500"]
+[BlackElo "2500"]
+
+1. e4 c5 1/2-1/2
+
+[WhiteElo "2200"]
+[BlackElo "2700"]
+
+1. d4 d5 1-0
+"#;
+
+    fn test_config(min_games: u32, min_elo: Option<u32>) -> Config {
+        Config {
+            output_path: String::new(),
+            pgn_paths: Vec::new(),
+            depth: DEFAULT_DEPTH,
+            min_games,
+            min_elo,
+        }
+    }
+
+    fn builder<'a>(random: &'a Random64, config: &'a Config) -> BookBuilder<'a> {
+        let mut builder = BookBuilder {
+            random,
+            config,
+            stats: HashMap::new(),
+            games: 0,
+            skipped: 0,
+        };
+        for game in pgn::parse_games(GAMES) {
+            builder.add_game(&game);
+        }
+        builder
+    }
+
+    fn board_after(moves: &[&str]) -> Board {
+        let mut board = Board::init();
+        board.fen_setup(None).unwrap();
+        for text in moves {
+            board.make(board.parse_uci(text).unwrap());
+        }
+        board
+    }
+
+    // The encoded moves and weights of the position after `moves`, in book order
+    fn book_moves(random: &Random64, entries: &[BookEntry], moves: &[&str]) -> Vec<(u16, u16)> {
+        let key = random.key(&board_after(moves));
+        entries
+            .iter()
+            .filter(|entry| entry.key == key)
+            .map(|entry| (entry.raw_move, entry.weight))
+            .collect()
+    }
+
+    fn encoded(moves: &[&str], text: &str) -> u16 {
+        encode_move(board_after(moves).parse_uci(text).unwrap())
+    }
+
+    #[test]
+    fn weights_count_wins_twice_and_draws_once() {
+        let random = Random64::polyglot();
+        let config = test_config(DEFAULT_MIN_GAMES, None);
+        let entries = builder(&random, &config).entries();
+        // e4 won once and drew once, d4 won once; moves that only lost are left out
+        assert_eq!(
+            book_moves(&random, &entries, &[]),
+            [(encoded(&[], "e2e4"), 3), (encoded(&[], "d2d4"), 2)]
+        );
+        assert_eq!(
+            book_moves(&random, &entries, &["e2e4"]),
+            [(encoded(&["e2e4"], "c7c5"), 1)]
+        );
+        assert!(book_moves(&random, &entries, &["d2d4"]).is_empty());
+        assert_eq!(entries.len(), 4);
+
+        assert!(entries.windows(2).all(|pair| {
+            (pair[0].key, std::cmp::Reverse(pair[0].weight))
+                <= (pair[1].key, std::cmp::Reverse(pair[1].weight))
+        }));
+    }
+
+    #[test]
+    fn min_games_and_min_elo_filter_moves() {
+        let random = Random64::polyglot();
+        let config = test_config(2, None);
+        let entries = builder(&random, &config).entries();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(
+            book_moves(&random, &entries, &[]),
+            [(encoded(&[], "e2e4"), 3)]
+        );
+
+        let config = test_config(DEFAULT_MIN_GAMES, Some(2500));
+        let entries = builder(&random, &config).entries();
+        assert_eq!(
+            book_moves(&random, &entries, &[]),
+            [(encoded(&[], "e2e4"), 3)]
+        );
+        // Black's 2400 lost anyway, but the 2500 draw still counts
+        assert_eq!(
+            book_moves(&random, &entries, &["e2e4"]),
+            [(encoded(&["e2e4"], "c7c5"), 1)]
+        );
+    }
+
+    #[test]
+    fn weights_too_big_for_u16_are_scaled_together() {
+        let random = Random64::polyglot();
+        let config = test_config(DEFAULT_MIN_GAMES, None);
+        let mut builder = builder(&random, &config);
+        let start = random.key(&board_after(&[]));
+        let e4 = encoded(&[], "e2e4");
+        let d4 = encoded(&[], "d2d4");
+        // 2 * 50_000 needs halving to fit, and halving d4's single draw would leave 0
+        builder.stats.insert(
+            (start, e4),
+            MoveStats {
+                games: 50_000,
+                wins: 50_000,
+                draws: 0,
+            },
+        );
+        builder.stats.insert(
+            (start, d4),
+            MoveStats {
+                games: 1,
+                wins: 0,
+                draws: 1,
+            },
+        );
+        let entries = builder.entries();
+        assert_eq!(book_moves(&random, &entries, &[]), [(e4, 50_000), (d4, 1)]);
+        // Other positions keep their weights as they are
+        assert_eq!(
+            book_moves(&random, &entries, &["e2e4"]),
+            [(encoded(&["e2e4"], "c7c5"), 1)]
+        );
+    }
+}
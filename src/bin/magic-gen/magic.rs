@@ -5,8 +5,7 @@ use std::sync::OnceLock;
 use flying_goose::board::types::{Direction, Square, SquareCoord};
 use flying_goose::movement::sliders::defs::{Slider, get_all_blockers_subsets};
 use flying_goose::movement::sliders::magics::MagicEntry;
-use flying_goose::types::print_bb;
-use flying_goose::types::{BitBoard, NumOf};
+use flying_goose::types::{Bitboard, NumOf};
 use flying_goose::types::{EMPTY_BITBOARD, FULL_BITBOARD};
 use rand::{Rng, SeedableRng};
 use rand_pcg::Pcg64;
@@ -33,7 +32,7 @@ fn init_square_lists() -> Result<(CornerArray, EdgeArray, InteriorArray), InitSq
     let mut edge_squares = Vec::with_capacity(NumOf::EDGE_SQUARES);
     let mut interior_squares = Vec::with_capacity(NumOf::INTERIOR_SQUARES);
     let directions: [Direction; 4] = [(0, 1), (0, -1), (1, 0), (-1, 0)];
-    for square_idx in 0..NumOf::SQUARES {
+    for square_idx in Square::ALL {
         let square: SquareCoord = SquareCoord::try_from(square_idx as u8).unwrap();
         let num_valid_directions = directions
             .iter()
@@ -60,7 +59,7 @@ pub fn get_slider_magics(
     slider: &Slider,
     seed: u64,
     verbose: bool,
-) -> (Vec<MagicEntry>, Vec<BitBoard>) {
+) -> (Vec<MagicEntry>, Vec<Bitboard>) {
     let mut rng = Pcg64::seed_from_u64(seed);
     // Sized to the classical no-sharing total: every square placed back-to-back with
     // zero overlap always fits, so this is a safe hard upper bound no matter how the
@@ -104,14 +103,15 @@ pub fn get_slider_magics(
 
         if verbose {
             println!(
-                "square {square_idx:>2}: bits={} offset={} table_len={} fill={:.1}% ({attempts} attempts)",
+                "square {:>2}: bits={} offset={} table_len={} fill={:.1}% ({attempts} attempts)",
+                square_idx.index(),
                 magic_entry.index_bits,
                 offset,
                 table.len(),
                 100.0 * fill as f64 / table.len() as f64
             );
         }
-        magic_entries[square_idx] = magic_entry;
+        magic_entries[square_idx.index()] = magic_entry;
     }
 
     let last_nonzero_attack = global_table
@@ -139,20 +139,19 @@ fn find_magic(
     slider: &Slider,
     square: Square,
     verbose: bool,
-) -> (MagicEntry, Vec<BitBoard>, u32) {
+) -> (MagicEntry, Vec<Bitboard>, u32) {
     const MAX_ATTEMPTS_WITHOUT_IMPROVEMENT: u32 = 100_000;
     const MAX_TOTAL_ATTEMPTS: u32 = 5_000_000;
     const PROGRESS_INTERVAL: u32 = 50_000;
 
-    let square_coord: SquareCoord = SquareCoord::try_from(square as u8).unwrap();
-    let blocker_mask = slider.get_blocker_mask(square_coord);
+    let blocker_mask = slider.get_blocker_mask(square);
     let distinct_values: usize = get_all_blockers_subsets(blocker_mask)
         .into_iter()
-        .map(|subset| slider.get_moves(square_coord, subset))
+        .map(|subset| slider.get_moves(square, subset))
         .collect::<HashSet<_>>()
         .len();
 
-    let mut best: Option<(MagicEntry, Vec<BitBoard>, usize)> = None;
+    let mut best: Option<(MagicEntry, Vec<Bitboard>, usize)> = None;
     let mut attempts_since_improvement: u32 = 0;
     let mut total_attempts: u32 = 0;
 
@@ -209,9 +208,8 @@ fn get_lookup_table(
     slider: &Slider,
     magic_entry: &MagicEntry,
     square: Square,
-) -> Result<Vec<BitBoard>, LookupTableCreationError> {
+) -> Result<Vec<Bitboard>, LookupTableCreationError> {
     let mut lookup_table = vec![EMPTY_BITBOARD; (1usize << magic_entry.index_bits)];
-    let square = SquareCoord::try_from(square as u8).unwrap();
     for blocker_subset in get_all_blockers_subsets(magic_entry.blocker_mask) {
         let eligible_moves = slider.get_moves(square, blocker_subset);
         let index = magic_entry.get_magic_index(blocker_subset);
@@ -239,7 +237,7 @@ pub fn print_magic_entries(entries: &[MagicEntry], slider_name: &str) {
     );
     for entry in entries {
         println!(
-            "    MagicEntry {{ number: {}, blocker_mask: {:#018x}, inverse_blocker_mask: {:#018x}, offset: {}, index_bits: {}, shift: {} }},",
+            "    MagicEntry {{ number: {}, blocker_mask: Bitboard({:#018x}), inverse_blocker_mask: Bitboard({:#018x}), offset: {}, index_bits: {}, shift: {} }},",
             entry.number,
             entry.blocker_mask,
             entry.inverse_blocker_mask,
@@ -270,7 +268,7 @@ mod tests {
             .spawn(|| {
                 let (entries, table) = get_slider_magics(&ROOK_SLIDER, RANDOM_SEED, true);
                 for (sq_idx, entry) in entries.iter().enumerate() {
-                    let sq = SquareCoord::try_from(sq_idx as u8).unwrap();
+                    let sq = Square::from_index(sq_idx);
                     for blockers in get_all_blockers_subsets(entry.blocker_mask) {
                         let expected = ROOK_SLIDER.get_moves(sq, blockers);
                         let idx = entry.get_magic_index(blockers);
@@ -294,7 +292,7 @@ mod tests {
             .spawn(|| {
                 let (entries, table) = get_slider_magics(&BISHOP_SLIDER, RANDOM_SEED, true);
                 for (sq_idx, entry) in entries.iter().enumerate() {
-                    let sq = SquareCoord::try_from(sq_idx as u8).unwrap();
+                    let sq = Square::from_index(sq_idx);
                     for blockers in get_all_blockers_subsets(entry.blocker_mask) {
                         let expected = BISHOP_SLIDER.get_moves(sq, blockers);
                         let idx = entry.get_magic_index(blockers);
@@ -3,7 +3,7 @@ mod magic;
 
 use flying_goose::{
     movement::sliders::defs::{BISHOP_SLIDER, ROOK_SLIDER, Slider},
-    types::{BitBoard, EMPTY_BITBOARD, print_bb},
+    types::{Bitboard, EMPTY_BITBOARD},
 };
 use rand::{Rng, SeedableRng};
 use rand_pcg::Pcg64;
@@ -32,7 +32,7 @@ fn generate_magics(slider: &Slider, slider_name: &str) -> () {
     let mut master_rng = Pcg64::seed_from_u64(RANDOM_SEED);
     let thread_seeds: Vec<u64> = (0..num_threads).map(|_| master_rng.random()).collect();
 
-    let best: Option<(Vec<MagicEntry>, Vec<BitBoard>)> = std::thread::scope(|scope| {
+    let best: Option<(Vec<MagicEntry>, Vec<Bitboard>)> = std::thread::scope(|scope| {
         let handles: Vec<_> = thread_seeds
             .into_iter()
             .enumerate()
@@ -44,7 +44,7 @@ fn generate_magics(slider: &Slider, slider_name: &str) -> () {
                         let thread_start = std::time::Instant::now();
                         let (magic_entries, slider_global_table) =
                             get_slider_magics(slider, seed, verbose);
-                        let size_kb = (slider_global_table.len() * std::mem::size_of::<BitBoard>())
+                        let size_kb = (slider_global_table.len() * std::mem::size_of::<Bitboard>())
                             as f64
                             / 1024.0;
                         println!(
@@ -58,7 +58,7 @@ fn generate_magics(slider: &Slider, slider_name: &str) -> () {
             })
             .collect();
 
-        let mut best: Option<(Vec<MagicEntry>, Vec<BitBoard>)> = None;
+        let mut best: Option<(Vec<MagicEntry>, Vec<Bitboard>)> = None;
         for handle in handles {
             let candidate = handle.join().expect("worker thread panicked");
             let is_better = match &best {
@@ -73,7 +73,7 @@ fn generate_magics(slider: &Slider, slider_name: &str) -> () {
     });
 
     let (best_entries, best_table) = best.expect("num_threads is > 0, so best is always set");
-    let size_kb = (best_table.len() * std::mem::size_of::<BitBoard>()) as f64 / 1024.0;
+    let size_kb = (best_table.len() * std::mem::size_of::<Bitboard>()) as f64 / 1024.0;
     println!(
         "Best {slider_name} global table: {:.2} Kb out of {num_threads} threads (total time {:.2?})",
         size_kb,
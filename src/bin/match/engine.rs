@@ -0,0 +1,163 @@
+use std::{
+    fmt::Display,
+    io::{BufRead, BufReader, Write},
+    process::{Child, ChildStdin, Command, Stdio},
+    sync::mpsc::{self, Receiver, RecvTimeoutError},
+    thread,
+    time::{Duration, Instant},
+};
+
+// How long an engine gets to answer uci, isready and stop
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(10);
+// How long an engine gets to exit after quit before it is killed
+const QUIT_TIMEOUT: Duration = Duration::from_secs(1);
+
+#[derive(Debug)]
+pub enum EngineError {
+    Spawn(std::io::Error),
+    Io(std::io::Error),
+    Timeout(&'static str),
+    Exited,
+}
+
+impl Display for EngineError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            EngineError::Spawn(err) => write!(f, "could not start the engine: {err}"),
+            EngineError::Io(err) => write!(f, "could not talk to the engine: {err}"),
+            EngineError::Timeout(waiting_for) => write!(f, "timed out waiting for {waiting_for}"),
+            EngineError::Exited => write!(f, "the engine exited"),
+        }
+    }
+}
+
+// A UCI engine running as a child process. Its output is read on a separate thread so
+// every wait can have a deadline.
+pub struct Engine {
+    pub name: String,
+    child: Child,
+    stdin: ChildStdin,
+    lines: Receiver<String>,
+}
+
+impl Engine {
+    pub fn start(command: &str, options: &[(String, String)]) -> Result<Self, EngineError> {
+        let mut child = Command::new(command)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()
+            .map_err(EngineError::Spawn)?;
+        let stdin = child.stdin.take().expect("stdin is piped");
+        let stdout = child.stdout.take().expect("stdout is piped");
+        let (sender, lines) = mpsc::channel();
+        thread::spawn(move || {
+            for line in BufReader::new(stdout).lines() {
+                let Ok(line) = line else { break };
+                if sender.send(line).is_err() {
+                    break;
+                }
+            }
+        });
+
+        let mut engine = Self {
+            name: command.to_string(),
+            child,
+            stdin,
+            lines,
+        };
+        engine.send("uci")?;
+        let deadline = Instant::now() + HANDSHAKE_TIMEOUT;
+        loop {
+            let line = engine.read_line(deadline, "uciok")?;
+            if let Some(name) = line.strip_prefix("id name ") {
+                engine.name = name.trim().to_string();
+            } else if line.trim() == "uciok" {
+                break;
+            }
+        }
+        for (name, value) in options {
+            engine.send(&format!("setoption name {name} value {value}"))?;
+        }
+        engine.sync()?;
+        Ok(engine)
+    }
+
+    fn send(&mut self, command: &str) -> Result<(), EngineError> {
+        writeln!(self.stdin, "{command}")
+            .and_then(|_| self.stdin.flush())
+            .map_err(EngineError::Io)
+    }
+
+    fn read_line(
+        &self,
+        deadline: Instant,
+        waiting_for: &'static str,
+    ) -> Result<String, EngineError> {
+        let timeout = deadline.saturating_duration_since(Instant::now());
+        self.lines.recv_timeout(timeout).map_err(|err| match err {
+            RecvTimeoutError::Timeout => EngineError::Timeout(waiting_for),
+            RecvTimeoutError::Disconnected => EngineError::Exited,
+        })
+    }
+
+    fn sync(&mut self) -> Result<(), EngineError> {
+        self.send("isready")?;
+        let deadline = Instant::now() + HANDSHAKE_TIMEOUT;
+        while self.read_line(deadline, "readyok")?.trim() != "readyok" {}
+        Ok(())
+    }
+
+    pub fn new_game(&mut self) -> Result<(), EngineError> {
+        self.send("ucinewgame")?;
+        self.sync()
+    }
+
+    // Sends the position and the go command, then waits up to `limit` for the best move
+    pub fn best_move(
+        &mut self,
+        position: &str,
+        go: &str,
+        limit: Duration,
+    ) -> Result<String, EngineError> {
+        self.send(position)?;
+        self.send(go)?;
+        let deadline = Instant::now() + limit;
+        loop {
+            let line = self.read_line(deadline, "bestmove")?;
+            if let Some(rest) = line.strip_prefix("bestmove") {
+                return Ok(rest
+                    .split_whitespace()
+                    .next()
+                    .unwrap_or_default()
+                    .to_string());
+            }
+        }
+    }
+
+    // Ends a search that ran out of time, collecting the best move it still owes
+    pub fn stop(&mut self) -> Result<(), EngineError> {
+        self.send("stop")?;
+        let deadline = Instant::now() + HANDSHAKE_TIMEOUT;
+        while !self
+            .read_line(deadline, "bestmove")?
+            .starts_with("bestmove")
+        {}
+        Ok(())
+    }
+}
+
+impl Drop for Engine {
+    fn drop(&mut self) {
+        let _ = self.send("quit");
+        let deadline = Instant::now() + QUIT_TIMEOUT;
+        while Instant::now() < deadline {
+            if let Ok(Some(_)) = self.child.try_wait() {
+                return;
+            }
+            thread::sleep(Duration::from_millis(10));
+        }
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
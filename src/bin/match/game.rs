@@ -0,0 +1,367 @@
+use std::{
+    fmt::Display,
+    str::FromStr,
+    time::{Duration, Instant},
+};
+
+use flying_goose::{
+    board::{
+        Board,
+        rules::GameEnd,
+        types::{MAX_GAME_MOVES, Side},
+    },
+    pgn::{Game, GameResult},
+};
+
+use crate::{
+    engine::{Engine, EngineError},
+    openings::Opening,
+};
+
+// The board history cannot hold more plies than this, longer games are called drawn
+const MAX_GAME_PLIES: usize = MAX_GAME_MOVES as usize - 1;
+
+// Base time and increment per move, written in seconds as 10+0.1
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TimeControl {
+    pub base: Duration,
+    pub increment: Duration,
+}
+
+impl FromStr for TimeControl {
+    type Err = String;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let (base, increment) = text.split_once('+').unwrap_or((text, "0"));
+        let seconds = |value: &str| {
+            value
+                .parse::<f64>()
+                .ok()
+                .filter(|seconds| seconds.is_finite() && *seconds >= 0.0)
+                .map(Duration::from_secs_f64)
+                .ok_or_else(|| format!("invalid time control {text:?}, expected base+increment"))
+        };
+        Ok(Self {
+            base: seconds(base)?,
+            increment: seconds(increment)?,
+        })
+    }
+}
+
+impl Display for TimeControl {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}+{}",
+            self.base.as_secs_f64(),
+            self.increment.as_secs_f64()
+        )
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Termination {
+    Rules(GameEnd),
+    TimeForfeit,
+    IllegalMove(String),
+    EngineFailure(String),
+    TooLong,
+}
+
+impl Termination {
+    // The value of the PGN Termination tag
+    fn tag(&self) -> &'static str {
+        match self {
+            Termination::Rules(_) => "normal",
+            Termination::TimeForfeit => "time forfeit",
+            Termination::IllegalMove(_) => "rules infraction",
+            Termination::EngineFailure(_) => "abandoned",
+            Termination::TooLong => "adjudication",
+        }
+    }
+}
+
+impl Display for Termination {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Termination::Rules(GameEnd::Checkmate) => write!(f, "checkmate"),
+            Termination::Rules(GameEnd::Stalemate) => write!(f, "stalemate"),
+            Termination::Rules(GameEnd::FiftyMoves) => write!(f, "fifty-move rule"),
+            Termination::Rules(GameEnd::Repetition) => write!(f, "threefold repetition"),
+            Termination::Rules(GameEnd::InsufficientMaterial) => {
+                write!(f, "insufficient material")
+            }
+            Termination::TimeForfeit => write!(f, "loses on time"),
+            Termination::IllegalMove(chess_move) => write!(f, "illegal move {chess_move:?}"),
+            Termination::EngineFailure(err) => write!(f, "engine failure: {err}"),
+            Termination::TooLong => write!(f, "game too long"),
+        }
+    }
+}
+
+pub struct GameRecord {
+    pub white: String,
+    pub black: String,
+    pub opening: Opening,
+    pub moves: Vec<String>,
+    pub result: GameResult,
+    pub termination: Termination,
+}
+
+impl GameRecord {
+    pub fn to_pgn(&self, event: &str, round: usize, time_control: TimeControl) -> String {
+        let mut tags = vec![
+            ("Event", event.to_string()),
+            ("Site", "?".to_string()),
+            ("Date", "????.??.??".to_string()),
+            ("Round", round.to_string()),
+            ("White", self.white.clone()),
+            ("Black", self.black.clone()),
+            ("Result", self.result.to_string()),
+        ];
+        if let Some(fen) = &self.opening.fen {
+            tags.push(("SetUp", "1".to_string()));
+            tags.push(("FEN", fen.clone()));
+        }
+        tags.push(("TimeControl", time_control.to_string()));
+        tags.push(("Termination", self.termination.tag().to_string()));
+        Game {
+            tags: tags
+                .into_iter()
+                .map(|(name, value)| (name.to_string(), value))
+                .collect(),
+            moves: self.moves.clone(),
+            result: Some(self.result),
+        }
+        .to_pgn()
+    }
+}
+
+fn win_for(side: Side) -> GameResult {
+    match side {
+        Side::White => GameResult::WhiteWin,
+        Side::Black => GameResult::BlackWin,
+    }
+}
+
+// Plays one game from the opening. Our own board decides when the game is over, and our
+// own clock decides when an engine has run out of time, `time_margin` being the grace
+// allowed on top of what is left. Fails without playing when the opening does not set up
+// or holds a move that is not legal.
+pub fn play(
+    white: &mut Engine,
+    black: &mut Engine,
+    opening: &Opening,
+    time_control: TimeControl,
+    time_margin: Duration,
+) -> Result<GameRecord, String> {
+    let mut board = Board::init();
+    board
+        .fen_setup(opening.fen.as_deref())
+        .map_err(|err| format!("opening does not set up: {err}"))?;
+    let mut uci_moves = Vec::new();
+    let mut moves = Vec::new();
+    for text in &opening.moves {
+        let chess_move = board
+            .parse_uci(text)
+            .ok_or_else(|| format!("opening move {text:?} is not legal"))?;
+        moves.push(board.to_san(chess_move));
+        uci_moves.push(text.clone());
+        board.make(chess_move);
+    }
+    let start_position = match &opening.fen {
+        Some(fen) => format!("position fen {fen}"),
+        None => "position startpos".to_string(),
+    };
+
+    let mut record = GameRecord {
+        white: white.name.clone(),
+        black: black.name.clone(),
+        opening: opening.clone(),
+        moves: Vec::new(),
+        result: GameResult::Draw,
+        termination: Termination::TooLong,
+    };
+    let mut engines = [black, white];
+    for side in [Side::White, Side::Black] {
+        if let Err(err) = engines[side as usize].new_game() {
+            record.moves = moves;
+            record.result = win_for(side.other());
+            record.termination = Termination::EngineFailure(err.to_string());
+            return Ok(record);
+        }
+    }
+
+    let mut clocks = [time_control.base; 2];
+    let (result, termination) = loop {
+        if let Some(end) = board.game_end() {
+            let result = match end {
+                GameEnd::Checkmate => win_for(board.get_opponent()),
+                _ => GameResult::Draw,
+            };
+            break (result, Termination::Rules(end));
+        }
+        if board.history.len() >= MAX_GAME_PLIES {
+            break (GameResult::Draw, Termination::TooLong);
+        }
+
+        let side = board.get_current_player();
+        let position = if uci_moves.is_empty() {
+            start_position.clone()
+        } else {
+            format!("{start_position} moves {}", uci_moves.join(" "))
+        };
+        let go = format!(
+            "go wtime {} btime {} winc {} binc {}",
+            clocks[Side::White as usize].as_millis(),
+            clocks[Side::Black as usize].as_millis(),
+            time_control.increment.as_millis(),
+            time_control.increment.as_millis()
+        );
+        let engine = &mut engines[side as usize];
+        let start = Instant::now();
+        let answer = engine.best_move(&position, &go, clocks[side as usize] + time_margin);
+        let elapsed = start.elapsed();
+
+        let text = match answer {
+            Ok(_) if elapsed > clocks[side as usize] + time_margin => {
+                break (win_for(side.other()), Termination::TimeForfeit);
+            }
+            Ok(text) => text,
+            Err(EngineError::Timeout(_)) => {
+                // The engine owes a bestmove; if it never comes the worker restarts it
+                let _ = engine.stop();
+                break (win_for(side.other()), Termination::TimeForfeit);
+            }
+            Err(err) => {
+                break (
+                    win_for(side.other()),
+                    Termination::EngineFailure(err.to_string()),
+                );
+            }
+        };
+        clocks[side as usize] =
+            clocks[side as usize].saturating_sub(elapsed) + time_control.increment;
+
+        let Some(chess_move) = board.parse_uci(&text) else {
+            break (win_for(side.other()), Termination::IllegalMove(text));
+        };
+        moves.push(board.to_san(chess_move));
+        uci_moves.push(text);
+        board.make(chess_move);
+    };
+
+    record.moves = moves;
+    record.result = result;
+    record.termination = termination;
+    Ok(record)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_time_controls() {
+        let tc: TimeControl = "10+0.1".parse().unwrap();
+        assert_eq!(tc.base, Duration::from_secs(10));
+        assert_eq!(tc.increment, Duration::from_millis(100));
+        assert_eq!(tc.to_string(), "10+0.1");
+        let sudden_death: TimeControl = "60".parse().unwrap();
+        assert_eq!(sudden_death.increment, Duration::ZERO);
+        assert!("ten+1".parse::<TimeControl>().is_err());
+        assert!("-1+0".parse::<TimeControl>().is_err());
+    }
+
+    // A UCI engine written as a shell script that answers each go with the next of `moves`
+    // and exits once they run out
+    #[cfg(unix)]
+    fn scripted_engine(name: &str, moves: &[&str]) -> Engine {
+        use std::os::unix::fs::PermissionsExt;
+
+        let path = std::env::temp_dir().join(format!("match-{}-{name}.sh", std::process::id()));
+        let script = format!(
+            "#!/bin/sh\n\
+             set -- {}\n\
+             while read -r line; do\n\
+             case \"$line\" in\n\
+             uci) echo 'id name {name}'; echo uciok ;;\n\
+             isready) echo readyok ;;\n\
+             go*) [ $# -eq 0 ] && exit 1; echo \"bestmove $1\"; shift ;;\n\
+             quit) exit 0 ;;\n\
+             esac\n\
+             done\n",
+            moves.join(" ")
+        );
+        std::fs::write(&path, script).unwrap();
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755)).unwrap();
+        let engine = Engine::start(path.to_str().unwrap(), &[]).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        engine
+    }
+
+    #[cfg(unix)]
+    fn play_scripted(
+        opening: &[&str],
+        white: &[&str],
+        black: &[&str],
+    ) -> Result<GameRecord, String> {
+        let mut white = scripted_engine("white", white);
+        let mut black = scripted_engine("black", black);
+        let opening = Opening {
+            fen: None,
+            moves: opening.iter().map(|text| text.to_string()).collect(),
+        };
+        let time_control = TimeControl {
+            base: Duration::from_secs(10),
+            increment: Duration::ZERO,
+        };
+        play(
+            &mut white,
+            &mut black,
+            &opening,
+            time_control,
+            Duration::from_secs(1),
+        )
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn plays_and_adjudicates_scripted_games() {
+        // Fool's mate after the f3 opening: the board, not the engines, ends the game
+        let record = play_scripted(&["f2f3"], &["g2g4"], &["e7e5", "d8h4"]).unwrap();
+        assert_eq!(
+            (record.white.as_str(), record.black.as_str()),
+            ("white", "black")
+        );
+        assert_eq!(record.moves, ["f3", "e5", "g4", "Qh4#"]);
+        assert_eq!(record.result, GameResult::BlackWin);
+        assert_eq!(record.termination, Termination::Rules(GameEnd::Checkmate));
+        let pgn = record.to_pgn("Test", 1, "10+0".parse().unwrap());
+        assert!(pgn.contains("[Result \"0-1\"]"));
+        assert!(pgn.contains("[Termination \"normal\"]"));
+        assert!(pgn.contains("1. f3 e5 2. g4 Qh4# 0-1"));
+
+        // An illegal move loses on the spot
+        let record = play_scripted(&["f2f3"], &["e2e5"], &["e7e5"]).unwrap();
+        assert_eq!(record.result, GameResult::BlackWin);
+        assert_eq!(
+            record.termination,
+            Termination::IllegalMove("e2e5".to_string())
+        );
+        assert!(
+            record
+                .to_pgn("Test", 1, "10+0".parse().unwrap())
+                .contains("rules infraction")
+        );
+
+        // So does an engine that exits mid-game, here Black after its only move
+        let record = play_scripted(&["f2f3"], &["g2g3", "g1f3"], &["e7e5"]).unwrap();
+        assert_eq!(record.result, GameResult::WhiteWin);
+        assert!(matches!(record.termination, Termination::EngineFailure(_)));
+        assert_eq!(record.moves, ["f3", "e5", "g3"]);
+
+        // An opening that is not legal is an error rather than a game
+        assert!(play_scripted(&["e1g1"], &["e2e4"], &["e7e5"]).is_err());
+    }
+}
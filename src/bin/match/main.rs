@@ -0,0 +1,326 @@
+mod engine;
+mod game;
+mod openings;
+mod sprt;
+
+use std::{
+    fs::File,
+    io::Write,
+    path::Path,
+    process::ExitCode,
+    sync::{
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        mpsc,
+    },
+    thread,
+    time::Duration,
+};
+
+use flying_goose::pgn::GameResult;
+
+use engine::Engine;
+use game::{GameRecord, Termination, TimeControl};
+use openings::Opening;
+use sprt::{Sprt, Tally};
+
+const DEFAULT_TIME_CONTROL: &str = "10+0.1";
+
+// Usage: match <engine1> <engine2> --openings <file.epd|file.pgn> [--games n]
+//              [--concurrency n] [--tc base+inc] [--timemargin ms] [--pgnout file]
+//              [--sprt elo0,elo1,alpha,beta] [--option name=value]... [--event name]
+//
+// Every opening is played twice, once with each engine as White. Results are from the
+// first engine's point of view, so to test a patch pass the patched build first. Without
+// --games each opening is used once; with it the openings repeat as needed.
+struct Config {
+    engines: [String; 2],
+    openings_path: String,
+    games: Option<usize>,
+    concurrency: usize,
+    time_control: TimeControl,
+    time_margin: Duration,
+    pgn_path: Option<String>,
+    sprt: Option<Sprt>,
+    options: Vec<(String, String)>,
+    event: String,
+}
+
+fn parse_args(args: &[String]) -> Result<Config, String> {
+    let mut positional = Vec::new();
+    let mut openings_path = None;
+    let mut games = None;
+    let mut concurrency = 1;
+    let mut time_control = DEFAULT_TIME_CONTROL.parse()?;
+    let mut time_margin = Duration::ZERO;
+    let mut pgn_path = None;
+    let mut sprt = None;
+    let mut options = Vec::new();
+    let mut event = String::from("Engine match");
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let mut value = |name: &str| {
+            args.next()
+                .cloned()
+                .ok_or_else(|| format!("{name} needs a value"))
+        };
+        let number = |name: &str, text: String| {
+            text.parse::<usize>()
+                .map_err(|err| format!("invalid value for {name}: {err}"))
+        };
+        match arg.as_str() {
+            "--openings" => openings_path = Some(value("--openings")?),
+            "--games" => games = Some(number("--games", value("--games")?)?),
+            "--concurrency" => {
+                concurrency = number("--concurrency", value("--concurrency")?)?.max(1)
+            }
+            "--tc" => time_control = value("--tc")?.parse()?,
+            "--timemargin" => {
+                time_margin =
+                    Duration::from_millis(number("--timemargin", value("--timemargin")?)? as u64)
+            }
+            "--pgnout" => pgn_path = Some(value("--pgnout")?),
+            "--sprt" => sprt = Some(value("--sprt")?.parse()?),
+            "--option" => {
+                let option = value("--option")?;
+                let (name, value) = option
+                    .split_once('=')
+                    .ok_or_else(|| format!("expected name=value, got {option:?}"))?;
+                options.push((name.to_string(), value.to_string()));
+            }
+            "--event" => event = value("--event")?,
+            _ => positional.push(arg.clone()),
+        }
+    }
+    let [first, second] = positional.as_slice() else {
+        return Err("expected two engine executables".into());
+    };
+    Ok(Config {
+        engines: [first.clone(), second.clone()],
+        openings_path: openings_path.ok_or("--openings is required")?,
+        games,
+        concurrency,
+        time_control,
+        time_margin,
+        pgn_path,
+        sprt,
+        options,
+        event,
+    })
+}
+
+// Game `index` of the schedule: its opening, and whether the first engine has White
+fn pairing(index: usize, openings: &[Opening]) -> (&Opening, bool) {
+    (
+        &openings[(index / 2) % openings.len()],
+        index.is_multiple_of(2),
+    )
+}
+
+enum Report {
+    Finished(usize, GameRecord),
+    Failed(String),
+}
+
+struct Worker<'a> {
+    config: &'a Config,
+    openings: &'a [Opening],
+    total: usize,
+    next_game: &'a AtomicUsize,
+    stop: &'a AtomicBool,
+}
+
+impl Worker<'_> {
+    fn run(&self, reports: mpsc::Sender<Report>) {
+        let start = |command: &String| Engine::start(command, &self.config.options);
+        let mut engines = match (
+            start(&self.config.engines[0]),
+            start(&self.config.engines[1]),
+        ) {
+            (Ok(first), Ok(second)) => [first, second],
+            (Err(err), _) | (_, Err(err)) => {
+                let _ = reports.send(Report::Failed(err.to_string()));
+                return;
+            }
+        };
+
+        while !self.stop.load(Ordering::Relaxed) {
+            let index = self.next_game.fetch_add(1, Ordering::Relaxed);
+            if index >= self.total {
+                break;
+            }
+            let (opening, first_is_white) = pairing(index, self.openings);
+            let [first, second] = &mut engines;
+            let (white, black) = if first_is_white {
+                (first, second)
+            } else {
+                (second, first)
+            };
+            let record = match game::play(
+                white,
+                black,
+                opening,
+                self.config.time_control,
+                self.config.time_margin,
+            ) {
+                Ok(record) => record,
+                Err(err) => {
+                    let _ = reports.send(Report::Failed(err));
+                    return;
+                }
+            };
+
+            // An engine that lost on time or stopped answering may be stuck mid-search
+            if matches!(
+                record.termination,
+                Termination::TimeForfeit | Termination::EngineFailure(_)
+            ) {
+                let loser = match (record.result, first_is_white) {
+                    (GameResult::WhiteWin, true) | (GameResult::BlackWin, false) => 1,
+                    _ => 0,
+                };
+                match start(&self.config.engines[loser]) {
+                    Ok(engine) => engines[loser] = engine,
+                    Err(err) => {
+                        let _ = reports.send(Report::Failed(err.to_string()));
+                        return;
+                    }
+                }
+            }
+            if reports.send(Report::Finished(index, record)).is_err() {
+                return;
+            }
+        }
+    }
+}
+
+fn print_standing(tally: &Tally, names: &[String; 2], sprt: Option<&Sprt>) {
+    println!(
+        "Score of {} vs {}: {} - {} - {} [{:.3}] {}",
+        names[0],
+        names[1],
+        tally.wins,
+        tally.losses,
+        tally.draws,
+        tally.score(),
+        tally.games()
+    );
+    match tally.elo() {
+        Some((elo, margin)) => println!("Elo difference: {elo:.1} +/- {margin:.1}"),
+        None => println!("Elo difference: not enough games yet"),
+    }
+    if let Some(sprt) = sprt {
+        let (lower, upper) = sprt.bounds();
+        println!(
+            "SPRT ({:.1}, {:.1}): LLR {:.2} ({lower:.2}, {upper:.2})",
+            sprt.elo0,
+            sprt.elo1,
+            sprt.llr(tally)
+        );
+    }
+}
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let config = match parse_args(&args) {
+        Ok(config) => config,
+        Err(err) => {
+            eprintln!("{err}");
+            eprintln!(
+                "usage: match <engine1> <engine2> --openings <file.epd|file.pgn> [--games n] \
+                 [--concurrency n] [--tc base+inc] [--timemargin ms] [--pgnout file] \
+                 [--sprt elo0,elo1,alpha,beta] [--option name=value]... [--event name]"
+            );
+            return ExitCode::FAILURE;
+        }
+    };
+    let openings = match openings::load(Path::new(&config.openings_path)) {
+        Ok(openings) => openings,
+        Err(err) => {
+            eprintln!("{err}");
+            return ExitCode::FAILURE;
+        }
+    };
+    let mut pgn_file = match config.pgn_path.as_ref().map(File::create).transpose() {
+        Ok(file) => file,
+        Err(err) => {
+            eprintln!("could not create the PGN file: {err}");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let total = config.games.unwrap_or(2 * openings.len());
+    let next_game = AtomicUsize::new(0);
+    let stop = AtomicBool::new(false);
+    let mut tally = Tally::default();
+    let mut names = config.engines.clone();
+    let mut failed = false;
+
+    thread::scope(|scope| {
+        let (sender, reports) = mpsc::channel();
+        for _ in 0..config.concurrency.min(total) {
+            let worker = Worker {
+                config: &config,
+                openings: &openings,
+                total,
+                next_game: &next_game,
+                stop: &stop,
+            };
+            let sender = sender.clone();
+            scope.spawn(move || worker.run(sender));
+        }
+        drop(sender);
+
+        for report in reports {
+            let (index, record) = match report {
+                Report::Finished(index, record) => (index, record),
+                Report::Failed(err) => {
+                    eprintln!("stopping the match: {err}");
+                    stop.store(true, Ordering::Relaxed);
+                    failed = true;
+                    continue;
+                }
+            };
+            let first_is_white = pairing(index, &openings).1;
+            names = if first_is_white {
+                [record.white.clone(), record.black.clone()]
+            } else {
+                [record.black.clone(), record.white.clone()]
+            };
+            match (record.result, first_is_white) {
+                (GameResult::Draw, _) => tally.draws += 1,
+                (GameResult::WhiteWin, true) | (GameResult::BlackWin, false) => tally.wins += 1,
+                _ => tally.losses += 1,
+            }
+            println!(
+                "Finished game {} ({} vs {}): {} {{{}}}",
+                index + 1,
+                record.white,
+                record.black,
+                record.result,
+                record.termination
+            );
+            if let Some(file) = pgn_file.as_mut() {
+                let pgn = record.to_pgn(&config.event, index + 1, config.time_control);
+                if let Err(err) = file.write_all(pgn.as_bytes()) {
+                    eprintln!("could not write the PGN file: {err}");
+                }
+            }
+            print_standing(&tally, &names, config.sprt.as_ref());
+
+            if let Some(decision) = config.sprt.as_ref().and_then(|sprt| sprt.decision(&tally))
+                && !stop.swap(true, Ordering::Relaxed)
+            {
+                println!("SPRT: {decision}, finishing the games in progress");
+            }
+        }
+    });
+
+    println!("Finished match");
+    print_standing(&tally, &names, config.sprt.as_ref());
+    if failed {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    }
+}
@@ -0,0 +1,113 @@
+use std::path::Path;
+
+use flying_goose::{board::Board, pgn};
+
+// A start position for a pair of games: a FEN (None for the initial position) and the
+// moves played from it, in UCI notation
+#[derive(Debug, Clone, PartialEq)]
+pub struct Opening {
+    pub fen: Option<String>,
+    pub moves: Vec<String>,
+}
+
+// Reads a PGN file when the name ends in .pgn and an EPD file otherwise. Openings that do
+// not parse are reported and skipped.
+pub fn load(path: &Path) -> Result<Vec<Opening>, String> {
+    let bytes =
+        std::fs::read(path).map_err(|err| format!("could not read {}: {err}", path.display()))?;
+    let text = String::from_utf8_lossy(&bytes);
+    let openings = if path
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("pgn"))
+    {
+        from_pgn(&text)
+    } else {
+        from_epd(&text)
+    };
+    if openings.is_empty() {
+        return Err(format!("no usable openings in {}", path.display()));
+    }
+    Ok(openings)
+}
+
+// The first four fields of every line are the position, the operations after them are
+// ignored
+fn from_epd(text: &str) -> Vec<Opening> {
+    let mut board = Board::init();
+    let mut openings = Vec::new();
+    for (number, line) in text.lines().enumerate() {
+        let fields: Vec<&str> = line.split_whitespace().take(4).collect();
+        if fields.is_empty() || fields[0].starts_with('#') {
+            continue;
+        }
+        let fen = fields.join(" ");
+        match board.fen_setup(Some(&fen)) {
+            Ok(()) => openings.push(Opening {
+                fen: Some(format!("{fen} 0 1")),
+                moves: Vec::new(),
+            }),
+            Err(err) => eprintln!("skipping EPD line {}: {err}", number + 1),
+        }
+    }
+    openings
+}
+
+// Every move has to be one of the legal moves of its position, so games can replay them
+fn from_pgn(text: &str) -> Vec<Opening> {
+    let mut board = Board::init();
+    let mut openings = Vec::new();
+    'games: for (number, game) in pgn::parse_games(text).iter().enumerate() {
+        let fen = game.tag("FEN").map(str::to_string);
+        if let Err(err) = board.fen_setup(fen.as_deref()) {
+            eprintln!("skipping opening {}: {err}", number + 1);
+            continue;
+        }
+        let mut moves = Vec::new();
+        for san in &game.moves {
+            match board.parse_san(san) {
+                Ok(chess_move) => {
+                    moves.push(chess_move.to_string());
+                    board.make(chess_move);
+                }
+                Err(err) => {
+                    eprintln!("skipping opening {}: {err}", number + 1);
+                    continue 'games;
+                }
+            }
+        }
+        openings.push(Opening { fen, moves });
+    }
+    openings
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_epd_and_pgn_openings() {
+        let epd = "rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq e3 id \"e4\";\n\
+                   not a position\n\
+                   \n\
+                   8/8/8/8/8/8/8/K6k w - -\n";
+        let openings = from_epd(epd);
+        assert_eq!(openings.len(), 2);
+        assert_eq!(
+            openings[0].fen.as_deref(),
+            Some("rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq e3 0 1")
+        );
+
+        // Ke7 runs into the e7 pawn and White cannot castle yet, so only the first opening
+        // is kept
+        let pgn = "[Event \"?\"]\n\n1. e4 c5 2. Nf3 *\n\n[Event \"?\"]\n\n1. e4 Ke7 Qh5 *\n\n\
+                   [Event \"?\"]\n\n1. O-O *\n";
+        let openings = from_pgn(pgn);
+        assert_eq!(
+            openings,
+            [Opening {
+                fen: None,
+                moves: vec!["e2e4".into(), "c7c5".into(), "g1f3".into()],
+            }]
+        );
+    }
+}
@@ -0,0 +1,234 @@
+use std::{fmt::Display, str::FromStr};
+
+// 95% of a normal distribution lies within this many standard deviations
+const Z_95: f64 = 1.959_964;
+
+// Games from the point of view of the first engine
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Tally {
+    pub wins: u32,
+    pub draws: u32,
+    pub losses: u32,
+}
+
+impl Tally {
+    pub fn games(&self) -> u32 {
+        self.wins + self.draws + self.losses
+    }
+
+    pub fn score(&self) -> f64 {
+        (self.wins as f64 + self.draws as f64 / 2.0) / self.games() as f64
+    }
+
+    // Variance of a single game's score
+    fn variance(&self) -> f64 {
+        let games = self.games() as f64;
+        let score = self.score();
+        let (win, draw, loss) = (
+            self.wins as f64 / games,
+            self.draws as f64 / games,
+            self.losses as f64 / games,
+        );
+        win * (1.0 - score).powi(2) + draw * (0.5 - score).powi(2) + loss * score.powi(2)
+    }
+
+    // The Elo difference with its 95% margin, once both engines have won a game and the
+    // interval no longer reaches a score of 0 or 1
+    pub fn elo(&self) -> Option<(f64, f64)> {
+        if self.wins == 0 || self.losses == 0 {
+            return None;
+        }
+        let score = self.score();
+        let deviation = (self.variance() / self.games() as f64).sqrt();
+        let (low, high) = (score - Z_95 * deviation, score + Z_95 * deviation);
+        if low <= 0.0 || high >= 1.0 {
+            return None;
+        }
+        Some((
+            score_to_elo(score),
+            (score_to_elo(high) - score_to_elo(low)) / 2.0,
+        ))
+    }
+}
+
+pub fn score_to_elo(score: f64) -> f64 {
+    -400.0 * (1.0 / score - 1.0).log10()
+}
+
+pub fn elo_to_score(elo: f64) -> f64 {
+    1.0 / (1.0 + 10f64.powf(-elo / 400.0))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Decision {
+    // The patch is no better than elo0
+    AcceptH0,
+    // The patch gains at least elo1
+    AcceptH1,
+}
+
+// A sequential probability ratio test between H0: the Elo difference is elo0, and
+// H1: it is elo1
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Sprt {
+    pub elo0: f64,
+    pub elo1: f64,
+    pub alpha: f64,
+    pub beta: f64,
+}
+
+impl Sprt {
+    // Stop for H0 below the first and for H1 above the second
+    pub fn bounds(&self) -> (f64, f64) {
+        (
+            (self.beta / (1.0 - self.alpha)).ln(),
+            ((1.0 - self.beta) / self.alpha).ln(),
+        )
+    }
+
+    // Log-likelihood ratio of H1 against H0, with the game scores taken as normally
+    // distributed around the observed mean and variance. Until the results vary at all
+    // there is no evidence either way.
+    pub fn llr(&self, tally: &Tally) -> f64 {
+        if tally.games() == 0 {
+            return 0.0;
+        }
+        let variance = tally.variance();
+        if variance <= 0.0 {
+            return 0.0;
+        }
+        let score0 = elo_to_score(self.elo0);
+        let score1 = elo_to_score(self.elo1);
+        tally.games() as f64 * (score1 - score0) * (2.0 * tally.score() - score0 - score1)
+            / (2.0 * variance)
+    }
+
+    pub fn decision(&self, tally: &Tally) -> Option<Decision> {
+        let llr = self.llr(tally);
+        let (lower, upper) = self.bounds();
+        if llr >= upper {
+            Some(Decision::AcceptH1)
+        } else if llr <= lower {
+            Some(Decision::AcceptH0)
+        } else {
+            None
+        }
+    }
+}
+
+// elo0,elo1,alpha,beta such as 0,5,0.05,0.05
+impl FromStr for Sprt {
+    type Err = String;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let values = text
+            .split(',')
+            .map(|value| value.trim().parse::<f64>())
+            .collect::<Result<Vec<f64>, _>>()
+            .map_err(|err| format!("invalid SPRT parameters {text:?}: {err}"))?;
+        let &[elo0, elo1, alpha, beta] = values.as_slice() else {
+            return Err(format!("expected elo0,elo1,alpha,beta, got {text:?}"));
+        };
+        // 0 or 1 would put a bound at infinity, so the test could never accept that side
+        let is_probability = |p: f64| p > 0.0 && p < 1.0;
+        if elo0 >= elo1 || !is_probability(alpha) || !is_probability(beta) {
+            return Err(format!(
+                "elo0 must be below elo1 and alpha, beta within (0, 1): {text:?}"
+            ));
+        }
+        Ok(Self {
+            elo0,
+            elo1,
+            alpha,
+            beta,
+        })
+    }
+}
+
+impl Display for Decision {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Decision::AcceptH0 => write!(f, "H0 accepted"),
+            Decision::AcceptH1 => write!(f, "H1 accepted"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn close(a: f64, b: f64) -> bool {
+        (a - b).abs() < 1e-3
+    }
+
+    #[test]
+    fn elo_with_error_bars() {
+        let tally = Tally {
+            wins: 40,
+            draws: 40,
+            losses: 20,
+        };
+        let (elo, margin) = tally.elo().unwrap();
+        assert!(close(elo, 70.437), "{elo}");
+        assert!(margin > 50.0 && margin < 70.0, "{margin}");
+        assert!(close(score_to_elo(elo_to_score(12.5)), 12.5));
+        assert_eq!(
+            Tally {
+                wins: 3,
+                draws: 1,
+                losses: 0
+            }
+            .elo(),
+            None
+        );
+    }
+
+    #[test]
+    fn sprt_decides_once_the_evidence_is_clear() {
+        let sprt: Sprt = "0,5,0.05,0.05".parse().unwrap();
+        let (lower, upper) = sprt.bounds();
+        assert!(close(lower, -2.944) && close(upper, 2.944));
+
+        let even = Tally {
+            wins: 300,
+            draws: 400,
+            losses: 300,
+        };
+        assert!(sprt.llr(&even) < 0.0);
+        assert_eq!(sprt.decision(&even), None);
+        let clearly_better = Tally {
+            wins: 3000,
+            draws: 4000,
+            losses: 2500,
+        };
+        assert_eq!(sprt.decision(&clearly_better), Some(Decision::AcceptH1));
+        let clearly_worse = Tally {
+            wins: 2500,
+            draws: 4000,
+            losses: 3000,
+        };
+        assert_eq!(sprt.decision(&clearly_worse), Some(Decision::AcceptH0));
+
+        // Wins and draws without a single loss still count as evidence
+        let unbeaten = Tally {
+            wins: 300,
+            draws: 700,
+            losses: 0,
+        };
+        assert!(sprt.llr(&unbeaten) > upper);
+        let all_draws = Tally {
+            wins: 0,
+            draws: 50,
+            losses: 0,
+        };
+        assert_eq!(sprt.llr(&all_draws), 0.0);
+        assert_eq!(sprt.llr(&Tally::default()), 0.0);
+
+        assert!("5,0,0.05,0.05".parse::<Sprt>().is_err());
+        assert!("0,5,0.05".parse::<Sprt>().is_err());
+        assert!("0,5,0,0.05".parse::<Sprt>().is_err());
+        assert!("0,5,0.05,1".parse::<Sprt>().is_err());
+        assert!("0,5,0.05,NaN".parse::<Sprt>().is_err());
+    }
+}
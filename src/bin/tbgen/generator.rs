@@ -0,0 +1,362 @@
+use flying_goose::{
+    board::{
+        Board,
+        piece_movement::MoveFlag,
+        types::{Piece, Side, Square},
+    },
+    movement::ATTACKS,
+    tablebase::{
+        DRAW, Dtm, ILLEGAL, MAX_PLIES, Material, Table, Tablebases, decode_full_index, full_index,
+    },
+    types::{Bitboard, EMPTY_BITBOARD, NumOf, RANK_MASKS, SQUARE_MASKS},
+};
+
+// Marks a position with a move out of the table that does not lose, so it can never be
+// lost however its moves inside the table turn out
+const NOT_LOSING: u8 = u8::MAX;
+
+#[derive(Debug, Default)]
+pub struct Stats {
+    pub wins: usize,
+    pub losses: usize,
+    pub draws: usize,
+    pub longest_mate: u8,
+}
+
+// Retrograde analysis over every position of the material, symmetric ones included.
+//
+// A pass over all positions finds the mates and stalemates and probes the smaller tables
+// behind every capture and promotion. The rest is worked out backwards one ply at a time:
+// a position one move away from a lost position is won, and a position whose moves all
+// reach won positions is lost once the last of them is known. Whatever is still open at
+// the end is a draw.
+pub struct Generator<'a> {
+    material: &'a Material,
+    tablebases: &'a Tablebases,
+    board: Board,
+    values: Vec<u8>,
+    // Moves that stay inside the table and are not yet known to lose
+    remaining: Vec<u8>,
+    // The longest the opponent takes to mate after a capture or promotion, or NOT_LOSING
+    exit_floor: Vec<u8>,
+    // Positions to resolve at each ply, unless something shorter got there first
+    buckets: Vec<Vec<u32>>,
+}
+
+impl<'a> Generator<'a> {
+    pub fn new(material: &'a Material, tablebases: &'a Tablebases) -> Self {
+        let mut board = Board::init();
+        board.game_state.castling = 0;
+        let size = material.full_size();
+        Self {
+            material,
+            tablebases,
+            board,
+            values: vec![DRAW; size],
+            remaining: vec![0; size],
+            exit_floor: vec![0; size],
+            buckets: vec![Vec::new(); MAX_PLIES + 1],
+        }
+    }
+
+    pub fn generate(mut self) -> (Table, Stats) {
+        self.classify_all();
+        self.propagate();
+
+        let mut stats = Stats::default();
+        for &value in &self.values {
+            match Dtm::from_value(value) {
+                Some(Dtm::Win(moves)) => {
+                    stats.wins += 1;
+                    stats.longest_mate = stats.longest_mate.max(moves);
+                }
+                Some(Dtm::Loss(_)) => stats.losses += 1,
+                Some(Dtm::Draw) => stats.draws += 1,
+                None => {}
+            }
+        }
+        (Table::from_full(self.material.clone(), &self.values), stats)
+    }
+
+    fn schedule(&mut self, plies: usize, index: usize) {
+        assert!(plies <= MAX_PLIES, "mate longer than the format can store");
+        self.buckets[plies].push(index as u32);
+    }
+
+    fn classify_all(&mut self) {
+        let mut squares = vec![Square::A1; self.material.pieces().len()];
+        let mut placed: Vec<Square> = Vec::new();
+        for index in 0..self.material.full_size() {
+            let side_to_move = decode_full_index(index, &mut squares);
+            if !self.is_placeable(&squares) {
+                self.values[index] = ILLEGAL;
+                continue;
+            }
+            self.place(&placed, &squares, side_to_move);
+            placed.clone_from(&squares);
+            self.classify(index);
+        }
+    }
+
+    // Every piece on its own square and no pawns on the back ranks
+    fn is_placeable(&self, squares: &[Square]) -> bool {
+        let occupancy = squares.iter().fold(EMPTY_BITBOARD, |occupancy, &square| {
+            occupancy | SQUARE_MASKS[square]
+        });
+        let back_ranks = RANK_MASKS[0] | RANK_MASKS[NumOf::RANKS - 1];
+        occupancy.popcount() as usize == squares.len()
+            && self
+                .material
+                .pieces()
+                .iter()
+                .zip(squares)
+                .all(|(&(_, piece), &square)| piece != Piece::Pawn || !back_ranks.is_set(square))
+    }
+
+    fn place(&mut self, previous: &[Square], squares: &[Square], side_to_move: Side) {
+        for (&(side, piece), &square) in self.material.pieces().iter().zip(previous) {
+            self.board.remove_piece(piece, side, square);
+        }
+        for (&(side, piece), &square) in self.material.pieces().iter().zip(squares) {
+            self.board.put_piece(piece, side, square);
+        }
+        self.board.game_state.active_color = side_to_move;
+    }
+
+    fn classify(&mut self, index: usize) {
+        let board = &mut self.board;
+        let side_to_move = board.get_current_player();
+        let occupancy = board.bb_sides[Side::White as usize] | board.bb_sides[Side::Black as usize];
+        let their_king = board.bb_pieces[side_to_move.other() as usize][Piece::King].lsb();
+        if !board
+            .attackers_to(their_king, side_to_move, occupancy)
+            .is_empty()
+        {
+            self.values[index] = ILLEGAL;
+            return;
+        }
+
+        let moves = board.legal_moves();
+        if moves.is_empty() {
+            if board.in_check() {
+                self.schedule(0, index);
+            } else {
+                self.exit_floor[index] = NOT_LOSING;
+            }
+            return;
+        }
+
+        let mut remaining = 0;
+        let mut exit_floor = 0;
+        let mut fastest_win = None;
+        for &chess_move in moves.iter() {
+            let flags = chess_move.flags().unwrap();
+            if matches!(flags, MoveFlag::Quiet | MoveFlag::DoublePawnPush) {
+                remaining += 1;
+                continue;
+            }
+            board.make(chess_move);
+            let dtm = self
+                .tablebases
+                .probe(board)
+                .expect("the tables captures and promotions lead to are generated first");
+            board.unmake(chess_move);
+            match dtm {
+                Dtm::Loss(moves) => {
+                    let plies = 2 * moves as usize + 1;
+                    fastest_win =
+                        Some(fastest_win.map_or(plies, |fastest: usize| fastest.min(plies)));
+                }
+                Dtm::Win(_) => exit_floor = exit_floor.max(dtm.plies().unwrap() as u8),
+                Dtm::Draw => exit_floor = NOT_LOSING,
+            }
+        }
+
+        self.remaining[index] = remaining;
+        if let Some(plies) = fastest_win {
+            self.exit_floor[index] = NOT_LOSING;
+            self.schedule(plies, index);
+        } else {
+            self.exit_floor[index] = exit_floor;
+            if remaining == 0 && exit_floor != NOT_LOSING {
+                self.schedule(exit_floor as usize + 1, index);
+            }
+        }
+    }
+
+    fn propagate(&mut self) {
+        let mut squares = vec![Square::A1; self.material.pieces().len()];
+        let mut predecessors = Vec::new();
+        let mut next_frontier = Vec::new();
+        for plies in 0..=MAX_PLIES {
+            let value = plies as u8 + 1;
+            let mut frontier = std::mem::take(&mut next_frontier);
+            for index in std::mem::take(&mut self.buckets[plies]) {
+                let index = index as usize;
+                if self.values[index] == DRAW {
+                    self.values[index] = value;
+                    frontier.push(index);
+                }
+            }
+
+            for &index in &frontier {
+                let side_to_move = decode_full_index(index, &mut squares);
+                predecessors.clear();
+                self.unmoves(side_to_move, &mut squares, &mut predecessors);
+                for &previous in &predecessors {
+                    if self.values[previous] != DRAW {
+                        continue;
+                    }
+                    if plies % 2 == 0 {
+                        // Moving into a lost position wins
+                        self.values[previous] = value + 1;
+                        next_frontier.push(previous);
+                    } else {
+                        self.remaining[previous] -= 1;
+                        let exit_floor = self.exit_floor[previous];
+                        if self.remaining[previous] == 0 && exit_floor != NOT_LOSING {
+                            self.schedule(plies.max(exit_floor as usize) + 1, previous);
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    // The positions one quiet move before this one, found by moving each piece of the side
+    // that just moved backwards. Pawns step back, captures and promotions never lead here.
+    fn unmoves(&self, side_to_move: Side, squares: &mut [Square], predecessors: &mut Vec<usize>) {
+        let mover = side_to_move.other();
+        let occupancy = squares.iter().fold(EMPTY_BITBOARD, |occupancy, &square| {
+            occupancy | SQUARE_MASKS[square]
+        });
+        for slot in 0..squares.len() {
+            let (side, piece) = self.material.pieces()[slot];
+            if side != mover {
+                continue;
+            }
+            let from = squares[slot];
+            let targets = match piece {
+                Piece::Pawn => pawn_unmoves(from, mover, occupancy),
+                Piece::King => ATTACKS.king_attacks[from],
+                Piece::Queen => ATTACKS.get_queen_attacks(from, occupancy),
+                Piece::Rook => ATTACKS.get_rook_attacks(from, occupancy),
+                Piece::Bishop => ATTACKS.get_bishop_attacks(from, occupancy),
+                _ => ATTACKS.knight_attacks[from],
+            } & !occupancy;
+            for target in targets {
+                squares[slot] = target;
+                let previous = full_index(mover, squares);
+                if self.values[previous] != ILLEGAL {
+                    predecessors.push(previous);
+                }
+            }
+            squares[slot] = from;
+        }
+    }
+}
+
+// Squares a pawn on `square` can have pushed from, single or double
+fn pawn_unmoves(square: Square, side: Side, occupancy: Bitboard) -> Bitboard {
+    let rank = square.rank();
+    let (back, double_push_rank, lowest_rank) = match side {
+        Side::White => (-(NumOf::FILES as isize), 3, 2),
+        Side::Black => (NumOf::FILES as isize, 4, 5),
+    };
+    let reachable = match side {
+        Side::White => rank >= lowest_rank,
+        Side::Black => rank <= lowest_rank,
+    };
+    if !reachable || occupancy.is_set(square.step(back)) {
+        return EMPTY_BITBOARD;
+    }
+    let one_back = square.step(back);
+    let mut targets = SQUARE_MASKS[one_back];
+    if rank == double_push_rank {
+        targets |= SQUARE_MASKS[one_back.step(back)];
+    }
+    targets
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flying_goose::eval::kpk;
+
+    fn generate_with_successors(name: &str, tablebases: &mut Tablebases) -> Stats {
+        let material = Material::parse(name).unwrap();
+        for successor in material.successors() {
+            if !tablebases.contains(&successor) {
+                generate_with_successors(successor.name(), tablebases);
+            }
+        }
+        let (table, stats) = Generator::new(&material, tablebases).generate();
+        tablebases.insert(table);
+        stats
+    }
+
+    #[test]
+    fn longest_mates_match_the_known_values() {
+        let mut tablebases = Tablebases::new();
+        assert_eq!(
+            generate_with_successors("KQK", &mut tablebases).longest_mate,
+            10
+        );
+        assert_eq!(
+            generate_with_successors("KRK", &mut tablebases).longest_mate,
+            16
+        );
+
+        let probe = |fen: &str| tablebases.probe(&Board::from_fen(fen).unwrap());
+        // Black takes the undefended queen
+        assert_eq!(probe("7K/8/8/8/8/8/1Q6/k7 b - - 0 1"), Some(Dtm::Draw));
+        assert_eq!(probe("k7/8/1K6/8/8/8/8/7R w - - 0 1"), Some(Dtm::Win(1)));
+        // The same mate with the colours swapped and Black to move
+        assert_eq!(probe("7r/8/8/8/8/1k6/8/K7 b - - 0 1"), Some(Dtm::Win(1)));
+    }
+
+    #[test]
+    fn kpk_agrees_with_the_bitbase() {
+        let mut tablebases = Tablebases::new();
+        generate_with_successors("KPK", &mut tablebases);
+
+        let mut board = Board::init();
+        board.game_state.castling = 0;
+        let mut checked = 0;
+        for &pawn in &Square::ALL[NumOf::FILES..NumOf::SQUARES - NumOf::FILES] {
+            if pawn.file() >= NumOf::FILES / 2 {
+                continue;
+            }
+            for white_king in Square::ALL {
+                for black_king in Square::ALL {
+                    if white_king == black_king || pawn == white_king || pawn == black_king {
+                        continue;
+                    }
+                    board.put_piece(Piece::King, Side::White, white_king);
+                    board.put_piece(Piece::King, Side::Black, black_king);
+                    board.put_piece(Piece::Pawn, Side::White, pawn);
+                    for side in [Side::White, Side::Black] {
+                        board.game_state.active_color = side;
+                        let Some(dtm) = tablebases.probe(&board) else {
+                            continue;
+                        };
+                        let white_wins = matches!(
+                            (dtm, side),
+                            (Dtm::Win(_), Side::White) | (Dtm::Loss(_), Side::Black)
+                        );
+                        assert_eq!(
+                            white_wins,
+                            kpk::probe(side == Side::White, white_king, pawn, black_king),
+                            "K{white_king} P{pawn} k{black_king}, {side:?} to move: {dtm:?}"
+                        );
+                        checked += 1;
+                    }
+                    board.remove_piece(Piece::King, Side::White, white_king);
+                    board.remove_piece(Piece::King, Side::Black, black_king);
+                    board.remove_piece(Piece::Pawn, Side::White, pawn);
+                }
+            }
+        }
+        assert!(checked > 100_000);
+    }
+}
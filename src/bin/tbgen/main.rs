@@ -0,0 +1,88 @@
+mod generator;
+
+use std::{path::Path, process::ExitCode, time::Instant};
+
+use flying_goose::tablebase::{FILE_EXTENSION, Material, TablebaseError, Tablebases};
+
+use generator::Generator;
+
+// Usage: tbgen <output dir> <material>...
+//
+// Generates distance-to-mate tables such as KQK, KRKN or KBNK, along with every smaller
+// table a capture or promotion leads to. Tables already in the directory are loaded and
+// reused instead of generated again.
+struct TableWriter<'a> {
+    dir: &'a Path,
+    tablebases: Tablebases,
+}
+
+impl TableWriter<'_> {
+    fn ensure(&mut self, material: &Material) -> Result<(), TablebaseError> {
+        if self.tablebases.contains(material) {
+            return Ok(());
+        }
+        for successor in material.successors() {
+            self.ensure(&successor)?;
+        }
+
+        let start = Instant::now();
+        let (table, stats) = Generator::new(material, &self.tablebases).generate();
+        let path = self
+            .dir
+            .join(format!("{}.{FILE_EXTENSION}", material.name()));
+        std::fs::write(&path, table.to_bytes()).map_err(TablebaseError::Io)?;
+        eprintln!(
+            "{}: {} won, {} lost, {} drawn, longest mate {} moves, in {:.2?}",
+            material.name(),
+            stats.wins,
+            stats.losses,
+            stats.draws,
+            stats.longest_mate,
+            start.elapsed()
+        );
+        self.tablebases.insert(table);
+        Ok(())
+    }
+}
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let Some((dir, names)) = args.split_first().filter(|(_, names)| !names.is_empty()) else {
+        eprintln!("usage: tbgen <output dir> <material>...");
+        return ExitCode::FAILURE;
+    };
+    let materials = match names
+        .iter()
+        .map(|name| Material::parse(name))
+        .collect::<Result<Vec<_>, _>>()
+    {
+        Ok(materials) => materials,
+        Err(err) => {
+            eprintln!("{err}");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let dir = Path::new(dir);
+    if let Err(err) = std::fs::create_dir_all(dir) {
+        eprintln!("could not create {}: {err}", dir.display());
+        return ExitCode::FAILURE;
+    }
+    let mut tablebases = Tablebases::new();
+    match tablebases.load_dir(dir) {
+        Ok(0) => {}
+        Ok(loaded) => eprintln!("Reusing {loaded} tables from {}", dir.display()),
+        Err(err) => {
+            eprintln!("{}: {err}", dir.display());
+            return ExitCode::FAILURE;
+        }
+    }
+    let mut writer = TableWriter { dir, tablebases };
+    for material in &materials {
+        if let Err(err) = writer.ensure(material) {
+            eprintln!("{}: {err}", material.name());
+            return ExitCode::FAILURE;
+        }
+    }
+    ExitCode::SUCCESS
+}
@@ -0,0 +1,153 @@
+use std::{fs, io, thread};
+
+use flying_goose::{
+    board::Board,
+    eval::{Evaluator, endgame::SCALE_NORMAL, score::MAX_PHASE},
+};
+
+// A labelled position reduced to what the evaluation needs: the non-zero parameter
+// counts and the weights the taper gives to the mg and eg halves
+pub struct Position {
+    pub coefficients: Vec<(u16, i16)>,
+    pub mg_weight: f64,
+    pub eg_weight: f64,
+    // 1 for a White win, 0.5 for a draw, 0 for a Black win
+    pub result: f64,
+}
+
+// Splits an EPD line into its position and its operations. The position is the first four
+// fields, plus the move counters when a full FEN was written instead.
+fn split_epd(line: &str) -> Option<(&str, &str)> {
+    let mut end = 0;
+    // The end of the field after `from`, and the field
+    let next_field = |from: usize| -> Option<(usize, &str)> {
+        let start = from + line[from..].find(|c: char| !c.is_whitespace())?;
+        let len = line[start..]
+            .find(char::is_whitespace)
+            .unwrap_or(line.len() - start);
+        Some((start + len, &line[start..start + len]))
+    };
+    for _ in 0..4 {
+        end = next_field(end)?.0;
+    }
+    if let Some((halfmove_end, halfmove)) = next_field(end)
+        && let Some((fullmove_end, fullmove)) = next_field(halfmove_end)
+        && halfmove.parse::<u32>().is_ok()
+        && fullmove.parse::<u32>().is_ok()
+    {
+        end = fullmove_end;
+    }
+    Some((&line[..end], &line[end..]))
+}
+
+// Accepts `fen | score | result` as written by datagen, `fen [result]`, and EPD lines
+// with a `c9 "result";` opcode among their operations. Results are either
+// `1-0`/`1/2-1/2`/`0-1` or numbers.
+pub fn parse_line(line: &str) -> Option<(&str, f64)> {
+    let line = line.trim();
+    let c9 = split_epd(line).and_then(|(fen, operations)| {
+        operations
+            .split(';')
+            .filter_map(|operation| operation.trim().split_once(char::is_whitespace))
+            .find(|&(opcode, _)| opcode == "c9")
+            .map(|(_, operand)| (fen, operand))
+    });
+    let (fen, result) = if let Some((fen, rest)) = line.split_once('|') {
+        (fen, rest.rsplit('|').next()?)
+    } else if let Some(c9) = c9 {
+        c9
+    } else if let Some((fen, rest)) = line.split_once('[') {
+        (fen, rest.split(']').next()?)
+    } else {
+        return None;
+    };
+    let result = match result.trim().trim_matches('"') {
+        "1-0" => 1.0,
+        "0-1" => 0.0,
+        "1/2-1/2" => 0.5,
+        number => number.parse().ok()?,
+    };
+    Some((fen.trim(), result))
+}
+
+fn load_chunk(lines: &[&str]) -> (Vec<Position>, usize) {
+    let mut board = Board::init();
+    let mut evaluator = Evaluator::new();
+    let mut positions = Vec::with_capacity(lines.len());
+    let mut skipped = 0;
+    for line in lines {
+        let Some((fen, result)) = parse_line(line) else {
+            skipped += 1;
+            continue;
+        };
+        if board.fen_setup(Some(fen)).is_err() {
+            skipped += 1;
+            continue;
+        }
+        // Endgames scored by dedicated code have nothing to teach the parameters
+        let Some(trace) = evaluator.trace(&board) else {
+            skipped += 1;
+            continue;
+        };
+        let coefficients = trace
+            .coefficients
+            .iter()
+            .enumerate()
+            .filter(|&(_, &count)| count != 0)
+            .map(|(param, &count)| (param as u16, count as i16))
+            .collect();
+        let mg_weight = trace.phase.clamp(0, MAX_PHASE) as f64 / MAX_PHASE as f64;
+        positions.push(Position {
+            coefficients,
+            mg_weight,
+            eg_weight: (1.0 - mg_weight) * trace.scale as f64 / SCALE_NORMAL as f64,
+            result,
+        });
+    }
+    (positions, skipped)
+}
+
+// Returns the positions and the number of lines that were skipped
+pub fn load(path: &str, num_threads: usize) -> io::Result<(Vec<Position>, usize)> {
+    let contents = fs::read_to_string(path)?;
+    let lines: Vec<&str> = contents.lines().filter(|l| !l.trim().is_empty()).collect();
+    let chunk_size = lines.len().div_ceil(num_threads).max(1);
+    let chunks: Vec<(Vec<Position>, usize)> = thread::scope(|scope| {
+        let handles: Vec<_> = lines
+            .chunks(chunk_size)
+            .map(|chunk| scope.spawn(|| load_chunk(chunk)))
+            .collect();
+        handles
+            .into_iter()
+            .map(|handle| handle.join().expect("loader thread panicked"))
+            .collect()
+    });
+
+    let skipped = chunks.iter().map(|(_, skipped)| skipped).sum();
+    let positions = chunks
+        .into_iter()
+        .flat_map(|(positions, _)| positions)
+        .collect();
+    Ok((positions, skipped))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_common_formats() {
+        let fen = "4k3/8/8/8/8/8/4P3/4K3 w - - 0 1";
+        assert_eq!(parse_line(&format!("{fen} | 35 | 1.0")), Some((fen, 1.0)));
+        assert_eq!(parse_line(&format!("{fen} [0.5]")), Some((fen, 0.5)));
+        assert_eq!(parse_line(&format!("{fen} c9 \"0-1\";")), Some((fen, 0.0)));
+        // Operations before c9 stay out of the position
+        let epd = "4k3/8/8/8/8/8/4P3/4K3 w - -";
+        assert_eq!(
+            parse_line(&format!("{epd} bm Kd2; id \"pawn [1]\"; c9 \"1/2-1/2\";")),
+            Some((epd, 0.5))
+        );
+        assert_eq!(parse_line(&format!("{epd} bm Kd2;")), None);
+        assert_eq!(parse_line(fen), None);
+    }
+}
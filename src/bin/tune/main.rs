@@ -0,0 +1,190 @@
+mod dataset;
+mod tuner;
+
+use std::{fmt::Write, process::ExitCode};
+
+use flying_goose::eval::{
+    params::PsqtParams,
+    score::Score,
+    trace::{Layout, PARAM_TABLES, Param, ParamTable, default_params},
+};
+use flying_goose::types::NumOf;
+
+use tuner::{Params, TuneConfig, fit_k, mean_error, tune};
+
+const DEFAULT_EPOCHS: usize = 1000;
+const DEFAULT_LEARNING_RATE: f64 = 1.0;
+const REPORT_EVERY: usize = 50;
+
+// Usage: tune <positions file> [epochs] [learning rate] [parameter file]
+//
+// Progress goes to stderr and the tuned tables to stdout, so the output can be redirected
+// straight into a file and pasted over the constants in src/eval. The piece values and
+// square tables are also written to the parameter file when one is given, to be loaded
+// by the engine without recompiling.
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().collect();
+    let Some(path) = args.get(1) else {
+        eprintln!("usage: tune <positions file> [epochs] [learning rate] [parameter file]");
+        return ExitCode::FAILURE;
+    };
+    let epochs = match args.get(2).map(|arg| arg.parse()) {
+        None => DEFAULT_EPOCHS,
+        Some(Ok(epochs)) => epochs,
+        Some(Err(err)) => {
+            eprintln!("invalid epoch count: {err}");
+            return ExitCode::FAILURE;
+        }
+    };
+    let learning_rate = match args.get(3).map(|arg| arg.parse()) {
+        None => DEFAULT_LEARNING_RATE,
+        Some(Ok(rate)) => rate,
+        Some(Err(err)) => {
+            eprintln!("invalid learning rate: {err}");
+            return ExitCode::FAILURE;
+        }
+    };
+    let num_threads = std::thread::available_parallelism()
+        .map(|n| n.get())
+        .unwrap_or(4);
+
+    let start = std::time::Instant::now();
+    let (positions, skipped) = match dataset::load(path, num_threads) {
+        Ok(loaded) => loaded,
+        Err(err) => {
+            eprintln!("could not read {path}: {err}");
+            return ExitCode::FAILURE;
+        }
+    };
+    if positions.is_empty() {
+        eprintln!("no usable positions in {path}");
+        return ExitCode::FAILURE;
+    }
+    eprintln!(
+        "Loaded {} positions ({skipped} skipped) in {:.2?}",
+        positions.len(),
+        start.elapsed()
+    );
+
+    let mut params: Params = default_params()
+        .iter()
+        .map(|score| [score.mg as f64, score.eg as f64])
+        .collect();
+    let k = fit_k(&positions, &params, num_threads);
+    eprintln!(
+        "K = {k:.4}, starting error {:.8}",
+        mean_error(&positions, &params, k, num_threads)
+    );
+
+    let config = TuneConfig {
+        epochs,
+        learning_rate,
+        report_every: REPORT_EVERY,
+        num_threads,
+    };
+    tune(&positions, &mut params, k, &config);
+    eprintln!("Tuned in {:.2?}", start.elapsed());
+    print!("{}", format_params(&params));
+    if let Some(out) = args.get(4) {
+        let text = psqt_params(&params).to_file_string();
+        if let Err(err) = std::fs::write(out, text) {
+            eprintln!("could not write {out}: {err}");
+            return ExitCode::FAILURE;
+        }
+        eprintln!("Wrote the piece values and square tables to {out}");
+    }
+    ExitCode::SUCCESS
+}
+
+fn rounded(param: [f64; 2]) -> Score {
+    Score::new(param[0].round() as i32, param[1].round() as i32)
+}
+
+fn psqt_params(params: &Params) -> PsqtParams {
+    let square = |piece: usize, square: usize| {
+        rounded(params[Param::PSQT + piece * NumOf::SQUARES + square])
+    };
+    PsqtParams {
+        piece_values: std::array::from_fn(|piece| rounded(params[Param::PIECE_VALUES + piece])),
+        mg_tables: std::array::from_fn(|piece| std::array::from_fn(|sq| square(piece, sq).mg)),
+        eg_tables: std::array::from_fn(|piece| std::array::from_fn(|sq| square(piece, sq).eg)),
+    }
+}
+
+fn score(param: [f64; 2]) -> String {
+    let score = rounded(param);
+    format!("Score::new({}, {})", score.mg, score.eg)
+}
+
+// The visibility each table is declared with in src/eval: the square tables are private
+// to psqt.rs, the piece values public and everything else crate-wide
+fn visibility(table: &ParamTable) -> &'static str {
+    match (table.layout, table.name) {
+        (Layout::PieceSquare, _) => "",
+        (_, "PIECE_VALUES") => "pub ",
+        _ => "pub(crate) ",
+    }
+}
+
+// Writes every table back out the way it is declared in the source, so each one can be
+// pasted over the old constant as is
+fn format_params(params: &Params) -> String {
+    let mut out = String::new();
+    for table in &PARAM_TABLES {
+        let values = &params[table.offset..table.offset + table.len];
+        let vis = visibility(table);
+        match table.layout {
+            Layout::Single => {
+                let _ = writeln!(
+                    out,
+                    "{vis}const {}: Score = {};",
+                    table.name,
+                    score(values[0])
+                );
+            }
+            Layout::Table => {
+                let _ = writeln!(out, "{vis}const {}: [Score; {}] = [", table.name, table.len);
+                for &value in values {
+                    let _ = writeln!(out, "    {},", score(value));
+                }
+                let _ = writeln!(out, "];");
+            }
+            Layout::PieceSquare => {
+                for (half, prefix) in ["MG", "EG"].iter().enumerate() {
+                    let _ = writeln!(out, "#[rustfmt::skip]");
+                    let _ = writeln!(out, "{vis}const {prefix}_{}: Table = [", table.name);
+                    for row in values.chunks(NumOf::FILES) {
+                        let row: Vec<String> = row
+                            .iter()
+                            .map(|value| format!("{:>3}", value[half].round() as i32))
+                            .collect();
+                        let _ = writeln!(out, "    {},", row.join(", "));
+                    }
+                    let _ = writeln!(out, "];");
+                    let _ = writeln!(out);
+                }
+                continue;
+            }
+        }
+        let _ = writeln!(out);
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tables_keep_their_declared_visibility() {
+        let params: Params = default_params()
+            .iter()
+            .map(|score| [score.mg as f64, score.eg as f64])
+            .collect();
+        let text = format_params(&params);
+        assert!(text.contains("pub const PIECE_VALUES: [Score; 6] = [\n"));
+        assert!(text.contains("#[rustfmt::skip]\nconst MG_KING: Table = [\n"));
+        assert!(text.contains("pub(crate) const PASSED_PAWN: [Score; 8] = [\n"));
+        assert!(text.contains("pub(crate) const ISOLATED_PAWN: Score = Score::new(-10, -12);\n"));
+    }
+}
@@ -0,0 +1,191 @@
+use std::thread;
+
+use crate::dataset::Position;
+
+// Parameters as (mg, eg) pairs, in the layout of flying_goose::eval::trace::Param
+pub type Params = Vec<[f64; 2]>;
+
+const BETA1: f64 = 0.9;
+const BETA2: f64 = 0.999;
+const EPSILON: f64 = 1e-8;
+
+#[inline(always)]
+fn evaluate(position: &Position, params: &Params) -> f64 {
+    position
+        .coefficients
+        .iter()
+        .map(|&(param, count)| {
+            let [mg, eg] = params[param as usize];
+            count as f64 * (mg * position.mg_weight + eg * position.eg_weight)
+        })
+        .sum()
+}
+
+// Expected score for White of a position evaluated at `eval` centipawns
+#[inline(always)]
+fn sigmoid(eval: f64, k: f64) -> f64 {
+    1.0 / (1.0 + 10f64.powf(-k * eval / 400.0))
+}
+
+// Runs `f` over equal slices of the positions on every thread and collects the results
+fn map_chunks<R: Send>(
+    positions: &[Position],
+    num_threads: usize,
+    f: impl Fn(&[Position]) -> R + Sync,
+) -> Vec<R> {
+    let chunk_size = positions.len().div_ceil(num_threads).max(1);
+    thread::scope(|scope| {
+        let handles: Vec<_> = positions
+            .chunks(chunk_size)
+            .map(|chunk| scope.spawn(|| f(chunk)))
+            .collect();
+        handles
+            .into_iter()
+            .map(|handle| handle.join().expect("tuner thread panicked"))
+            .collect()
+    })
+}
+
+pub fn mean_error(positions: &[Position], params: &Params, k: f64, num_threads: usize) -> f64 {
+    let total: f64 = map_chunks(positions, num_threads, |chunk| {
+        chunk
+            .iter()
+            .map(|position| (position.result - sigmoid(evaluate(position, params), k)).powi(2))
+            .sum::<f64>()
+    })
+    .iter()
+    .sum();
+    total / positions.len() as f64
+}
+
+// Finds the K that best maps the current evaluation onto the results, one decimal digit
+// at a time
+pub fn fit_k(positions: &[Position], params: &Params, num_threads: usize) -> f64 {
+    let mut best_k = 1.0;
+    let mut best_error = mean_error(positions, params, best_k, num_threads);
+    let mut step = 1.0;
+    for _ in 0..5 {
+        let centre = best_k;
+        for i in -10..=10 {
+            let k = centre + i as f64 * step;
+            if k <= 0.0 {
+                continue;
+            }
+            let error = mean_error(positions, params, k, num_threads);
+            if error < best_error {
+                best_error = error;
+                best_k = k;
+            }
+        }
+        step /= 10.0;
+    }
+    best_k
+}
+
+fn gradient(positions: &[Position], params: &Params, k: f64, num_threads: usize) -> Params {
+    let partials = map_chunks(positions, num_threads, |chunk| {
+        let mut gradient = vec![[0.0; 2]; params.len()];
+        for position in chunk {
+            let expected = sigmoid(evaluate(position, params), k);
+            // Derivative of (result - sigmoid)^2 with respect to the evaluation
+            let error = -2.0
+                * (position.result - expected)
+                * expected
+                * (1.0 - expected)
+                * k
+                * std::f64::consts::LN_10
+                / 400.0;
+            for &(param, count) in &position.coefficients {
+                let slot = &mut gradient[param as usize];
+                slot[0] += error * count as f64 * position.mg_weight;
+                slot[1] += error * count as f64 * position.eg_weight;
+            }
+        }
+        gradient
+    });
+
+    let mut gradient = vec![[0.0; 2]; params.len()];
+    for partial in partials {
+        for (total, part) in gradient.iter_mut().zip(partial) {
+            total[0] += part[0];
+            total[1] += part[1];
+        }
+    }
+    let n = positions.len() as f64;
+    for slot in &mut gradient {
+        slot[0] /= n;
+        slot[1] /= n;
+    }
+    gradient
+}
+
+pub struct TuneConfig {
+    pub epochs: usize,
+    pub learning_rate: f64,
+    pub report_every: usize,
+    pub num_threads: usize,
+}
+
+// Adam on the mean squared error between the results and the sigmoid of the evaluation
+pub fn tune(positions: &[Position], params: &mut Params, k: f64, config: &TuneConfig) {
+    let mut momentum = vec![[0.0; 2]; params.len()];
+    let mut velocity = vec![[0.0; 2]; params.len()];
+    for epoch in 1..=config.epochs {
+        let gradient = gradient(positions, params, k, config.num_threads);
+        let correction1 = 1.0 - BETA1.powi(epoch as i32);
+        let correction2 = 1.0 - BETA2.powi(epoch as i32);
+        for (((param, grad), m), v) in params
+            .iter_mut()
+            .zip(&gradient)
+            .zip(&mut momentum)
+            .zip(&mut velocity)
+        {
+            for half in 0..2 {
+                m[half] = BETA1 * m[half] + (1.0 - BETA1) * grad[half];
+                v[half] = BETA2 * v[half] + (1.0 - BETA2) * grad[half] * grad[half];
+                let m_hat = m[half] / correction1;
+                let v_hat = v[half] / correction2;
+                param[half] -= config.learning_rate * m_hat / (v_hat.sqrt() + EPSILON);
+            }
+        }
+        if epoch % config.report_every == 0 || epoch == config.epochs {
+            let error = mean_error(positions, params, k, config.num_threads);
+            eprintln!("epoch {epoch:>5}: error {error:.8}");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // One parameter, positions won whenever it is present
+    fn toy_positions() -> Vec<Position> {
+        (0..100)
+            .map(|i| Position {
+                coefficients: if i % 2 == 0 { vec![(0, 1)] } else { vec![] },
+                mg_weight: 1.0,
+                eg_weight: 0.0,
+                result: if i % 2 == 0 { 1.0 } else { 0.5 },
+            })
+            .collect()
+    }
+
+    #[test]
+    fn adam_lowers_the_error() {
+        let positions = toy_positions();
+        let mut params: Params = vec![[0.0, 0.0]];
+        let before = mean_error(&positions, &params, 1.0, 2);
+        let config = TuneConfig {
+            epochs: 200,
+            learning_rate: 5.0,
+            report_every: usize::MAX,
+            num_threads: 2,
+        };
+        tune(&positions, &mut params, 1.0, &config);
+        assert!(mean_error(&positions, &params, 1.0, 2) < before / 2.0);
+        assert!(params[0][0] > 100.0);
+        // The eg half never takes part
+        assert_eq!(params[0][1], 0.0);
+    }
+}
@@ -2,14 +2,18 @@ pub mod defs;
 pub mod fen;
 pub mod history;
 pub mod initialization;
+pub mod movegen;
 pub mod piece_movement;
+pub mod rules;
+pub mod san;
 pub mod state;
 pub mod types;
 pub mod zobrist;
 
 use crate::{
-    board::types::Piece,
-    types::{BitBoard, NumOf},
+    board::types::ColoredPiece,
+    eval::{nnue::accumulator::FeatureUpdates, params::ActiveParams},
+    types::{Bitboard, NumOf},
 };
 use history::GameHistory;
 use state::GameState;
@@ -17,10 +21,14 @@ use zobrist::Zobrist;
 
 #[derive(Debug, Clone)]
 pub struct Board {
-    pub bb_pieces: [[BitBoard; NumOf::PIECE_TYPES]; NumOf::SIDES],
-    pub bb_sides: [BitBoard; NumOf::SIDES],
-    pub piece_list: [Piece; NumOf::SQUARES],
+    pub bb_pieces: [[Bitboard; NumOf::PIECE_TYPES]; NumOf::SIDES],
+    pub bb_sides: [Bitboard; NumOf::SIDES],
+    pub piece_list: [Option<ColoredPiece>; NumOf::SQUARES],
     pub game_state: GameState,
     pub history: GameHistory,
     pub zobrist_hashmap: Zobrist,
+    pub feature_updates: FeatureUpdates,
+    // The square tables the psqt sum in game_state is built from, fixed when the board is
+    // set up so switching parameter sets never mixes two tables in one sum
+    pub params: &'static ActiveParams,
 }
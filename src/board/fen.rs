@@ -2,7 +2,7 @@ use crate::board::types::Side;
 use crate::{
     board::{
         Board,
-        types::{CastlingRight, FIFTY_MOVE_RULE, Files, MAX_GAME_MOVES, Pieces, Ranks},
+        types::{CastlingRight, FIFTY_MOVE_RULE, Files, MAX_GAME_MOVES, Piece, Ranks, SquareCoord},
     },
     types::{NumOf, SQUARE_MASKS},
 };
@@ -30,6 +30,7 @@ pub const FEN_PARSE_FUNCS: [FenParseFunc; FEN_NR_PARTS] = [
 pub enum FenError {
     IncorrectLength,
     PiecePart,
+    KingCount,
     SidePart,
     CastlingPart,
     EnpassantPart,
@@ -42,6 +43,7 @@ impl Display for FenError {
         let err = match self {
             FenError::IncorrectLength => "FEN String error: Must have 6 parts",
             FenError::PiecePart => "Error encountered during the parsing of the piece part",
+            FenError::KingCount => "FEN String error: Each side must have exactly one king",
             FenError::SidePart => "Error encountered during the parsing of the side part",
             FenError::CastlingPart => "Error encountered during the parsing of the castling part",
             FenError::EnpassantPart => {
@@ -88,57 +90,55 @@ pub fn fen_parse_pieces(board: &mut Board, part: &str) -> Result<(), FenError> {
             let mut is_piece_match = true;
             match c {
                 'k' => {
-                    board.bb_pieces[Side::Black as usize][Pieces::KING] |= SQUARE_MASKS[square_idx];
+                    board.bb_pieces[Side::Black as usize][Piece::King] |= SQUARE_MASKS[square_idx];
                     board.bb_sides[Side::Black as usize] |= SQUARE_MASKS[square_idx];
                 }
                 'q' => {
-                    board.bb_pieces[Side::Black as usize][Pieces::QUEEN] |=
-                        SQUARE_MASKS[square_idx];
+                    board.bb_pieces[Side::Black as usize][Piece::Queen] |= SQUARE_MASKS[square_idx];
                     board.bb_sides[Side::Black as usize] |= SQUARE_MASKS[square_idx];
                 }
                 'r' => {
-                    board.bb_pieces[Side::Black as usize][Pieces::ROOK] |= SQUARE_MASKS[square_idx];
+                    board.bb_pieces[Side::Black as usize][Piece::Rook] |= SQUARE_MASKS[square_idx];
                     board.bb_sides[Side::Black as usize] |= SQUARE_MASKS[square_idx];
                 }
                 'b' => {
-                    board.bb_pieces[Side::Black as usize][Pieces::BISHOP] |=
+                    board.bb_pieces[Side::Black as usize][Piece::Bishop] |=
                         SQUARE_MASKS[square_idx];
                     board.bb_sides[Side::Black as usize] |= SQUARE_MASKS[square_idx];
                 }
                 'n' => {
-                    board.bb_pieces[Side::Black as usize][Pieces::KNIGHT] |=
+                    board.bb_pieces[Side::Black as usize][Piece::Knight] |=
                         SQUARE_MASKS[square_idx];
                     board.bb_sides[Side::Black as usize] |= SQUARE_MASKS[square_idx];
                 }
                 'p' => {
-                    board.bb_pieces[Side::Black as usize][Pieces::PAWN] |= SQUARE_MASKS[square_idx];
+                    board.bb_pieces[Side::Black as usize][Piece::Pawn] |= SQUARE_MASKS[square_idx];
                     board.bb_sides[Side::Black as usize] |= SQUARE_MASKS[square_idx];
                 }
                 'K' => {
-                    board.bb_pieces[Side::White as usize][Pieces::KING] |= SQUARE_MASKS[square_idx];
+                    board.bb_pieces[Side::White as usize][Piece::King] |= SQUARE_MASKS[square_idx];
                     board.bb_sides[Side::White as usize] |= SQUARE_MASKS[square_idx];
                 }
                 'Q' => {
-                    board.bb_pieces[Side::White as usize][Pieces::QUEEN] |=
-                        SQUARE_MASKS[square_idx];
+                    board.bb_pieces[Side::White as usize][Piece::Queen] |= SQUARE_MASKS[square_idx];
                     board.bb_sides[Side::White as usize] |= SQUARE_MASKS[square_idx];
                 }
                 'R' => {
-                    board.bb_pieces[Side::White as usize][Pieces::ROOK] |= SQUARE_MASKS[square_idx];
+                    board.bb_pieces[Side::White as usize][Piece::Rook] |= SQUARE_MASKS[square_idx];
                     board.bb_sides[Side::White as usize] |= SQUARE_MASKS[square_idx];
                 }
                 'B' => {
-                    board.bb_pieces[Side::White as usize][Pieces::BISHOP] |=
+                    board.bb_pieces[Side::White as usize][Piece::Bishop] |=
                         SQUARE_MASKS[square_idx];
                     board.bb_sides[Side::White as usize] |= SQUARE_MASKS[square_idx];
                 }
                 'N' => {
-                    board.bb_pieces[Side::White as usize][Pieces::KNIGHT] |=
+                    board.bb_pieces[Side::White as usize][Piece::Knight] |=
                         SQUARE_MASKS[square_idx];
                     board.bb_sides[Side::White as usize] |= SQUARE_MASKS[square_idx];
                 }
                 'P' => {
-                    board.bb_pieces[Side::White as usize][Pieces::PAWN] |= SQUARE_MASKS[square_idx];
+                    board.bb_pieces[Side::White as usize][Piece::Pawn] |= SQUARE_MASKS[square_idx];
                     board.bb_sides[Side::White as usize] |= SQUARE_MASKS[square_idx];
                 }
                 '1'..='8' => {
@@ -160,6 +160,12 @@ pub fn fen_parse_pieces(board: &mut Board, part: &str) -> Result<(), FenError> {
             return Err(FenError::PiecePart);
         }
     }
+    // Move generation and evaluation look up each side's king without checking for it
+    for side in [Side::White, Side::Black] {
+        if board.bb_pieces[side as usize][Piece::King].popcount() != 1 {
+            return Err(FenError::KingCount);
+        }
+    }
     Ok(())
 }
 
@@ -178,6 +184,7 @@ pub fn fen_parse_castling_rights(board: &mut Board, part: &str) -> Result<(), Fe
     if !(1..=4).contains(&part.len()) {
         return Err(FenError::CastlingPart);
     }
+    board.game_state.castling = 0;
     part.chars().try_for_each(|c| {
         match c {
             'k' => board.game_state.castling |= CastlingRight::BlackKingSide as u8,
@@ -192,7 +199,6 @@ pub fn fen_parse_castling_rights(board: &mut Board, part: &str) -> Result<(), Fe
     Ok(())
 }
 
-// TODO: Write unit test for this
 pub fn fen_parse_enpassant(board: &mut Board, part: &str) -> Result<(), FenError> {
     if part.len() == 1 {
         if let Some(c) = part.chars().nth(0)
@@ -216,15 +222,13 @@ pub fn fen_parse_enpassant(board: &mut Board, part: &str) -> Result<(), FenError
             'h' => Files::H,
             _ => return Err(FenError::EnpassantPart),
         };
-        let rank: Ranks = match pchar.nth(1).unwrap() {
+        let rank: Ranks = match pchar.next().unwrap() {
             '3' => Ranks::R3,
             '6' => Ranks::R6,
             _ => return Err(FenError::EnpassantPart),
         };
-        let file = file as usize;
-        let rank = rank as usize;
-        let enpassant_square_idx = file + (rank * NumOf::RANKS);
-        board.game_state.enpassant = Some(enpassant_square_idx);
+        let enpassant_square = SquareCoord { file, rank }.to_square();
+        board.game_state.enpassant = Some(enpassant_square);
         return Ok(());
     }
     Err(FenError::EnpassantPart)
@@ -258,10 +262,11 @@ mod tests {
         fen::FEN_START_POSITION,
         history::GameHistory,
         state::GameState,
-        types::{Files, SquareCoord},
+        types::{Files, Square, SquareCoord},
         zobrist::Zobrist,
     };
-    use crate::types::SQUARE_MASKS;
+    use crate::eval::{nnue::accumulator::FeatureUpdates, params};
+    use crate::types::{Bitboard, EMPTY_BITBOARD, RANK_MASKS, SQUARE_MASKS};
 
     use super::*;
 
@@ -293,12 +298,14 @@ mod tests {
     #[test]
     fn test_fen_parse_pieces() {
         let mut test_board = Board {
-            bb_pieces: [[0; NumOf::PIECE_TYPES]; NumOf::SIDES],
-            bb_sides: [0; NumOf::SIDES],
-            piece_list: [Pieces::NONE; NumOf::SQUARES],
+            bb_pieces: [[EMPTY_BITBOARD; NumOf::PIECE_TYPES]; NumOf::SIDES],
+            bb_sides: [EMPTY_BITBOARD; NumOf::SIDES],
+            piece_list: [None; NumOf::SQUARES],
             game_state: GameState::new(),
             history: GameHistory::new(),
             zobrist_hashmap: Zobrist::new(None),
+            feature_updates: FeatureUpdates::new(),
+            params: params::active(),
         };
 
         // test start position
@@ -307,15 +314,17 @@ mod tests {
         assert!(!res.is_err());
         // check if the board has the right values
         // 1. Check the bb_sides array
-        const WHITE_START_MASK: u64 = (1u64 << NumOf::PIECES_PER_SIDE) - 1;
+        const WHITE_START_MASK: Bitboard = Bitboard((1u64 << NumOf::PIECES_PER_SIDE) - 1);
         assert_eq!(test_board.bb_sides[Side::White as usize], WHITE_START_MASK);
-        const BLACK_START_MASK: u64 = !((1u64 << (NumOf::SQUARES - NumOf::PIECES_PER_SIDE)) - 1);
+        const BLACK_START_MASK: Bitboard =
+            Bitboard(!((1u64 << (NumOf::SQUARES - NumOf::PIECES_PER_SIDE)) - 1));
         assert_eq!(test_board.bb_sides[Side::Black as usize], BLACK_START_MASK);
         let white_pieces = test_board.bb_pieces[Side::White as usize];
         let black_pieces = test_board.bb_pieces[Side::Black as usize];
-        for (piece_type, (wp, bp)) in white_pieces.iter().zip(black_pieces.iter()).enumerate() {
+        for piece_type in Piece::ALL {
+            let (wp, bp) = (&white_pieces[piece_type], &black_pieces[piece_type]);
             match piece_type {
-                Pieces::KING => {
+                Piece::King => {
                     let white_king_square = SquareCoord {
                         file: Files::E,
                         rank: Ranks::R1,
@@ -324,13 +333,13 @@ mod tests {
                         file: Files::E,
                         rank: Ranks::R8,
                     };
-                    let white_king_square_idx = white_king_square.to_usize();
-                    let black_king_square_idx = black_king_square.to_usize();
+                    let white_king_square_idx = white_king_square.to_square();
+                    let black_king_square_idx = black_king_square.to_square();
                     assert_eq!(*wp, SQUARE_MASKS[white_king_square_idx]);
                     assert_eq!(*bp, SQUARE_MASKS[black_king_square_idx]);
                 }
 
-                Pieces::QUEEN => {
+                Piece::Queen => {
                     let white_queen_square = SquareCoord {
                         file: Files::D,
                         rank: Ranks::R1,
@@ -339,12 +348,12 @@ mod tests {
                         file: Files::D,
                         rank: Ranks::R8,
                     };
-                    let white_queen_square_idx = white_queen_square.to_usize();
-                    let black_queen_square_idx = black_queen_square.to_usize();
+                    let white_queen_square_idx = white_queen_square.to_square();
+                    let black_queen_square_idx = black_queen_square.to_square();
                     assert_eq!(*wp, SQUARE_MASKS[white_queen_square_idx]);
                     assert_eq!(*bp, SQUARE_MASKS[black_queen_square_idx]);
                 }
-                Pieces::ROOK => {
+                Piece::Rook => {
                     let white_rook_squares: [SquareCoord; 2] = [
                         SquareCoord {
                             file: Files::A,
@@ -355,8 +364,8 @@ mod tests {
                             rank: Ranks::R1,
                         },
                     ];
-                    let white_rook_mask = SQUARE_MASKS[white_rook_squares[0].to_usize()]
-                        | SQUARE_MASKS[white_rook_squares[1].to_usize()];
+                    let white_rook_mask = SQUARE_MASKS[white_rook_squares[0].to_square()]
+                        | SQUARE_MASKS[white_rook_squares[1].to_square()];
                     let black_rook_squares: [SquareCoord; 2] = [
                         SquareCoord {
                             file: Files::A,
@@ -367,12 +376,12 @@ mod tests {
                             rank: Ranks::R8,
                         },
                     ];
-                    let black_rook_mask = SQUARE_MASKS[black_rook_squares[0].to_usize()]
-                        | SQUARE_MASKS[black_rook_squares[1].to_usize()];
+                    let black_rook_mask = SQUARE_MASKS[black_rook_squares[0].to_square()]
+                        | SQUARE_MASKS[black_rook_squares[1].to_square()];
                     assert_eq!(*wp, white_rook_mask);
                     assert_eq!(*bp, black_rook_mask);
                 }
-                Pieces::BISHOP => {
+                Piece::Bishop => {
                     let white_bishop_squares: [SquareCoord; 2] = [
                         SquareCoord {
                             file: Files::C,
@@ -383,8 +392,8 @@ mod tests {
                             rank: Ranks::R1,
                         },
                     ];
-                    let white_bishop_mask = SQUARE_MASKS[white_bishop_squares[0].to_usize()]
-                        | SQUARE_MASKS[white_bishop_squares[1].to_usize()];
+                    let white_bishop_mask = SQUARE_MASKS[white_bishop_squares[0].to_square()]
+                        | SQUARE_MASKS[white_bishop_squares[1].to_square()];
                     let black_bishop_squares: [SquareCoord; 2] = [
                         SquareCoord {
                             file: Files::C,
@@ -395,12 +404,12 @@ mod tests {
                             rank: Ranks::R8,
                         },
                     ];
-                    let black_bishop_mask = SQUARE_MASKS[black_bishop_squares[0].to_usize()]
-                        | SQUARE_MASKS[black_bishop_squares[1].to_usize()];
+                    let black_bishop_mask = SQUARE_MASKS[black_bishop_squares[0].to_square()]
+                        | SQUARE_MASKS[black_bishop_squares[1].to_square()];
                     assert_eq!(*wp, white_bishop_mask);
                     assert_eq!(*bp, black_bishop_mask);
                 }
-                Pieces::KNIGHT => {
+                Piece::Knight => {
                     let white_knight_squares: [SquareCoord; 2] = [
                         SquareCoord {
                             file: Files::B,
@@ -411,8 +420,8 @@ mod tests {
                             rank: Ranks::R1,
                         },
                     ];
-                    let white_knight_mask = SQUARE_MASKS[white_knight_squares[0].to_usize()]
-                        | SQUARE_MASKS[white_knight_squares[1].to_usize()];
+                    let white_knight_mask = SQUARE_MASKS[white_knight_squares[0].to_square()]
+                        | SQUARE_MASKS[white_knight_squares[1].to_square()];
                     let black_knight_squares: [SquareCoord; 2] = [
                         SquareCoord {
                             file: Files::B,
@@ -423,19 +432,60 @@ mod tests {
                             rank: Ranks::R8,
                         },
                     ];
-                    let black_knight_mask = SQUARE_MASKS[black_knight_squares[0].to_usize()]
-                        | SQUARE_MASKS[black_knight_squares[1].to_usize()];
+                    let black_knight_mask = SQUARE_MASKS[black_knight_squares[0].to_square()]
+                        | SQUARE_MASKS[black_knight_squares[1].to_square()];
                     assert_eq!(*wp, white_knight_mask);
                     assert_eq!(*bp, black_knight_mask);
                 }
-                Pieces::PAWN => {
-                    let white_pawn_masks = ((1u64 << 16) - 1) & !((1u64 << 8) - 1);
-                    let black_pawn_masks = (1u64 << 56) - 1 & !((1u64 << 48) - 1);
+                Piece::Pawn => {
+                    let white_pawn_masks = RANK_MASKS[1];
+                    let black_pawn_masks = RANK_MASKS[6];
                     assert_eq!(*wp, white_pawn_masks);
                     assert_eq!(*bp, black_pawn_masks);
                 }
-                _ => {}
             }
         }
     }
+
+    #[test]
+    fn fen_setup_replaces_previous_position() {
+        let mut board = Board::init();
+        board
+            .fen_setup(Some("4k3/8/8/8/8/8/8/4K3 w - - 0 1"))
+            .unwrap();
+        assert_eq!(board.bb_sides[Side::White as usize].popcount(), 1);
+        assert_eq!(board.bb_sides[Side::Black as usize].popcount(), 1);
+        assert_eq!(
+            board.bb_pieces[Side::White as usize][Piece::Pawn],
+            EMPTY_BITBOARD
+        );
+    }
+
+    #[test]
+    fn rejects_positions_without_one_king_per_side() {
+        for fen in [
+            "8/8/8/8/8/8/8/4K3 w - - 0 1",
+            "4k3/8/8/8/8/8/8/8 b - - 0 1",
+            "4k3/8/8/8/8/8/8/3KK3 w - - 0 1",
+        ] {
+            assert_eq!(
+                Board::from_fen(fen).err(),
+                Some(FenError::KingCount),
+                "{fen}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_fen_parse_enpassant() {
+        let mut board = Board::init();
+        fen_parse_enpassant(&mut board, "e3").unwrap();
+        assert_eq!(board.game_state.enpassant, Some(Square::E3));
+        fen_parse_enpassant(&mut board, "d6").unwrap();
+        assert_eq!(board.game_state.enpassant, Some(Square::D6));
+        assert_eq!(
+            fen_parse_enpassant(&mut board, "e4").unwrap_err(),
+            FenError::EnpassantPart
+        );
+    }
 }
@@ -3,20 +3,28 @@ use crate::board::fen::{FEN_PARSE_FUNCS, FenError, fen_split_string};
 use crate::board::history::GameHistory;
 use crate::board::state::GameState;
 use crate::board::{
-    types::{Pieces, Side},
+    types::{Piece, Side},
     zobrist::{Zobrist, ZobristKey},
 };
-use crate::types::{BitBoard, EMPTY_BITBOARD, NumOf};
+use crate::eval::{
+    nnue::accumulator::FeatureUpdates,
+    params,
+    psqt::{PHASE_WEIGHTS, psqt},
+    score::Score,
+};
+use crate::types::{Bitboard, EMPTY_BITBOARD, NumOf};
 
 impl Board {
     fn new() -> Self {
         Board {
             bb_pieces: [[EMPTY_BITBOARD; NumOf::PIECE_TYPES]; NumOf::SIDES],
             bb_sides: [EMPTY_BITBOARD; NumOf::SIDES],
-            piece_list: [Pieces::NONE; NumOf::SQUARES],
+            piece_list: [None; NumOf::SQUARES],
             game_state: GameState::new(),
             history: GameHistory::new(),
             zobrist_hashmap: Zobrist::new(None),
+            feature_updates: FeatureUpdates::new(),
+            params: params::active(),
         }
     }
     pub fn init() -> Self {
@@ -24,12 +32,21 @@ impl Board {
         let (white_side, black_side) = board.init_bb_sides();
         board.bb_sides[Side::White as usize] = white_side;
         board.bb_sides[Side::Black as usize] = black_side;
-        board.piece_list = board.get_piece_list();
-        board.game_state.zobrist_key = board.init_zobrist_key();
+        board.init_derived_state();
         board
     }
 
-    fn init_bb_sides(&self) -> (BitBoard, BitBoard) {
+    // Everything that put_piece/remove_piece maintain incrementally, recomputed from scratch
+    fn init_derived_state(&mut self) {
+        self.piece_list = self.get_piece_list();
+        self.game_state.zobrist_key = self.init_zobrist_key();
+        self.game_state.pawn_key = self.init_pawn_key();
+        self.params = params::active();
+        (self.game_state.psqt, self.game_state.phase) = self.init_psqt();
+        self.feature_updates = FeatureUpdates::new();
+    }
+
+    fn init_bb_sides(&self) -> (Bitboard, Bitboard) {
         let mut white_side = EMPTY_BITBOARD;
         let mut black_side = EMPTY_BITBOARD;
         for (wp, bp) in self.bb_pieces[Side::White as usize]
@@ -46,22 +63,16 @@ impl Board {
         let mut key = 0u64;
         let white_bbs = self.bb_pieces[Side::White as usize];
         let black_bbs = self.bb_pieces[Side::Black as usize];
-        for (piece_type, (w, b)) in white_bbs.iter().zip(black_bbs.iter()).enumerate() {
-            let mut white_bitboard = *w;
-            let mut black_bitboard = *b;
-            while white_bitboard != 0 {
-                let square_idx = white_bitboard.trailing_zeros() as usize;
+        for piece_type in Piece::ALL {
+            for square_idx in white_bbs[piece_type] {
                 key ^= self
                     .zobrist_hashmap
                     .piece(Side::White, piece_type, square_idx);
-                white_bitboard &= white_bitboard - 1;
             }
-            while black_bitboard != 0 {
-                let square_idx = black_bitboard.trailing_zeros() as usize;
+            for square_idx in black_bbs[piece_type] {
                 key ^= self
                     .zobrist_hashmap
                     .piece(Side::Black, piece_type, square_idx);
-                black_bitboard &= black_bitboard - 1;
             }
         }
         // White to move so we don't include the side_hash
@@ -69,8 +80,7 @@ impl Board {
         key ^= self.zobrist_hashmap.castling(self.game_state.castling);
         // handle the enpassant file:
         if let Some(enpassant_square) = self.game_state.enpassant {
-            let enpassant_file = (enpassant_square % NumOf::FILES) as usize;
-            key ^= self.zobrist_hashmap.enpassant(enpassant_file);
+            key ^= self.zobrist_hashmap.enpassant(enpassant_square.file());
         }
         if self.game_state.active_color == Side::Black {
             key ^= self.zobrist_hashmap.side();
@@ -78,17 +88,52 @@ impl Board {
         key
     }
 
-    // TODO: write this method without relying on cloning the board
+    fn init_pawn_key(&self) -> ZobristKey {
+        let mut key = 0u64;
+        for side in [Side::White, Side::Black] {
+            for piece_type in [Piece::Pawn, Piece::King] {
+                for square_idx in self.bb_pieces[side as usize][piece_type] {
+                    key ^= self.zobrist_hashmap.piece(side, piece_type, square_idx);
+                }
+            }
+        }
+        key
+    }
+
+    fn init_psqt(&self) -> ([Score; NumOf::SIDES], i32) {
+        let mut sums = [Score::ZERO; NumOf::SIDES];
+        let mut phase = 0;
+        for side in [Side::White, Side::Black] {
+            for piece_type in Piece::ALL {
+                for square_idx in self.bb_pieces[side as usize][piece_type] {
+                    sums[side as usize] += psqt(&self.params.psqt, side, piece_type, square_idx);
+                    phase += PHASE_WEIGHTS[piece_type];
+                }
+            }
+        }
+        (sums, phase)
+    }
+
     pub fn fen_setup(&mut self, fen: Option<&str>) -> Result<(), FenError> {
         // Step 1. Split the FEN string into 6 parts that we need to parse.
         let fen_parts = fen_split_string(fen)?;
-        // expensive operation, should probably do something else here
-        let mut new_board = self.clone();
+        // The parsers OR pieces into the bitboards, so start from an empty board. Only the
+        // zobrist keys carry over.
+        let mut new_board = Self::new();
+        new_board.zobrist_hashmap = self.zobrist_hashmap;
         FEN_PARSE_FUNCS
             .iter()
             .zip(fen_parts.iter())
             .try_for_each(|(fen_parser, part)| fen_parser(&mut new_board, part.as_str()))?;
+        // The FEN parsers only fill in the bitboards and the game state flags
+        new_board.init_derived_state();
         *self = new_board;
         Ok(())
     }
+
+    pub fn from_fen(fen: &str) -> Result<Self, FenError> {
+        let mut board = Self::init();
+        board.fen_setup(Some(fen))?;
+        Ok(board)
+    }
 }
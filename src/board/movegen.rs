@@ -0,0 +1,274 @@
+use std::ops::Deref;
+
+use crate::{
+    board::{
+        Board,
+        piece_movement::Move,
+        types::{CastlingRight, Piece, Side, Square},
+    },
+    movement::ATTACKS,
+    types::{Bitboard, EMPTY_BITBOARD, NumOf, RANK_MASKS, SQUARE_MASKS},
+};
+
+// No legal chess position has more than 218 moves
+pub const MAX_MOVES: usize = 256;
+
+const PROMOTION_PIECES: [Piece; 4] = [Piece::Queen, Piece::Rook, Piece::Bishop, Piece::Knight];
+
+// Fixed capacity so generating moves never allocates
+pub struct MoveList {
+    moves: [Move; MAX_MOVES],
+    len: usize,
+}
+
+impl MoveList {
+    pub fn new() -> Self {
+        Self {
+            moves: [Move::new(Square::A1, Square::A1, 0); MAX_MOVES],
+            len: 0,
+        }
+    }
+
+    #[inline(always)]
+    pub fn push(&mut self, chess_move: Move) {
+        self.moves[self.len] = chess_move;
+        self.len += 1;
+    }
+}
+
+impl Default for MoveList {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Deref for MoveList {
+    type Target = [Move];
+
+    fn deref(&self) -> &[Move] {
+        &self.moves[..self.len]
+    }
+}
+
+impl Board {
+    pub fn in_check(&self) -> bool {
+        let side = self.get_current_player();
+        let king = self.bb_pieces[side as usize][Piece::King].lsb();
+        !self
+            .attackers_to(king, side.other(), self.occupancy())
+            .is_empty()
+    }
+
+    pub fn legal_moves(&self) -> MoveList {
+        let mut pseudo_legal = MoveList::new();
+        self.pseudo_legal_moves(&mut pseudo_legal);
+        let mut legal = MoveList::new();
+        for &chess_move in pseudo_legal.iter() {
+            if self.is_legal(chess_move) {
+                legal.push(chess_move);
+            }
+        }
+        legal
+    }
+
+    // The legal move written as `chess_move` in UCI's long algebraic notation
+    pub fn parse_uci(&self, chess_move: &str) -> Option<Move> {
+        self.legal_moves()
+            .iter()
+            .copied()
+            .find(|legal| legal.to_string() == chess_move)
+    }
+
+    // Leaf nodes of the legal move tree, the standard check of a move generator
+    pub fn perft(&mut self, depth: usize) -> u64 {
+        if depth == 0 {
+            return 1;
+        }
+        let moves = self.legal_moves();
+        if depth == 1 {
+            return moves.len() as u64;
+        }
+        let mut nodes = 0;
+        for &chess_move in moves.iter() {
+            self.make(chess_move);
+            nodes += self.perft(depth - 1);
+            self.unmake(chess_move);
+        }
+        nodes
+    }
+
+    fn occupancy(&self) -> Bitboard {
+        self.bb_sides[Side::White as usize] | self.bb_sides[Side::Black as usize]
+    }
+
+    // Every move that is legal apart from possibly leaving the own king in check.
+    // Castling is the exception: its path is checked here, as is_legal only sees the
+    // king's destination.
+    fn pseudo_legal_moves(&self, list: &mut MoveList) {
+        let side = self.get_current_player();
+        let own = self.bb_sides[side as usize];
+        let them = self.bb_sides[side.other() as usize];
+        let occupancy = own | them;
+        let pieces = &self.bb_pieces[side as usize];
+
+        let push_targets = |list: &mut MoveList, from: Square, targets: Bitboard| {
+            for to in targets & !own {
+                list.push(self.build_move(from, to, None));
+            }
+        };
+        for piece in [
+            Piece::King,
+            Piece::Queen,
+            Piece::Rook,
+            Piece::Bishop,
+            Piece::Knight,
+        ] {
+            for from in pieces[piece] {
+                let targets = match piece {
+                    Piece::King => ATTACKS.king_attacks[from],
+                    Piece::Queen => ATTACKS.get_queen_attacks(from, occupancy),
+                    Piece::Rook => ATTACKS.get_rook_attacks(from, occupancy),
+                    Piece::Bishop => ATTACKS.get_bishop_attacks(from, occupancy),
+                    _ => ATTACKS.knight_attacks[from],
+                };
+                push_targets(list, from, targets);
+            }
+        }
+
+        self.pawn_moves(list, occupancy, them);
+        self.castling_moves(list, occupancy);
+    }
+
+    fn pawn_moves(&self, list: &mut MoveList, occupancy: Bitboard, them: Bitboard) {
+        let side = self.get_current_player();
+        let (forward, start_rank, last_rank): (isize, usize, usize) = match side {
+            Side::White => (NumOf::FILES as isize, 1, NumOf::RANKS - 1),
+            Side::Black => (-(NumOf::FILES as isize), NumOf::RANKS - 2, 0),
+        };
+        let enpassant = self
+            .game_state
+            .enpassant
+            .map_or(EMPTY_BITBOARD, |square| SQUARE_MASKS[square]);
+
+        for from in self.bb_pieces[side as usize][Piece::Pawn] {
+            let mut targets = ATTACKS.pawn_attacks[side][from] & (them | enpassant);
+            let one = from.step(forward);
+            if !occupancy.is_set(one) {
+                targets |= SQUARE_MASKS[one];
+                if from.rank() == start_rank && !occupancy.is_set(one.step(forward)) {
+                    targets |= SQUARE_MASKS[one.step(forward)];
+                }
+            }
+
+            for to in targets {
+                if RANK_MASKS[last_rank].is_set(to) {
+                    for promotion in PROMOTION_PIECES {
+                        list.push(self.build_move(from, to, Some(promotion)));
+                    }
+                } else {
+                    list.push(self.build_move(from, to, None));
+                }
+            }
+        }
+    }
+
+    fn castling_moves(&self, list: &mut MoveList, occupancy: Bitboard) {
+        let side = self.get_current_player();
+        let (king_side, queen_side, king) = match side {
+            Side::White => (
+                CastlingRight::WhiteKingSide as u8,
+                CastlingRight::WhiteQueenSide as u8,
+                Square::E1,
+            ),
+            Side::Black => (
+                CastlingRight::BlackKingSide as u8,
+                CastlingRight::BlackQueenSide as u8,
+                Square::E8,
+            ),
+        };
+        let attacked = |square: Square| {
+            !self
+                .attackers_to(square, side.other(), occupancy)
+                .is_empty()
+        };
+        if self.game_state.castling & (king_side | queen_side) == 0 || attacked(king) {
+            return;
+        }
+        // The squares between king and rook have to be empty, and the square the king
+        // passes over must not be attacked
+        let empty = |squares: &[Square]| squares.iter().all(|&square| !occupancy.is_set(square));
+        if self.game_state.castling & king_side != 0
+            && empty(&[king.step(1), king.step(2)])
+            && !attacked(king.step(1))
+        {
+            list.push(self.build_move(king, king.step(2), None));
+        }
+        if self.game_state.castling & queen_side != 0
+            && empty(&[king.step(-1), king.step(-2), king.step(-3)])
+            && !attacked(king.step(-1))
+        {
+            list.push(self.build_move(king, king.step(-2), None));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Node counts from the Chess Programming Wiki's perft results page
+    fn check_perft(fen: &str, expected: &[u64]) {
+        let mut board = Board::from_fen(fen).unwrap();
+        let zobrist_key = board.game_state.zobrist_key;
+        for (depth, &nodes) in expected.iter().enumerate() {
+            assert_eq!(
+                board.perft(depth + 1),
+                nodes,
+                "{fen} at depth {}",
+                depth + 1
+            );
+        }
+        assert_eq!(board.game_state.zobrist_key, zobrist_key);
+    }
+
+    #[test]
+    fn perft_matches_reference_counts() {
+        check_perft(
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+            &[20, 400, 8902, 197281],
+        );
+        check_perft(
+            "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+            &[48, 2039, 97862],
+        );
+        check_perft(
+            "8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1",
+            &[14, 191, 2812, 43238],
+        );
+        check_perft(
+            "r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 w kq - 0 1",
+            &[6, 264, 9467],
+        );
+        check_perft(
+            "rnbq1k1r/pp1Pbppp/2p5/8/2B5/8/PPP1NnPP/RNBQK2R w KQ - 1 8",
+            &[44, 1486, 62379],
+        );
+    }
+
+    #[test]
+    fn unmake_restores_the_position() {
+        let mut board =
+            Board::from_fen("r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1")
+                .unwrap();
+        let before = board.clone();
+        for &chess_move in board.legal_moves().iter() {
+            board.make(chess_move);
+            board.unmake(chess_move);
+            assert_eq!(board.bb_pieces, before.bb_pieces, "{chess_move:?}");
+            assert_eq!(board.piece_list, before.piece_list, "{chess_move:?}");
+            assert_eq!(board.game_state.castling, before.game_state.castling);
+            assert_eq!(board.game_state.zobrist_key, before.game_state.zobrist_key);
+            assert_eq!(board.game_state.psqt, before.game_state.psqt);
+        }
+    }
+}
@@ -3,26 +3,36 @@ use std::fmt::Display;
 use crate::{
     board::{
         Board,
-        types::{Piece, Pieces, Square, Side},
+        types::{CastlingRight, CastlingState, Piece, Side, Square},
     },
+    eval::psqt::{PHASE_WEIGHTS, psqt},
     types::{NumOf, SQUARE_MASKS},
 };
 
-use super::types::SQ;
+// Castling rights that are lost once anything moves from or to each square: the king and
+// rook home squares
+const fn castling_rights_lost() -> [CastlingState; NumOf::SQUARES] {
+    let mut lost = [0; NumOf::SQUARES];
+    lost[Square::A1 as usize] = CastlingRight::WhiteQueenSide as u8;
+    lost[Square::H1 as usize] = CastlingRight::WhiteKingSide as u8;
+    lost[Square::E1 as usize] =
+        CastlingRight::WhiteKingSide as u8 | CastlingRight::WhiteQueenSide as u8;
+    lost[Square::A8 as usize] = CastlingRight::BlackQueenSide as u8;
+    lost[Square::H8 as usize] = CastlingRight::BlackKingSide as u8;
+    lost[Square::E8 as usize] =
+        CastlingRight::BlackKingSide as u8 | CastlingRight::BlackQueenSide as u8;
+    lost
+}
+const CASTLING_RIGHTS_LOST: [CastlingState; NumOf::SQUARES] = castling_rights_lost();
 
 impl Board {
-    // TODO: remaining fixes for make()
-    // - Revoke castling rights when a king moves, a rook moves from its home
-    //   square, castling happens, or a rook is captured on its home square.
-    // - Update half_move_clock (reset on pawn move/capture, increment
-    //   otherwise) and fullmove_counter (increment after Black's move).
-    // - XOR the zobrist side-to-move hash in toggle_side() so the key
-    //   reflects whose turn it is.
     pub fn make(&mut self, chess_move: Move) -> () {
         let mut prev_game_state = self.game_state;
+        // Whatever an unmake restored here belongs to the move before
+        prev_game_state.captured_piece = None;
         let from_square: Square = chess_move.from_square();
         let dest_square: Square = chess_move.dest_square();
-        let moved_piece: Piece = self.piece_list[from_square];
+        let moved_piece: Piece = self.piece_on(from_square).expect("No piece to move");
         // TODO: handle this better than with unwrap
         let move_flags = chess_move.flags().unwrap();
 
@@ -33,19 +43,21 @@ impl Board {
             | MoveFlag::BishopCapturePromotion
             | MoveFlag::RookCapturePromotion
             | MoveFlag::QueenCapturePromotion => {
-                let captured_piece: Piece = self.piece_list[dest_square];
+                let captured_piece: Piece = self
+                    .piece_on(dest_square)
+                    .expect("Capture onto an empty square");
                 let captured_piece_color: Side = self.get_opponent();
                 prev_game_state.captured_piece = Some(captured_piece);
                 self.remove_piece(captured_piece, captured_piece_color, dest_square);
             }
             MoveFlag::EpCapture => {
-                prev_game_state.captured_piece = Some(Pieces::PAWN);
+                prev_game_state.captured_piece = Some(Piece::Pawn);
                 let (captured_pawn_square, captured_pawn_color): (Square, Side) =
                     match self.game_state.active_color {
-                        Side::White => (dest_square - NumOf::FILES, Side::Black),
-                        Side::Black => (dest_square + NumOf::FILES, Side::White),
+                        Side::White => (dest_square.step(-8), Side::Black),
+                        Side::Black => (dest_square.step(8), Side::White),
                     };
-                self.remove_piece(Pieces::PAWN, captured_pawn_color, captured_pawn_square);
+                self.remove_piece(Piece::Pawn, captured_pawn_color, captured_pawn_square);
             }
             _ => {}
         }
@@ -68,20 +80,20 @@ impl Board {
         // Promotions
         match move_flags {
             MoveFlag::KnightPromotion | MoveFlag::KnightCapturePromotion => {
-                self.remove_piece(Pieces::PAWN, self.game_state.active_color, dest_square);
-                self.put_piece(Pieces::KNIGHT, self.game_state.active_color, dest_square);
+                self.remove_piece(Piece::Pawn, self.game_state.active_color, dest_square);
+                self.put_piece(Piece::Knight, self.game_state.active_color, dest_square);
             }
             MoveFlag::BishopPromotion | MoveFlag::BishopCapturePromotion => {
-                self.remove_piece(Pieces::PAWN, self.game_state.active_color, dest_square);
-                self.put_piece(Pieces::BISHOP, self.game_state.active_color, dest_square);
+                self.remove_piece(Piece::Pawn, self.game_state.active_color, dest_square);
+                self.put_piece(Piece::Bishop, self.game_state.active_color, dest_square);
             }
             MoveFlag::RookPromotion | MoveFlag::RookCapturePromotion => {
-                self.remove_piece(Pieces::PAWN, self.game_state.active_color, dest_square);
-                self.put_piece(Pieces::ROOK, self.game_state.active_color, dest_square);
+                self.remove_piece(Piece::Pawn, self.game_state.active_color, dest_square);
+                self.put_piece(Piece::Rook, self.game_state.active_color, dest_square);
             }
             MoveFlag::QueenPromotion | MoveFlag::QueenCapturePromotion => {
-                self.remove_piece(Pieces::PAWN, self.game_state.active_color, dest_square);
-                self.put_piece(Pieces::QUEEN, self.game_state.active_color, dest_square);
+                self.remove_piece(Piece::Pawn, self.game_state.active_color, dest_square);
+                self.put_piece(Piece::Queen, self.game_state.active_color, dest_square);
             }
             _ => {}
         }
@@ -89,13 +101,10 @@ impl Board {
         // DoublePawnPush
         match move_flags {
             MoveFlag::DoublePawnPush => {
-                let mut enpassant_square: Square = dest_square;
-                if self.game_state.active_color as Side == Side::White {
-                    enpassant_square -= NumOf::FILES;
-                }
-                if self.game_state.active_color as Side == Side::Black {
-                    enpassant_square += NumOf::FILES;
-                }
+                let enpassant_square: Square = match self.game_state.active_color {
+                    Side::White => dest_square.step(-8),
+                    Side::Black => dest_square.step(8),
+                };
                 self.game_state.set_enpassant(enpassant_square);
                 self.set_enpassant_move(enpassant_square);
             }
@@ -110,59 +119,181 @@ impl Board {
             MoveFlag::KingSideCastle => match self.game_state.active_color {
                 Side::White => {
                     self.move_piece(
-                        Pieces::ROOK,
+                        Piece::Rook,
                         self.game_state.active_color,
-                        SQ::H1 as Square,
-                        dest_square - 1,
+                        Square::H1,
+                        dest_square.step(-1),
                     );
                 }
                 Side::Black => {
                     self.move_piece(
-                        Pieces::ROOK,
+                        Piece::Rook,
                         self.game_state.active_color,
-                        SQ::H8 as Square,
-                        dest_square - 1,
+                        Square::H8,
+                        dest_square.step(-1),
                     );
                 }
             },
             MoveFlag::QueenSideCastle => match self.game_state.active_color {
                 Side::White => {
                     self.move_piece(
-                        Pieces::ROOK,
+                        Piece::Rook,
                         self.game_state.active_color,
-                        SQ::A1 as Square,
-                        dest_square + 1,
+                        Square::A1,
+                        dest_square.step(1),
                     );
                 }
                 Side::Black => {
                     self.move_piece(
-                        Pieces::ROOK,
+                        Piece::Rook,
                         self.game_state.active_color,
-                        SQ::A8 as Square,
-                        dest_square + 1,
+                        Square::A8,
+                        dest_square.step(1),
                     );
                 }
             },
             _ => {}
         }
+
+        let castling = self.game_state.castling
+            & !(CASTLING_RIGHTS_LOST[from_square] | CASTLING_RIGHTS_LOST[dest_square]);
+        if castling != self.game_state.castling {
+            self.game_state.zobrist_key ^= self.zobrist_hashmap.castling(self.game_state.castling);
+            self.game_state.zobrist_key ^= self.zobrist_hashmap.castling(castling);
+            self.game_state.castling = castling;
+        }
+
+        if moved_piece == Piece::Pawn || prev_game_state.captured_piece.is_some() {
+            self.game_state.half_move_clock = 0;
+        } else {
+            self.game_state.half_move_clock = self.game_state.half_move_clock.saturating_add(1);
+        }
+        if self.game_state.active_color == Side::Black {
+            self.game_state.fullmove_counter += 1;
+        }
         self.game_state.toggle_side();
+        self.game_state.zobrist_key ^= self.zobrist_hashmap.side();
     }
 
-    pub fn unmake(&mut self, piece: Piece, from_square: Square, to_square: Square) -> () {
-        todo!()
+    // The move of the piece on `from` to `to`, with the flags the position calls for. A
+    // king moving two files is a castle.
+    pub fn build_move(&self, from: Square, to: Square, promotion: Option<Piece>) -> Move {
+        let piece = self.piece_on(from);
+        let capture = self.bb_sides[self.get_opponent() as usize].is_set(to);
+        let flag = match promotion {
+            Some(promotion) => {
+                let base = match promotion {
+                    Piece::Knight => MoveFlag::KnightPromotion,
+                    Piece::Bishop => MoveFlag::BishopPromotion,
+                    Piece::Rook => MoveFlag::RookPromotion,
+                    _ => MoveFlag::QueenPromotion,
+                } as u8;
+                if capture {
+                    base | MoveFlag::Capture as u8
+                } else {
+                    base
+                }
+            }
+            None if piece == Some(Piece::King) && to.index() == from.index() + 2 => {
+                MoveFlag::KingSideCastle as u8
+            }
+            None if piece == Some(Piece::King) && to.index() + 2 == from.index() => {
+                MoveFlag::QueenSideCastle as u8
+            }
+            None if piece == Some(Piece::Pawn)
+                && self.game_state.enpassant == Some(to)
+                && from.file() != to.file() =>
+            {
+                MoveFlag::EpCapture as u8
+            }
+            None if piece == Some(Piece::Pawn)
+                && from.index().abs_diff(to.index()) == 2 * NumOf::FILES =>
+            {
+                MoveFlag::DoublePawnPush as u8
+            }
+            None if capture => MoveFlag::Capture as u8,
+            None => MoveFlag::Quiet as u8,
+        };
+        Move::new(from, to, flag)
     }
+
+    // Takes back `chess_move`, which has to be the last move made. The pieces are moved
+    // back and the rest of the state (hashes, psqt, clocks, rights) comes from the history.
+    pub fn unmake(&mut self, chess_move: Move) {
+        let prev_game_state = self
+            .history
+            .pop()
+            .expect("unmake called without a move to take back");
+        let side = prev_game_state.active_color;
+        let from_square = chess_move.from_square();
+        let dest_square = chess_move.dest_square();
+        let move_flags = chess_move.flags().unwrap();
+
+        match move_flags {
+            MoveFlag::KingSideCastle => {
+                self.move_piece(Piece::Rook, side, dest_square.step(-1), dest_square.step(1))
+            }
+            MoveFlag::QueenSideCastle => {
+                self.move_piece(Piece::Rook, side, dest_square.step(1), dest_square.step(-2))
+            }
+            MoveFlag::KnightPromotion
+            | MoveFlag::BishopPromotion
+            | MoveFlag::RookPromotion
+            | MoveFlag::QueenPromotion
+            | MoveFlag::KnightCapturePromotion
+            | MoveFlag::BishopCapturePromotion
+            | MoveFlag::RookCapturePromotion
+            | MoveFlag::QueenCapturePromotion => {
+                let promoted = self
+                    .piece_on(dest_square)
+                    .expect("Promoted piece is missing");
+                self.remove_piece(promoted, side, dest_square);
+                self.put_piece(Piece::Pawn, side, dest_square);
+            }
+            _ => {}
+        }
+
+        let moved_piece = self.piece_on(dest_square).expect("Moved piece is missing");
+        self.move_piece(moved_piece, side, dest_square, from_square);
+
+        if let Some(captured_piece) = prev_game_state.captured_piece {
+            let captured_square = match (move_flags, side) {
+                (MoveFlag::EpCapture, Side::White) => dest_square.step(-8),
+                (MoveFlag::EpCapture, Side::Black) => dest_square.step(8),
+                _ => dest_square,
+            };
+            self.put_piece(captured_piece, side.other(), captured_square);
+        }
+
+        self.game_state = prev_game_state;
+        // The accumulator stack pops back to the previous ply instead of replaying these
+        self.feature_updates.take();
+    }
+
     pub fn put_piece(&mut self, piece: Piece, side: Side, square_idx: Square) {
         self.bb_pieces[side as usize][piece] |= SQUARE_MASKS[square_idx];
         self.bb_sides[side as usize] |= SQUARE_MASKS[square_idx];
-        self.piece_list[square_idx] = piece;
-        self.game_state.zobrist_key ^= self.zobrist_hashmap.piece(side, piece, square_idx)
+        self.piece_list[square_idx] = Some(side.piece(piece));
+        self.game_state.zobrist_key ^= self.zobrist_hashmap.piece(side, piece, square_idx);
+        if piece == Piece::Pawn || piece == Piece::King {
+            self.game_state.pawn_key ^= self.zobrist_hashmap.piece(side, piece, square_idx);
+        }
+        self.game_state.psqt[side as usize] += psqt(&self.params.psqt, side, piece, square_idx);
+        self.game_state.phase += PHASE_WEIGHTS[piece];
+        self.feature_updates.add(side, piece, square_idx);
     }
 
     pub fn remove_piece(&mut self, piece: Piece, side: Side, square_idx: Square) {
         self.bb_pieces[side as usize][piece] &= !SQUARE_MASKS[square_idx];
         self.bb_sides[side as usize] &= !SQUARE_MASKS[square_idx];
-        self.piece_list[square_idx] = Pieces::NONE;
+        self.piece_list[square_idx] = None;
         self.game_state.zobrist_key ^= self.zobrist_hashmap.piece(side, piece, square_idx);
+        if piece == Piece::Pawn || piece == Piece::King {
+            self.game_state.pawn_key ^= self.zobrist_hashmap.piece(side, piece, square_idx);
+        }
+        self.game_state.psqt[side as usize] -= psqt(&self.params.psqt, side, piece, square_idx);
+        self.game_state.phase -= PHASE_WEIGHTS[piece];
+        self.feature_updates.remove(side, piece, square_idx);
     }
 
     pub fn move_piece(
@@ -177,12 +308,11 @@ impl Board {
     }
 
     pub fn set_enpassant_move(&mut self, square: Square) {
-        let file = (square % 8) as usize;
-        self.game_state.zobrist_key ^= self.zobrist_hashmap.enpassant(file);
+        self.game_state.zobrist_key ^= self.zobrist_hashmap.enpassant(square.file());
     }
 }
 
-#[derive(Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct Move(u16);
 
 impl Move {
@@ -194,26 +324,40 @@ impl Move {
 
     pub fn new(from_square: Square, dest_square: Square, flags: u8) -> Self {
         let chess_move: u16 = from_square as u16
-            | (dest_square << Self::DEST_SQUARE_BIT_SHIFT) as u16
-            | (flags << Self::FLAGS_BIT_SHIFT) as u16;
+            | (dest_square as u16) << Self::DEST_SQUARE_BIT_SHIFT
+            | (flags as u16) << Self::FLAGS_BIT_SHIFT;
         Move(chess_move)
     }
     pub fn from_square(&self) -> Square {
-        (self.0 & Self::FROM_SQUARE_MASK) as Square
+        Square::from_index((self.0 & Self::FROM_SQUARE_MASK) as usize)
     }
 
     pub fn dest_square(&self) -> Square {
-        (self.0 & Self::DEST_SQUARE_MASK) as Square
+        Square::from_index(
+            ((self.0 & Self::DEST_SQUARE_MASK) >> Self::DEST_SQUARE_BIT_SHIFT) as usize,
+        )
     }
 
     pub fn flags(&self) -> Result<MoveFlag, InvalidMoveFlag> {
-        let move_flag_values = (self.0 & Self::FLAGS_MASK) as u8;
+        let move_flag_values = ((self.0 & Self::FLAGS_MASK) >> Self::FLAGS_BIT_SHIFT) as u8;
         MoveFlag::try_from(move_flag_values)
     }
 }
 
+// Long algebraic notation as UCI uses it, such as e2e4 or e7e8q
+impl Display for Move {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}{}", self.from_square(), self.dest_square())?;
+        let flags = ((self.0 & Self::FLAGS_MASK) >> Self::FLAGS_BIT_SHIFT) as u8;
+        if flags & MoveFlag::KnightPromotion as u8 != 0 {
+            write!(f, "{}", ['n', 'b', 'r', 'q'][(flags & 0b11) as usize])?;
+        }
+        Ok(())
+    }
+}
+
 #[repr(u8)]
-#[derive(Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub enum MoveFlag {
     Quiet = 0b0000,
     DoublePawnPush = 0b0001,
@@ -262,7 +406,7 @@ impl TryFrom<u8> for MoveFlag {
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, PartialEq)]
 pub struct InvalidMoveFlag {
     invalid_flag_state: u8,
 }
@@ -276,3 +420,57 @@ impl Display for InvalidMoveFlag {
         )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn moves_display_in_uci_notation() {
+        assert_eq!(
+            Move::new(Square::E2, Square::E4, MoveFlag::DoublePawnPush as u8).to_string(),
+            "e2e4"
+        );
+        assert_eq!(
+            Move::new(
+                Square::E7,
+                Square::F8,
+                MoveFlag::KnightCapturePromotion as u8
+            )
+            .to_string(),
+            "e7f8n"
+        );
+        assert_eq!(
+            Move::new(Square::E8, Square::G8, MoveFlag::KingSideCastle as u8).to_string(),
+            "e8g8"
+        );
+    }
+
+    #[test]
+    fn make_keeps_state_in_line_with_fen_setup() {
+        let mut board = Board::init();
+        board.fen_setup(None).unwrap();
+        // 1. e4 e5 2. Nf3 Nc6 3. Ke2
+        for (from, to) in [
+            (Square::E2, Square::E4),
+            (Square::E7, Square::E5),
+            (Square::G1, Square::F3),
+            (Square::B8, Square::C6),
+            (Square::E1, Square::E2),
+        ] {
+            let chess_move = board.build_move(from, to, None);
+            board.make(chess_move);
+        }
+        let expected =
+            Board::from_fen("r1bqkbnr/pppp1ppp/2n5/4p3/4P3/5N2/PPPPKPPP/RNBQ1B1R b kq - 3 3")
+                .unwrap();
+        assert_eq!(board.game_state.castling, expected.game_state.castling);
+        assert_eq!(board.game_state.half_move_clock, 3);
+        assert_eq!(board.game_state.fullmove_counter, 3);
+        assert_eq!(
+            board.game_state.zobrist_key,
+            expected.game_state.zobrist_key
+        );
+        assert_eq!(board.game_state.pawn_key, expected.game_state.pawn_key);
+    }
+}
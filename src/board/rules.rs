@@ -0,0 +1,127 @@
+use crate::{
+    board::{
+        Board,
+        types::{FIFTY_MOVE_RULE, Piece, Side},
+    },
+    types::{EMPTY_BITBOARD, LIGHT_SQUARES},
+};
+
+// Why a game is over by the rules alone
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum GameEnd {
+    // The side to move is mated, every other ending is a draw
+    Checkmate,
+    Stalemate,
+    FiftyMoves,
+    Repetition,
+    InsufficientMaterial,
+}
+
+impl Board {
+    pub fn game_end(&self) -> Option<GameEnd> {
+        if self.legal_moves().is_empty() {
+            return Some(if self.in_check() {
+                GameEnd::Checkmate
+            } else {
+                GameEnd::Stalemate
+            });
+        }
+        if self.game_state.half_move_clock >= FIFTY_MOVE_RULE {
+            Some(GameEnd::FiftyMoves)
+        } else if self.repetitions() >= 2 {
+            Some(GameEnd::Repetition)
+        } else if self.has_insufficient_material() {
+            Some(GameEnd::InsufficientMaterial)
+        } else {
+            None
+        }
+    }
+
+    // Earlier occurrences of the current position since the last capture or pawn move
+    pub fn repetitions(&self) -> usize {
+        let plies = (self.game_state.half_move_clock as usize).min(self.history.len());
+        (2..=plies)
+            .step_by(2)
+            .filter(|&ply| {
+                let state = self.history.get(self.history.len() - ply);
+                state.zobrist_key == self.game_state.zobrist_key
+            })
+            .count()
+    }
+
+    // Neither side can ever mate: bare kings, a single minor piece, or bishops that all
+    // stand on squares of one colour
+    pub fn has_insufficient_material(&self) -> bool {
+        let mut minors = 0;
+        let mut bishops = EMPTY_BITBOARD;
+        for side in [Side::White, Side::Black] {
+            let pieces = &self.bb_pieces[side as usize];
+            if !(pieces[Piece::Pawn] | pieces[Piece::Rook] | pieces[Piece::Queen]).is_empty() {
+                return false;
+            }
+            minors += (pieces[Piece::Knight] | pieces[Piece::Bishop]).popcount();
+            bishops |= pieces[Piece::Bishop];
+        }
+        minors <= 1
+            || (bishops.popcount() == minors
+                && ((bishops & LIGHT_SQUARES).is_empty() || (bishops & !LIGHT_SQUARES).is_empty()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::types::Square;
+
+    #[test]
+    fn detects_the_ways_a_game_ends() {
+        let mut board = Board::init();
+        let mut game_end = |fen: &str| {
+            board.fen_setup(Some(fen)).unwrap();
+            board.game_end()
+        };
+        let cases = [
+            (
+                "R5k1/5ppp/8/8/8/8/8/6K1 b - - 0 1",
+                Some(GameEnd::Checkmate),
+            ),
+            ("7k/5Q2/6K1/8/8/8/8/8 b - - 0 1", Some(GameEnd::Stalemate)),
+            (
+                "7k/8/6K1/8/8/8/8/R7 b - - 100 80",
+                Some(GameEnd::FiftyMoves),
+            ),
+            ("7k/8/6K1/8/8/8/8/BB6 b - - 0 1", None),
+            (
+                "7k/8/6K1/8/8/8/1b6/B7 b - - 0 1",
+                Some(GameEnd::InsufficientMaterial),
+            ),
+            ("7k/8/6K1/8/8/8/8/Bb6 b - - 0 1", None),
+            (
+                "7k/8/6K1/8/8/8/8/N7 b - - 0 1",
+                Some(GameEnd::InsufficientMaterial),
+            ),
+        ];
+        for (fen, expected) in cases {
+            assert_eq!(game_end(fen), expected, "{fen}");
+        }
+
+        board
+            .fen_setup(Some("7k/8/6K1/8/8/8/8/R7 w - - 0 1"))
+            .unwrap();
+        // Ra2 Kg8 Ra1 Kh8 twice over
+        for _ in 0..2 {
+            assert_eq!(board.game_end(), None);
+            for (from, to) in [
+                (Square::A1, Square::A2),
+                (Square::H8, Square::G8),
+                (Square::A2, Square::A1),
+                (Square::G8, Square::H8),
+            ] {
+                let chess_move = board.build_move(from, to, None);
+                board.make(chess_move);
+            }
+        }
+        assert_eq!(board.repetitions(), 2);
+        assert_eq!(board.game_end(), Some(GameEnd::Repetition));
+    }
+}
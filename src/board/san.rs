@@ -0,0 +1,368 @@
+use std::fmt::Display;
+
+use crate::{
+    board::{
+        Board,
+        piece_movement::{Move, MoveFlag},
+        types::{Piece, Side, Square},
+    },
+    movement::ATTACKS,
+    types::{Bitboard, FILE_MASKS, FULL_BITBOARD, RANK_MASKS, SQUARE_MASKS},
+};
+
+#[derive(Debug, PartialEq)]
+pub enum SanError {
+    Malformed(String),
+    NoMatchingMove(String),
+    Ambiguous(String),
+}
+
+impl Display for SanError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SanError::Malformed(san) => write!(f, "{san:?} is not a valid SAN move"),
+            SanError::NoMatchingMove(san) => write!(f, "{san:?} matches no legal move"),
+            SanError::Ambiguous(san) => write!(f, "{san:?} matches more than one legal move"),
+        }
+    }
+}
+
+fn parse_square(file: u8, rank: u8) -> Option<Square> {
+    if !(b'a'..=b'h').contains(&file) || !(b'1'..=b'8').contains(&rank) {
+        return None;
+    }
+    Some(Square::from_coords(
+        (file - b'a') as usize,
+        (rank - b'1') as usize,
+    ))
+}
+
+// Pawns have no letter in SAN
+fn parse_piece(letter: u8) -> Option<Piece> {
+    Piece::from_char(letter as char).filter(|&piece| piece != Piece::Pawn)
+}
+
+impl Board {
+    // Pieces of `by` attacking `square` when the board holds `occupancy`
+    pub fn attackers_to(&self, square: Square, by: Side, occupancy: Bitboard) -> Bitboard {
+        let pieces = &self.bb_pieces[by as usize];
+        let diagonal = pieces[Piece::Bishop] | pieces[Piece::Queen];
+        let straight = pieces[Piece::Rook] | pieces[Piece::Queen];
+        (ATTACKS.knight_attacks[square] & pieces[Piece::Knight])
+            | (ATTACKS.king_attacks[square] & pieces[Piece::King])
+            | (ATTACKS.pawn_attacks[by.other()][square] & pieces[Piece::Pawn])
+            | (ATTACKS.get_bishop_attacks(square, occupancy) & diagonal)
+            | (ATTACKS.get_rook_attacks(square, occupancy) & straight)
+    }
+
+    // Whether `chess_move` leaves the mover's king out of check. The move itself has to
+    // be pseudo-legal.
+    pub fn is_legal(&self, chess_move: Move) -> bool {
+        let side = self.get_current_player();
+        let them = side.other();
+        let from = chess_move.from_square();
+        let to = chess_move.dest_square();
+        let occupancy = self.bb_sides[Side::White as usize] | self.bb_sides[Side::Black as usize];
+        let mut occupancy = (occupancy & !SQUARE_MASKS[from]) | SQUARE_MASKS[to];
+        let mut captured = SQUARE_MASKS[to];
+        if chess_move.flags() == Ok(MoveFlag::EpCapture) {
+            let captured_square = match side {
+                Side::White => to.step(-8),
+                Side::Black => to.step(8),
+            };
+            occupancy &= !SQUARE_MASKS[captured_square];
+            captured = SQUARE_MASKS[captured_square];
+        }
+        let king = if self.piece_on(from) == Some(Piece::King) {
+            to
+        } else {
+            self.bb_pieces[side as usize][Piece::King].lsb()
+        };
+        (self.attackers_to(king, them, occupancy) & !captured).is_empty()
+    }
+
+    // Resolves a move in standard algebraic notation, such as `Nbd7`, `exd6`, `e8=Q+` or
+    // `O-O`, against the legal moves of the position
+    pub fn parse_san(&self, san: &str) -> Result<Move, SanError> {
+        let malformed = || SanError::Malformed(san.to_string());
+        let text = san.trim_end_matches(['+', '#', '!', '?']);
+        let legal_moves = self.legal_moves();
+        let matching = |found: Vec<Move>| match found[..] {
+            [chess_move] => Ok(chess_move),
+            [] => Err(SanError::NoMatchingMove(san.to_string())),
+            _ => Err(SanError::Ambiguous(san.to_string())),
+        };
+
+        let castle = match text {
+            "O-O" | "0-0" => Some(MoveFlag::KingSideCastle),
+            "O-O-O" | "0-0-0" => Some(MoveFlag::QueenSideCastle),
+            _ => None,
+        };
+        if let Some(castle) = castle {
+            return matching(
+                legal_moves
+                    .iter()
+                    .copied()
+                    .filter(|chess_move| chess_move.flags() == Ok(castle))
+                    .collect(),
+            );
+        }
+
+        let mut bytes: Vec<u8> = text.bytes().filter(|&b| b != b'x' && b != b'-').collect();
+        let mut promotion = None;
+        if let Some(&last) = bytes.last()
+            && let Some(piece) = parse_piece(last)
+        {
+            promotion = Some(piece);
+            bytes.pop();
+            if bytes.last() == Some(&b'=') {
+                bytes.pop();
+            }
+        }
+        let (piece, bytes) = match bytes.first().copied().and_then(parse_piece) {
+            Some(piece) => (piece, &bytes[1..]),
+            None => (Piece::Pawn, &bytes[..]),
+        };
+        if bytes.len() < 2 {
+            return Err(malformed());
+        }
+        let (hint, dest) = bytes.split_at(bytes.len() - 2);
+        let to = parse_square(dest[0], dest[1]).ok_or_else(malformed)?;
+        // A pawn capture always names the file it starts on, so a bare pawn move is a push
+        let mut from_mask = if piece == Piece::Pawn && hint.is_empty() {
+            FILE_MASKS[to.file()]
+        } else {
+            FULL_BITBOARD
+        };
+        for &c in hint {
+            from_mask &= match c {
+                b'a'..=b'h' => FILE_MASKS[(c - b'a') as usize],
+                b'1'..=b'8' => RANK_MASKS[(c - b'1') as usize],
+                _ => return Err(malformed()),
+            };
+        }
+
+        matching(
+            legal_moves
+                .iter()
+                .copied()
+                .filter(|chess_move| {
+                    let from = chess_move.from_square();
+                    chess_move.dest_square() == to
+                        && from_mask.is_set(from)
+                        && self.piece_on(from) == Some(piece)
+                        && *chess_move == self.build_move(from, to, promotion)
+                })
+                .collect(),
+        )
+    }
+
+    // Standard algebraic notation for a legal move, with the check or mate suffix
+    pub fn to_san(&mut self, chess_move: Move) -> String {
+        let from = chess_move.from_square();
+        let to = chess_move.dest_square();
+        let flags = chess_move.flags().unwrap();
+        let piece = self.piece_on(from).expect("No piece to move");
+        let capture = matches!(
+            flags,
+            MoveFlag::Capture
+                | MoveFlag::EpCapture
+                | MoveFlag::KnightCapturePromotion
+                | MoveFlag::BishopCapturePromotion
+                | MoveFlag::RookCapturePromotion
+                | MoveFlag::QueenCapturePromotion
+        );
+
+        let mut san = match flags {
+            MoveFlag::KingSideCastle => String::from("O-O"),
+            MoveFlag::QueenSideCastle => String::from("O-O-O"),
+            _ if piece == Piece::Pawn => {
+                let mut san = String::new();
+                if capture {
+                    san.push_str(&from.to_string()[..1]);
+                    san.push('x');
+                }
+                san.push_str(&to.to_string());
+                if let Some(letter) = chess_move.to_string().chars().nth(4) {
+                    san.push('=');
+                    san.push(letter.to_ascii_uppercase());
+                }
+                san
+            }
+            _ => {
+                let mut san = String::from(piece.to_char());
+                // Name the file, the rank or both when another piece of the same kind can
+                // go to the same square
+                let rivals: Vec<Square> = self
+                    .legal_moves()
+                    .iter()
+                    .filter(|other| {
+                        other.dest_square() == to
+                            && other.from_square() != from
+                            && self.piece_on(other.from_square()) == Some(piece)
+                    })
+                    .map(|other| other.from_square())
+                    .collect();
+                let from_name = from.to_string();
+                if !rivals.is_empty() {
+                    if rivals.iter().all(|&rival| rival.file() != from.file()) {
+                        san.push_str(&from_name[..1]);
+                    } else if rivals.iter().all(|&rival| rival.rank() != from.rank()) {
+                        san.push_str(&from_name[1..]);
+                    } else {
+                        san.push_str(&from_name);
+                    }
+                }
+                if capture {
+                    san.push('x');
+                }
+                san.push_str(&to.to_string());
+                san
+            }
+        };
+
+        self.make(chess_move);
+        if self.in_check() {
+            san.push(if self.legal_moves().is_empty() {
+                '#'
+            } else {
+                '+'
+            });
+        }
+        self.unmake(chess_move);
+        san
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn squares(chess_move: Move) -> (Square, Square) {
+        (chess_move.from_square(), chess_move.dest_square())
+    }
+
+    #[test]
+    fn resolves_pieces_pawns_and_castling() {
+        let board =
+            Board::from_fen("r3k2r/pppq1ppp/2n2n2/3pp3/3PPN2/2N5/PPPQ1PPP/R3K2R w KQkq - 0 1")
+                .unwrap();
+        assert_eq!(
+            squares(board.parse_san("Nb5").unwrap()),
+            (Square::C3, Square::B5)
+        );
+        assert_eq!(
+            board.parse_san("Nd5"),
+            Err(SanError::Ambiguous("Nd5".to_string()))
+        );
+        assert_eq!(
+            squares(board.parse_san("Nfxd5").unwrap()),
+            (Square::F4, Square::D5)
+        );
+        assert_eq!(
+            squares(board.parse_san("exd5").unwrap()),
+            (Square::E4, Square::D5)
+        );
+        assert_eq!(
+            squares(board.parse_san("a4").unwrap()),
+            (Square::A2, Square::A4)
+        );
+        assert_eq!(
+            board.parse_san("a4").unwrap().flags(),
+            Ok(MoveFlag::DoublePawnPush)
+        );
+        let castle = board.parse_san("O-O-O").unwrap();
+        assert_eq!(squares(castle), (Square::E1, Square::C1));
+        assert_eq!(castle.flags(), Ok(MoveFlag::QueenSideCastle));
+        assert!(board.parse_san("Nc4").is_err());
+        assert!(board.parse_san("Zz9").is_err());
+    }
+
+    #[test]
+    fn castling_has_to_be_legal() {
+        let board =
+            Board::from_fen("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1").unwrap();
+        assert_eq!(
+            board.parse_san("O-O"),
+            Err(SanError::NoMatchingMove("O-O".to_string()))
+        );
+        // The f1 square is attacked, so only the queen side is open
+        let board = Board::from_fen("r3k2r/8/8/8/8/8/5r2/R3K2R w KQkq - 0 1").unwrap();
+        assert!(board.parse_san("O-O").is_err());
+        assert_eq!(
+            board.parse_san("O-O-O").map(|castle| castle.flags()),
+            Ok(Ok(MoveFlag::QueenSideCastle))
+        );
+    }
+
+    #[test]
+    fn pinned_pieces_do_not_make_moves_ambiguous() {
+        // The c3 knight is pinned by the bishop, so Ne4 can only be the g5 knight
+        let board = Board::from_fen("4k3/8/5b2/6N1/8/2N5/8/K7 w - - 0 1").unwrap();
+        assert_eq!(
+            squares(board.parse_san("Ne4").unwrap()),
+            (Square::G5, Square::E4)
+        );
+    }
+
+    #[test]
+    fn promotions_and_en_passant() {
+        let board = Board::from_fen("1n2k3/P7/8/3pP3/8/8/8/4K3 w - d6 0 1").unwrap();
+        let ep = board.parse_san("exd6").unwrap();
+        assert_eq!(
+            (squares(ep), ep.flags()),
+            ((Square::E5, Square::D6), Ok(MoveFlag::EpCapture))
+        );
+        let promotion = board.parse_san("axb8=Q+").unwrap();
+        assert_eq!(promotion.flags(), Ok(MoveFlag::QueenCapturePromotion));
+        let promotion = board.parse_san("a8N").unwrap();
+        assert_eq!(promotion.flags(), Ok(MoveFlag::KnightPromotion));
+    }
+
+    #[test]
+    fn writes_san_that_parses_back() {
+        let mut board =
+            Board::from_fen("r3k2r/pppq1ppp/2n2n2/3pp3/3PPN2/2N5/PPPQ1PPP/R3K2R w KQkq - 0 1")
+                .unwrap();
+        let write = |board: &mut Board, from: Square, to: Square| {
+            let chess_move = board.build_move(from, to, None);
+            board.to_san(chess_move)
+        };
+        assert_eq!(write(&mut board, Square::F4, Square::D5), "Nfxd5");
+        assert_eq!(write(&mut board, Square::C3, Square::B5), "Nb5");
+        assert_eq!(write(&mut board, Square::E4, Square::D5), "exd5");
+        assert_eq!(write(&mut board, Square::E1, Square::C1), "O-O-O");
+
+        let mut board = Board::from_fen("1n2k3/P7/8/8/8/8/8/R3K3 w - - 0 1").unwrap();
+        let promotion = board.build_move(Square::A7, Square::B8, Some(Piece::Queen));
+        assert_eq!(board.to_san(promotion), "axb8=Q+");
+        assert_eq!(write(&mut board, Square::A1, Square::D1), "Rd1");
+        for chess_move in board.legal_moves().iter() {
+            let san = board.to_san(*chess_move);
+            assert_eq!(board.parse_san(&san), Ok(*chess_move), "{san}");
+        }
+
+        let mut board = Board::from_fen("6k1/5ppp/8/8/8/8/8/R5K1 w - - 0 1").unwrap();
+        assert_eq!(write(&mut board, Square::A1, Square::A8), "Ra8#");
+    }
+
+    #[test]
+    fn replays_a_whole_game() {
+        let mut board =
+            Board::from_fen("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1").unwrap();
+        let moves = "e4 e5 Nf3 d6 d4 Bg4 dxe5 Bxf3 Qxf3 dxe5 Bc4 Nf6 Qb3 Qe7 Nc3 c6 Bg5 b5 Nxb5 \
+                     cxb5 Bxb5+ Nbd7 O-O-O Rd8 Rxd7 Rxd7 Rd1 Qe6 Bxd7+ Nxd7 Qb8+ Nxb8 Rd8#";
+        for san in moves.split_whitespace() {
+            let chess_move = board.parse_san(san).unwrap();
+            board.make(chess_move);
+        }
+        let expected =
+            Board::from_fen("1n1Rkb1r/p4ppp/4q3/4p1B1/4P3/8/PPP2PPP/2K5 b k - 1 17").unwrap();
+        assert_eq!(board.bb_pieces, expected.bb_pieces);
+        assert_eq!(board.game_state.castling, expected.game_state.castling);
+        assert_eq!(
+            board.game_state.zobrist_key,
+            expected.game_state.zobrist_key
+        );
+    }
+}
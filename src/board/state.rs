@@ -1,8 +1,10 @@
-use crate::board::Piece;
 use crate::board::types::CastlingRight;
+use crate::board::types::Piece;
 use crate::board::types::{CastlingState, Side, Square};
 
 use crate::board::zobrist::ZobristKey;
+use crate::eval::score::Score;
+use crate::types::NumOf;
 
 #[derive(Clone, Copy, Debug)]
 pub struct GameState {
@@ -13,6 +15,12 @@ pub struct GameState {
     pub half_move_clock: u8,
     pub fullmove_counter: u16,
     pub zobrist_key: ZobristKey,
+    // Only hashes the pawns and kings, used to index the pawn hash table
+    pub pawn_key: ZobristKey,
+    // Material + piece-square sums per side and the game phase, kept up to date by
+    // put_piece/remove_piece so the evaluation never has to rescan the bitboards
+    pub psqt: [Score; NumOf::SIDES],
+    pub phase: i32,
 }
 impl GameState {
     pub fn new() -> Self {
@@ -29,6 +37,9 @@ impl GameState {
             half_move_clock: 0,
             fullmove_counter: 1,
             zobrist_key: 0,
+            pawn_key: 0,
+            psqt: [Score::ZERO; NumOf::SIDES],
+            phase: 0,
         }
     }
     pub fn revoke_right(&mut self, right: CastlingRight) {
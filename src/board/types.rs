@@ -1,9 +1,10 @@
 use std::{
     fmt::Display,
     ops::{Index, IndexMut},
+    str::FromStr,
 };
 
-use crate::types::NumOf;
+use crate::types::{Bitboard, NumOf};
 use num_enum::TryFromPrimitive;
 
 pub const MAX_GAME_MOVES: u64 = 2048;
@@ -11,47 +12,153 @@ pub const MAX_GAME_MOVES: u64 = 2048;
 // 50 full moves equates to 100 half moves
 pub const FIFTY_MOVE_RULE: u8 = 100;
 
-pub type Piece = usize;
-pub type Square = usize;
 pub type CastlingState = u8;
 
-#[repr(usize)]
+#[repr(u8)]
 #[rustfmt::skip]
-#[derive(Copy, Clone, PartialEq, Eq, Ord, PartialOrd, Debug)]
-pub enum SQ {
-    A1, A2, A3, A4, A5, A6, A7, A8,
-    B1, B2, B3, B4, B5, B6, B7, B8,
-    C1, C2, C3, C4, C5, C6, C7, C8,
-    D1, D2, D3, D4, D5, D6, D7, D8,
-    E1, E2, E3, E4, E5, E6, E7, E8,
-    F1, F2, F3, F4, F5, F6, F7, F8,
-    G1, G2, G3, G4, G5, G6, G7, G8,
-    H1, H2, H3, H4, H5, H6, H7, H8,
-}
-
-impl From<usize> for SQ {
+#[derive(Copy, Clone, PartialEq, Eq, Ord, PartialOrd, Hash, Debug)]
+pub enum Square {
+    A1, B1, C1, D1, E1, F1, G1, H1,
+    A2, B2, C2, D2, E2, F2, G2, H2,
+    A3, B3, C3, D3, E3, F3, G3, H3,
+    A4, B4, C4, D4, E4, F4, G4, H4,
+    A5, B5, C5, D5, E5, F5, G5, H5,
+    A6, B6, C6, D6, E6, F6, G6, H6,
+    A7, B7, C7, D7, E7, F7, G7, H7,
+    A8, B8, C8, D8, E8, F8, G8, H8,
+}
+
+impl Square {
+    // In index order, so ALL[sq.index()] == sq
     #[rustfmt::skip]
+    pub const ALL: [Square; NumOf::SQUARES] = [
+        Square::A1, Square::B1, Square::C1, Square::D1, Square::E1, Square::F1, Square::G1, Square::H1,
+        Square::A2, Square::B2, Square::C2, Square::D2, Square::E2, Square::F2, Square::G2, Square::H2,
+        Square::A3, Square::B3, Square::C3, Square::D3, Square::E3, Square::F3, Square::G3, Square::H3,
+        Square::A4, Square::B4, Square::C4, Square::D4, Square::E4, Square::F4, Square::G4, Square::H4,
+        Square::A5, Square::B5, Square::C5, Square::D5, Square::E5, Square::F5, Square::G5, Square::H5,
+        Square::A6, Square::B6, Square::C6, Square::D6, Square::E6, Square::F6, Square::G6, Square::H6,
+        Square::A7, Square::B7, Square::C7, Square::D7, Square::E7, Square::F7, Square::G7, Square::H7,
+        Square::A8, Square::B8, Square::C8, Square::D8, Square::E8, Square::F8, Square::G8, Square::H8,
+    ];
+
     #[inline(always)]
-    fn from(value: usize) -> Self {
-        match value {
-            0 => SQ::A1, 1 => SQ::B1, 2 => SQ::C1, 3 => SQ::D1,
-            4 => SQ::E1, 5 => SQ::F1, 6 => SQ::G1, 7 => SQ::H1,
-            8 => SQ::A2, 9 => SQ::B2, 10 => SQ::C2, 11 => SQ::D2,
-            12 => SQ::E2, 13 => SQ::F2, 14 => SQ::G2, 15 => SQ::H2,
-            16 => SQ::A3, 17 => SQ::B3, 18 => SQ::C3, 19 => SQ::D3,
-            20 => SQ::E3, 21 => SQ::F3, 22 => SQ::G3, 23 => SQ::H3,
-            24 => SQ::A4, 25 => SQ::B4, 26 => SQ::C4, 27 => SQ::D4,
-            28 => SQ::E4, 29 => SQ::F4, 30 => SQ::G4, 31 => SQ::H4,
-            32 => SQ::A5, 33 => SQ::B5, 34 => SQ::C5, 35 => SQ::D5,
-            36 => SQ::E5, 37 => SQ::F5, 38 => SQ::G5, 39 => SQ::H5,
-            40 => SQ::A6, 41 => SQ::B6, 42 => SQ::C6, 43 => SQ::D6,
-            44 => SQ::E6, 45 => SQ::F6, 46 => SQ::G6, 47 => SQ::H6,
-            48 => SQ::A7, 49 => SQ::B7, 50 => SQ::C7, 51 => SQ::D7,
-            52 => SQ::E7, 53 => SQ::F7, 54 => SQ::G7, 55 => SQ::H7,
-            56 => SQ::A8, 57 => SQ::B8, 58 => SQ::C8, 59 => SQ::D8,
-            60 => SQ::E8, 61 => SQ::F8, 62 => SQ::G8, 63 => SQ::H8,
-            _ => panic!("unable to convert usize value {} to SQ enum variant", value),
+    pub const fn index(self) -> usize {
+        self as usize
+    }
+
+    // Panics past H8, untrusted input should go through TryFrom instead
+    #[inline(always)]
+    pub const fn from_index(index: usize) -> Self {
+        Self::ALL[index]
+    }
+
+    #[inline(always)]
+    pub const fn from_coords(file: usize, rank: usize) -> Self {
+        debug_assert!(file < NumOf::FILES && rank < NumOf::RANKS);
+        Self::from_index(rank * NumOf::FILES + file)
+    }
+
+    #[inline(always)]
+    pub const fn file(self) -> usize {
+        self as usize % NumOf::FILES
+    }
+
+    #[inline(always)]
+    pub const fn rank(self) -> usize {
+        self as usize / NumOf::FILES
+    }
+
+    // The same square seen from the other side of the board, e.g. e2 <-> e7
+    #[inline(always)]
+    pub const fn flip_rank(self) -> Self {
+        Self::from_index(self as usize ^ 56)
+    }
+
+    // a <-> h, b <-> g and so on
+    #[inline(always)]
+    pub const fn flip_file(self) -> Self {
+        Self::from_index(self as usize ^ 7)
+    }
+
+    // `delta` indices further on, e.g. +8 is one rank up. Callers make sure the result is
+    // still on the board.
+    #[inline(always)]
+    pub const fn step(self, delta: isize) -> Self {
+        Self::from_index(self.index().wrapping_add_signed(delta))
+    }
+
+    // The square one step along `direction`, or None when that leaves the board
+    #[inline(always)]
+    pub const fn offset(self, direction: Direction) -> Option<Self> {
+        let file = self.file() as i8 + direction.0;
+        let rank = self.rank() as i8 + direction.1;
+        if file < 0 || file >= NumOf::FILES as i8 || rank < 0 || rank >= NumOf::RANKS as i8 {
+            return None;
         }
+        Some(Self::from_coords(file as usize, rank as usize))
+    }
+
+    #[inline(always)]
+    pub const fn bitboard(self) -> Bitboard {
+        Bitboard::from_square(self)
+    }
+}
+
+impl TryFrom<usize> for Square {
+    type Error = BoardError;
+
+    fn try_from(value: usize) -> Result<Self, Self::Error> {
+        Self::ALL
+            .get(value)
+            .copied()
+            .ok_or(BoardError::SquareOutOfBound)
+    }
+}
+
+impl FromStr for Square {
+    type Err = BoardError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut chars = s.chars();
+        let (Some(file), Some(rank), None) = (chars.next(), chars.next(), chars.next()) else {
+            return Err(BoardError::SquareOutOfBound);
+        };
+        if !('a'..='h').contains(&file) {
+            return Err(BoardError::FileOutOfBound);
+        }
+        if !('1'..='8').contains(&rank) {
+            return Err(BoardError::RankOutOfBound);
+        }
+        Ok(Self::from_coords(
+            (file as u8 - b'a') as usize,
+            (rank as u8 - b'1') as usize,
+        ))
+    }
+}
+
+impl Display for Square {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let file = (b'a' + self.file() as u8) as char;
+        let rank = (b'1' + self.rank() as u8) as char;
+        write!(f, "{file}{rank}")
+    }
+}
+
+// Lets any per-square table be indexed by a Square directly
+impl<T> Index<Square> for [T] {
+    type Output = T;
+
+    #[inline(always)]
+    fn index(&self, index: Square) -> &Self::Output {
+        &self[index as usize]
+    }
+}
+
+impl<T> IndexMut<Square> for [T] {
+    #[inline(always)]
+    fn index_mut(&mut self, index: Square) -> &mut Self::Output {
+        &mut self[index as usize]
     }
 }
 
@@ -65,30 +172,185 @@ where
     }
 }
 
-impl<T> Index<SQ> for BySquare<T> {
+impl<T> Index<Square> for BySquare<T> {
     type Output = T;
 
-    fn index(&self, index: SQ) -> &Self::Output {
-        &self.0[index as usize]
+    fn index(&self, index: Square) -> &Self::Output {
+        &self.0[index]
     }
 }
 
-impl<T> IndexMut<SQ> for BySquare<T> {
-    fn index_mut(&mut self, index: SQ) -> &mut Self::Output {
-        &mut self.0[index as usize]
+impl<T> IndexMut<Square> for BySquare<T> {
+    fn index_mut(&mut self, index: Square) -> &mut Self::Output {
+        &mut self.0[index]
+    }
+}
+
+#[repr(u8)]
+#[derive(Copy, Clone, PartialEq, Eq, Ord, PartialOrd, Hash, Debug)]
+pub enum Piece {
+    King,
+    Queen,
+    Rook,
+    Bishop,
+    Knight,
+    Pawn,
+}
+
+impl Piece {
+    pub const ALL: [Piece; NumOf::PIECE_TYPES] = [
+        Piece::King,
+        Piece::Queen,
+        Piece::Rook,
+        Piece::Bishop,
+        Piece::Knight,
+        Piece::Pawn,
+    ];
+
+    #[inline(always)]
+    pub const fn index(self) -> usize {
+        self as usize
+    }
+
+    // Upper case, as in SAN
+    pub const fn to_char(self) -> char {
+        match self {
+            Piece::King => 'K',
+            Piece::Queen => 'Q',
+            Piece::Rook => 'R',
+            Piece::Bishop => 'B',
+            Piece::Knight => 'N',
+            Piece::Pawn => 'P',
+        }
+    }
+
+    // Only upper case letters, a lower case 'b' is a file in SAN
+    pub const fn from_char(c: char) -> Option<Self> {
+        match c {
+            'K' => Some(Piece::King),
+            'Q' => Some(Piece::Queen),
+            'R' => Some(Piece::Rook),
+            'B' => Some(Piece::Bishop),
+            'N' => Some(Piece::Knight),
+            'P' => Some(Piece::Pawn),
+            _ => None,
+        }
+    }
+}
+
+impl TryFrom<usize> for Piece {
+    type Error = BoardError;
+
+    fn try_from(value: usize) -> Result<Self, Self::Error> {
+        Self::ALL
+            .get(value)
+            .copied()
+            .ok_or(BoardError::InvalidPiece)
+    }
+}
+
+impl FromStr for Piece {
+    type Err = BoardError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut chars = s.chars();
+        match (chars.next(), chars.next()) {
+            (Some(c), None) => Self::from_char(c).ok_or(BoardError::InvalidPiece),
+            _ => Err(BoardError::InvalidPiece),
+        }
+    }
+}
+
+impl Display for Piece {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.to_char())
     }
 }
 
-#[derive(Debug, PartialEq)]
-pub struct Pieces;
-impl Pieces {
-    pub const KING: Piece = 0;
-    pub const QUEEN: Piece = 1;
-    pub const ROOK: Piece = 2;
-    pub const BISHOP: Piece = 3;
-    pub const KNIGHT: Piece = 4;
-    pub const PAWN: Piece = 5;
-    pub const NONE: Piece = 6;
+impl<T> Index<Piece> for [T] {
+    type Output = T;
+
+    #[inline(always)]
+    fn index(&self, index: Piece) -> &Self::Output {
+        &self[index as usize]
+    }
+}
+
+impl<T> IndexMut<Piece> for [T] {
+    #[inline(always)]
+    fn index_mut(&mut self, index: Piece) -> &mut Self::Output {
+        &mut self[index as usize]
+    }
+}
+
+// A piece together with its side, packed as side * 6 + piece so it fits in a byte and
+// doubles as an index into per-coloured-piece tables
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub struct ColoredPiece(u8);
+
+impl ColoredPiece {
+    #[inline(always)]
+    pub const fn new(side: Side, piece: Piece) -> Self {
+        Self(side as u8 * NumOf::PIECE_TYPES as u8 + piece as u8)
+    }
+
+    #[inline(always)]
+    pub const fn side(self) -> Side {
+        if self.0 as usize >= NumOf::PIECE_TYPES {
+            Side::White
+        } else {
+            Side::Black
+        }
+    }
+
+    #[inline(always)]
+    pub const fn piece(self) -> Piece {
+        Piece::ALL[self.0 as usize % NumOf::PIECE_TYPES]
+    }
+
+    #[inline(always)]
+    pub const fn index(self) -> usize {
+        self.0 as usize
+    }
+
+    // FEN letter, upper case for White
+    pub const fn to_char(self) -> char {
+        let c = self.piece().to_char();
+        match self.side() {
+            Side::White => c,
+            Side::Black => c.to_ascii_lowercase(),
+        }
+    }
+
+    pub const fn from_char(c: char) -> Option<Self> {
+        let side = if c.is_ascii_uppercase() {
+            Side::White
+        } else {
+            Side::Black
+        };
+        match Piece::from_char(c.to_ascii_uppercase()) {
+            Some(piece) => Some(Self::new(side, piece)),
+            None => None,
+        }
+    }
+}
+
+impl FromStr for ColoredPiece {
+    type Err = BoardError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut chars = s.chars();
+        match (chars.next(), chars.next()) {
+            (Some(c), None) => Self::from_char(c).ok_or(BoardError::InvalidPiece),
+            _ => Err(BoardError::InvalidPiece),
+        }
+    }
+}
+
+impl Display for ColoredPiece {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.to_char())
+    }
 }
 
 pub struct BySide<T>([T; NumOf::SIDES]);
@@ -96,11 +358,22 @@ impl<T> BySide<T>
 where
     T: Copy,
 {
-    pub fn new(value: T) -> Self {
+    pub const fn new(value: T) -> Self {
         Self([value; NumOf::SIDES])
     }
 }
 
+impl<T> BySide<T> {
+    // Takes the sides by name, whichever order Side stores them in
+    pub const fn from_sides(white: T, black: T) -> Self {
+        Self(if (Side::White as usize) == 0 {
+            [white, black]
+        } else {
+            [black, white]
+        })
+    }
+}
+
 impl<T> Index<Side> for BySide<T> {
     type Output = T;
 
@@ -118,7 +391,7 @@ impl<T> IndexMut<Side> for BySide<T> {
 }
 
 #[repr(usize)]
-#[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Hash)]
 pub enum Side {
     Black = 0x00,
     White = 0x01,
@@ -155,6 +428,11 @@ impl Side {
     pub fn u8(&self) -> u8 {
         *self as u8
     }
+
+    #[inline(always)]
+    pub const fn piece(self, piece: Piece) -> ColoredPiece {
+        ColoredPiece::new(self, piece)
+    }
 }
 
 #[repr(u8)]
@@ -184,6 +462,7 @@ pub enum BoardError {
     FileOutOfBound,
     RankOutOfBound,
     SquareOutOfBound,
+    InvalidPiece,
 }
 
 impl Display for BoardError {
@@ -192,6 +471,7 @@ impl Display for BoardError {
             BoardError::FileOutOfBound => "File does not exist or is out of bound",
             BoardError::RankOutOfBound => "Rank does not exist or is out of bound",
             BoardError::SquareOutOfBound => "Square does not exist or is out of bound",
+            BoardError::InvalidPiece => "Piece does not exist",
         };
         write!(f, "{err}")
     }
@@ -243,11 +523,8 @@ pub struct SquareCoord {
 }
 
 impl SquareCoord {
-    pub fn to_usize(&self) -> usize {
-        let file = usize::from(self.file as u8);
-        let rank = usize::from(self.rank as u8);
-
-        (rank * NumOf::RANKS) + file
+    pub fn to_square(&self) -> Square {
+        Square::from_coords(self.file as usize, self.rank as usize)
     }
 
     pub fn next(self, direction: Direction) -> Result<Self, BoardError> {
@@ -283,3 +560,51 @@ impl TryFrom<u8> for SquareCoord {
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn squares_agree_with_board_indices() {
+        for (index, square) in Square::ALL.into_iter().enumerate() {
+            assert_eq!(square.index(), index);
+            assert_eq!(Square::try_from(index).unwrap(), square);
+            assert_eq!(square.to_string().parse::<Square>().unwrap(), square);
+        }
+        assert_eq!(Square::B1.index(), 1);
+        assert_eq!(Square::A2.index(), 8);
+        assert_eq!((Square::E4.file(), Square::E4.rank()), (4, 3));
+        assert_eq!(Square::E4.to_string(), "e4");
+        assert_eq!(Square::E2.flip_rank(), Square::E7);
+        assert_eq!(Square::B3.flip_file(), Square::G3);
+        assert_eq!(Square::H1.offset((1, 0)), None);
+        assert_eq!(Square::G1.offset((-1, 2)), Some(Square::F3));
+        assert!(Square::try_from(NumOf::SQUARES).is_err());
+        assert!("i1".parse::<Square>().is_err());
+        assert!("a9".parse::<Square>().is_err());
+        assert!("a10".parse::<Square>().is_err());
+    }
+
+    #[test]
+    fn pieces_round_trip_through_letters() {
+        for piece in Piece::ALL {
+            assert_eq!(piece.to_string().parse::<Piece>().unwrap(), piece);
+            for side in [Side::White, Side::Black] {
+                let colored = side.piece(piece);
+                assert_eq!((colored.side(), colored.piece()), (side, piece));
+                assert_eq!(
+                    colored.to_string().parse::<ColoredPiece>().unwrap(),
+                    colored
+                );
+            }
+        }
+        assert_eq!(Side::White.piece(Piece::Knight).to_string(), "N");
+        assert_eq!(Side::Black.piece(Piece::Queen).to_string(), "q");
+        assert_eq!(Side::Black.piece(Piece::Pawn).index(), 5);
+        assert_eq!(Side::White.piece(Piece::King).index(), 6);
+        assert!("x".parse::<ColoredPiece>().is_err());
+        assert!("n".parse::<Piece>().is_err());
+        assert!(Piece::try_from(NumOf::PIECE_TYPES).is_err());
+    }
+}
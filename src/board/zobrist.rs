@@ -1,6 +1,6 @@
 use crate::types::NumOf;
 
-use super::types::{CastlingState, Piece, Pieces, Side, Square};
+use super::types::{CastlingState, ColoredPiece, Piece, Side, Square};
 use rand::{Rng, SeedableRng};
 use rand_pcg::Pcg64;
 
@@ -58,10 +58,7 @@ impl Zobrist {
         }
     }
     pub fn piece(&self, side: Side, piece_type: Piece, square: Square) -> ZobristKey {
-        debug_assert!((side as usize) < NumOf::SIDES, "Invalid side: {:?}", side);
-        debug_assert!(piece_type < Pieces::NONE, "Invalid piece: {:?}", piece_type);
-        debug_assert!(square < NumOf::SQUARES, "Invalid square: {:?}", square);
-        let piece_index = piece_type + (side.u8() * 6) as usize;
+        let piece_index = ColoredPiece::new(side, piece_type).index();
 
         return self.pieces_hash[piece_index][square];
     }
@@ -0,0 +1,408 @@
+pub mod polyglot_key;
+
+use std::{fmt::Display, path::Path, path::PathBuf};
+
+use rand::Rng;
+
+use crate::board::{
+    Board,
+    piece_movement::{Move, MoveFlag},
+    types::{Piece, Side, Square},
+};
+use polyglot_key::{RANDOM_COUNT, Random64};
+
+// Every entry is 16 big-endian bytes: key, move, weight and a learn field we ignore
+const ENTRY_BYTES: usize = 16;
+
+#[derive(Debug)]
+pub enum BookError {
+    Io(std::io::Error),
+    TruncatedBook {
+        len: usize,
+    },
+    InvalidRandomValue(String),
+    WrongRandomCount {
+        found: usize,
+    },
+    RandomTableMismatch {
+        fen: &'static str,
+        expected: u64,
+        found: u64,
+    },
+}
+
+impl Display for BookError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BookError::Io(err) => write!(f, "Could not read the book: {err}"),
+            BookError::TruncatedBook { len } => write!(
+                f,
+                "Book size {len} is not a multiple of the {ENTRY_BYTES} byte entry size"
+            ),
+            BookError::InvalidRandomValue(value) => {
+                write!(f, "Invalid value {value:?} in the Random64 table")
+            }
+            BookError::WrongRandomCount { found } => write!(
+                f,
+                "Random64 table has {found} values, expected {RANDOM_COUNT}"
+            ),
+            BookError::RandomTableMismatch {
+                fen,
+                expected,
+                found,
+            } => write!(
+                f,
+                "Random64 table hashes {fen} to {found:016x}, not {expected:016x}"
+            ),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BookEntry {
+    pub key: u64,
+    pub raw_move: u16,
+    pub weight: u16,
+}
+
+impl BookEntry {
+    pub fn to_bytes(&self) -> [u8; ENTRY_BYTES] {
+        let mut bytes = [0; ENTRY_BYTES];
+        bytes[0..8].copy_from_slice(&self.key.to_be_bytes());
+        bytes[8..10].copy_from_slice(&self.raw_move.to_be_bytes());
+        bytes[10..12].copy_from_slice(&self.weight.to_be_bytes());
+        bytes
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Selection {
+    // Always the entry with the highest weight
+    Best,
+    // Each entry in proportion to its weight
+    #[default]
+    Weighted,
+}
+
+// The values of the OwnBook, BookFile, BookDepth and BookSelection options
+#[derive(Debug, Clone)]
+pub struct BookOptions {
+    pub own_book: bool,
+    pub book_file: Option<PathBuf>,
+    // Plies from the start of the game after which the book is no longer probed
+    pub book_depth: u16,
+    pub selection: Selection,
+}
+
+impl Default for BookOptions {
+    fn default() -> Self {
+        Self {
+            own_book: false,
+            book_file: None,
+            book_depth: 32,
+            selection: Selection::Weighted,
+        }
+    }
+}
+
+impl BookOptions {
+    pub fn in_book(&self, board: &Board) -> bool {
+        self.own_book && game_ply(board) < self.book_depth as u32
+    }
+
+    // The book the options point at, None when no book file is set
+    pub fn load_book(&self) -> Result<Option<Book>, BookError> {
+        let Some(book_file) = &self.book_file else {
+            return Ok(None);
+        };
+        Book::load(book_file, Random64::polyglot()).map(Some)
+    }
+}
+
+fn game_ply(board: &Board) -> u32 {
+    let moves = board.game_state.fullmove_counter.saturating_sub(1) as u32;
+    2 * moves + (board.get_current_player() == Side::Black) as u32
+}
+
+// A Polyglot book read fully into memory. Books are sorted by key, so the entries for a
+// position are found by binary search.
+pub struct Book {
+    entries: Vec<BookEntry>,
+    random: Random64,
+}
+
+impl Book {
+    pub fn from_bytes(bytes: &[u8], random: Random64) -> Result<Self, BookError> {
+        if !bytes.len().is_multiple_of(ENTRY_BYTES) {
+            return Err(BookError::TruncatedBook { len: bytes.len() });
+        }
+        let entries = bytes
+            .chunks_exact(ENTRY_BYTES)
+            .map(|entry| BookEntry {
+                key: u64::from_be_bytes(entry[0..8].try_into().unwrap()),
+                raw_move: u16::from_be_bytes([entry[8], entry[9]]),
+                weight: u16::from_be_bytes([entry[10], entry[11]]),
+            })
+            .collect();
+        Ok(Self { entries, random })
+    }
+
+    pub fn load(path: &Path, random: Random64) -> Result<Self, BookError> {
+        let bytes = std::fs::read(path).map_err(BookError::Io)?;
+        Self::from_bytes(&bytes, random)
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn key(&self, board: &Board) -> u64 {
+        self.random.key(board)
+    }
+
+    pub fn entries(&self, board: &Board) -> &[BookEntry] {
+        let key = self.key(board);
+        let start = self.entries.partition_point(|entry| entry.key < key);
+        let end = start + self.entries[start..].partition_point(|entry| entry.key == key);
+        &self.entries[start..end]
+    }
+
+    // A book move for the position, None when the book has nothing usable for it
+    pub fn probe(&self, board: &Board, selection: Selection, rng: &mut impl Rng) -> Option<Move> {
+        let entries: Vec<(BookEntry, Move)> = self
+            .entries(board)
+            .iter()
+            .filter_map(|&entry| Some((entry, decode_move(board, entry.raw_move)?)))
+            .collect();
+        let chosen = match selection {
+            Selection::Best => entries.iter().max_by_key(|(entry, _)| entry.weight),
+            Selection::Weighted => {
+                let total: u32 = entries.iter().map(|(entry, _)| entry.weight as u32).sum();
+                if total == 0 {
+                    entries.first()
+                } else {
+                    let mut pick = rng.random_range(0..total);
+                    entries.iter().find(|(entry, _)| {
+                        let weight = entry.weight as u32;
+                        if pick < weight {
+                            return true;
+                        }
+                        pick -= weight;
+                        false
+                    })
+                }
+            }
+        };
+        chosen.map(|&(_, chess_move)| chess_move)
+    }
+}
+
+// Polyglot numbers promotions knight, bishop, rook, queen from 1
+const PROMOTION_PIECES: [Piece; 4] = [Piece::Knight, Piece::Bishop, Piece::Rook, Piece::Queen];
+
+// Polyglot moves are to file, to rank, from file, from rank and a promotion piece, three
+// bits each. Castling is written as the king taking its own rook. None when the move
+// does not fit the position, which only happens on a key collision.
+pub fn decode_move(board: &Board, raw_move: u16) -> Option<Move> {
+    let to = Square::from_index((raw_move & 0x3f) as usize);
+    let from = Square::from_index(((raw_move >> 6) & 0x3f) as usize);
+    let promotion = match (raw_move >> 12) & 0x7 {
+        0 => None,
+        piece @ 1..=4 => Some(PROMOTION_PIECES[piece as usize - 1]),
+        _ => return None,
+    };
+    let side = board.get_current_player();
+    if !board.bb_sides[side as usize].is_set(from) {
+        return None;
+    }
+    let own_rook = board.bb_pieces[side as usize][Piece::Rook].is_set(to);
+    let king_start = match side {
+        Side::White => Square::E1,
+        Side::Black => Square::E8,
+    };
+
+    if board.piece_on(from) == Some(Piece::King) && from == king_start && own_rook {
+        let to = if to > from {
+            from.step(2)
+        } else {
+            from.step(-2)
+        };
+        return Some(board.build_move(from, to, None));
+    }
+    Some(board.build_move(from, to, promotion))
+}
+
+// The inverse of decode_move
+pub fn encode_move(chess_move: Move) -> u16 {
+    let from = chess_move.from_square();
+    let (to, promotion) = match chess_move.flags() {
+        Ok(MoveFlag::KingSideCastle) => (from.step(3), 0),
+        Ok(MoveFlag::QueenSideCastle) => (from.step(-4), 0),
+        // Promotion flags carry the piece in their two low bits, knight first
+        Ok(flag) if flag as u8 & MoveFlag::KnightPromotion as u8 != 0 => {
+            (chess_move.dest_square(), (flag as u8 & 0b11) as u16 + 1)
+        }
+        _ => (chess_move.dest_square(), 0),
+    };
+    to as u16 | (from as u16) << 6 | promotion << 12
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+    use rand_pcg::Pcg64;
+
+    fn raw_move(from: Square, to: Square, promotion: u16) -> u16 {
+        to as u16 | (from as u16) << 6 | promotion << 12
+    }
+
+    fn entry_bytes(key: u64, raw_move: u16, weight: u16) -> Vec<u8> {
+        let entry = BookEntry {
+            key,
+            raw_move,
+            weight,
+        };
+        entry.to_bytes().to_vec()
+    }
+
+    #[test]
+    fn decodes_castling_and_special_moves() {
+        let board = Board::from_fen("r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 0 1").unwrap();
+        let short = decode_move(&board, raw_move(Square::E1, Square::H1, 0)).unwrap();
+        assert_eq!(
+            (short.from_square(), short.dest_square()),
+            (Square::E1, Square::G1)
+        );
+        assert_eq!(short.flags(), Ok(MoveFlag::KingSideCastle));
+        let long = decode_move(&board, raw_move(Square::E1, Square::A1, 0)).unwrap();
+        assert_eq!(long.dest_square(), Square::C1);
+        assert_eq!(long.flags(), Ok(MoveFlag::QueenSideCastle));
+        // Not the side to move's piece
+        assert!(decode_move(&board, raw_move(Square::E8, Square::H8, 0)).is_none());
+
+        let promotions = Board::from_fen("1n2k3/P7/8/3pP3/8/8/8/4K3 w - d6 0 1").unwrap();
+        let ep = decode_move(&promotions, raw_move(Square::E5, Square::D6, 0)).unwrap();
+        assert_eq!(ep.flags(), Ok(MoveFlag::EpCapture));
+        let promotion = decode_move(&promotions, raw_move(Square::A7, Square::B8, 4)).unwrap();
+        assert_eq!(promotion.flags(), Ok(MoveFlag::QueenCapturePromotion));
+        let underpromotion = decode_move(&promotions, raw_move(Square::A7, Square::A8, 1)).unwrap();
+        assert_eq!(underpromotion.flags(), Ok(MoveFlag::KnightPromotion));
+
+        for (board, raw) in [
+            (&board, raw_move(Square::E1, Square::H1, 0)),
+            (&board, raw_move(Square::E1, Square::A1, 0)),
+            (&promotions, raw_move(Square::E5, Square::D6, 0)),
+            (&promotions, raw_move(Square::A7, Square::B8, 4)),
+            (&promotions, raw_move(Square::A7, Square::A8, 1)),
+        ] {
+            assert_eq!(encode_move(decode_move(board, raw).unwrap()), raw);
+        }
+    }
+
+    #[test]
+    fn enpassant_only_hashed_when_capturable() {
+        let random = Random64::polyglot();
+        let with_ep = Board::from_fen("4k3/8/8/8/4P3/8/8/4K3 b - e3 0 1").unwrap();
+        let without_ep = Board::from_fen("4k3/8/8/8/4P3/8/8/4K3 b - - 0 1").unwrap();
+        assert_eq!(random.key(&with_ep), random.key(&without_ep));
+        let with_ep = Board::from_fen("4k3/8/8/8/3pP3/8/8/4K3 b - e3 0 1").unwrap();
+        let without_ep = Board::from_fen("4k3/8/8/8/3pP3/8/8/4K3 b - - 0 1").unwrap();
+        assert_ne!(random.key(&with_ep), random.key(&without_ep));
+    }
+
+    #[test]
+    fn probes_entries_by_key() {
+        let board =
+            Board::from_fen("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1").unwrap();
+        let key = Random64::polyglot().key(&board);
+        let e4 = raw_move(Square::E2, Square::E4, 0);
+        let d4 = raw_move(Square::D2, Square::D4, 0);
+        let mut bytes = entry_bytes(
+            key.wrapping_sub(1),
+            raw_move(Square::G1, Square::F3, 0),
+            100,
+        );
+        bytes.extend(entry_bytes(key, d4, 1));
+        bytes.extend(entry_bytes(key, e4, 9));
+        bytes.extend(entry_bytes(
+            key.wrapping_add(1),
+            raw_move(Square::B1, Square::C3, 0),
+            100,
+        ));
+        let book = Book::from_bytes(&bytes, Random64::polyglot()).unwrap();
+        assert_eq!(book.len(), 4);
+        assert_eq!(book.entries(&board).len(), 2);
+
+        let mut rng = Pcg64::seed_from_u64(1);
+        let best = book.probe(&board, Selection::Best, &mut rng).unwrap();
+        assert_eq!(
+            (best.from_square(), best.dest_square()),
+            (Square::E2, Square::E4)
+        );
+        assert_eq!(best.flags(), Ok(MoveFlag::DoublePawnPush));
+        let picks = (0..1000)
+            .filter(|_| {
+                book.probe(&board, Selection::Weighted, &mut rng)
+                    .unwrap()
+                    .from_square()
+                    == Square::D2
+            })
+            .count();
+        assert!((50..150).contains(&picks), "{picks}");
+
+        assert!(Book::from_bytes(&bytes[..20], Random64::polyglot()).is_err());
+        let unknown = Board::from_fen("4k3/8/8/8/8/8/8/4K3 w - - 0 1").unwrap();
+        assert!(book.probe(&unknown, Selection::Best, &mut rng).is_none());
+    }
+
+    #[test]
+    fn rejects_tables_that_are_not_polyglots() {
+        let values: Vec<String> = (0..RANDOM_COUNT).map(|i| format!("0x{i:x}")).collect();
+        assert!(matches!(
+            Random64::parse(&values.join(",\n")),
+            Err(BookError::RandomTableMismatch { .. })
+        ));
+        assert!(matches!(
+            Random64::parse(&values[..780].join(" ")),
+            Err(BookError::WrongRandomCount { found: 780 })
+        ));
+    }
+
+    #[test]
+    fn polyglot_table_matches_reference_keys() {
+        let random = Random64::polyglot();
+        for (fen, key) in polyglot_key::REFERENCE_KEYS {
+            assert_eq!(random.key(&Board::from_fen(fen).unwrap()), key, "{fen}");
+        }
+    }
+
+    #[test]
+    fn options_load_the_book_file() {
+        assert!(matches!(BookOptions::default().load_book(), Ok(None)));
+        let options = BookOptions {
+            book_file: Some(PathBuf::from("no/such/book.bin")),
+            ..BookOptions::default()
+        };
+        assert!(matches!(options.load_book(), Err(BookError::Io(_))));
+    }
+
+    #[test]
+    fn book_depth_counts_plies() {
+        let options = BookOptions {
+            own_book: true,
+            book_depth: 2,
+            ..BookOptions::default()
+        };
+        assert!(options.in_book(&Board::from_fen("4k3/8/8/8/8/8/8/4K3 b - - 0 1").unwrap()));
+        assert!(!options.in_book(&Board::from_fen("4k3/8/8/8/8/8/8/4K3 w - - 0 2").unwrap()));
+        assert!(
+            !BookOptions::default()
+                .in_book(&Board::from_fen("4k3/8/8/8/8/8/8/4K3 w - - 0 1").unwrap())
+        );
+    }
+}
@@ -0,0 +1,189 @@
+pub mod attacks;
+pub mod endgame;
+pub mod king;
+pub mod kpk;
+pub mod nnue;
+pub mod params;
+pub mod pawns;
+pub mod pieces;
+pub mod psqt;
+pub mod score;
+pub mod trace;
+
+use std::sync::Arc;
+
+use crate::board::{Board, types::Side};
+use attacks::AttackInfo;
+use endgame::{MaterialSignature, SCALE_NORMAL, evaluate_endgame, scale_factor};
+use king::evaluate_king_danger;
+use nnue::{Network, NnueState};
+use pawns::{PawnEntry, PawnHashTable, evaluate_passed_pawns};
+use pieces::{evaluate_pieces, evaluate_threats};
+use psqt::trace_psqt;
+use trace::{NoTrace, Trace, Tracer};
+
+// Holds the caches the evaluation relies on, one per search thread
+#[derive(Default)]
+pub struct Evaluator {
+    pub pawn_table: PawnHashTable,
+    // Replaces the hand-crafted evaluation once a network is loaded
+    pub nnue: Option<NnueState>,
+}
+
+impl Evaluator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // The accumulators start from `board`, and have to be pushed and popped along with
+    // every move made and unmade on it from then on
+    pub fn set_network(&mut self, network: Arc<Network>, board: &mut Board) {
+        self.nnue = Some(NnueState::new(network, board));
+    }
+
+    // Static evaluation in centipawns from the point of view of the side to move
+    pub fn evaluate(&mut self, board: &Board) -> i32 {
+        if let Some(nnue) = &self.nnue {
+            return nnue.evaluate(board);
+        }
+        let white_pov = self.evaluate_white_pov(board, &mut NoTrace);
+        match board.get_current_player() {
+            Side::White => white_pov,
+            Side::Black => -white_pov,
+        }
+    }
+
+    // The counts of every parameter behind the hand-crafted evaluation, for tuning. None
+    // for the endgames scored by dedicated code instead of by the parameters.
+    pub fn trace(&mut self, board: &Board) -> Option<Trace> {
+        if evaluate_endgame(board, &MaterialSignature::new(board)).is_some() {
+            return None;
+        }
+        let mut trace = Trace::new();
+        self.evaluate_white_pov(board, &mut trace);
+        Some(trace)
+    }
+
+    fn evaluate_white_pov<T: Tracer>(&mut self, board: &Board, trace: &mut T) -> i32 {
+        let signature = MaterialSignature::new(board);
+        if let Some(score) = evaluate_endgame(board, &signature) {
+            return score;
+        }
+
+        let state = &board.game_state;
+        let mut score = state.psqt[Side::White as usize] - state.psqt[Side::Black as usize];
+        if T::ENABLED {
+            trace_psqt(board, trace);
+        }
+
+        let pawn_entry = if T::ENABLED {
+            PawnEntry::with_trace(board, trace)
+        } else {
+            *self.pawn_table.probe(board)
+        };
+        let passed = pawn_entry.passed;
+        score += pawn_entry.score;
+        score += evaluate_passed_pawns(board, passed[Side::White as usize], Side::White, trace);
+        score -= evaluate_passed_pawns(board, passed[Side::Black as usize], Side::Black, trace);
+
+        // Both sides' attack maps have to be complete before king danger and threats
+        let mut info = AttackInfo::new(board);
+        score += evaluate_pieces(board, &mut info, Side::White, trace);
+        score -= evaluate_pieces(board, &mut info, Side::Black, trace);
+        score += evaluate_king_danger(board, &info, Side::White, trace);
+        score -= evaluate_king_danger(board, &info, Side::Black, trace);
+        score += evaluate_threats(board, &info, Side::White, trace);
+        score -= evaluate_threats(board, &info, Side::Black, trace);
+
+        let scale = scale_factor(board, &signature);
+        trace.set_taper(state.phase, scale);
+        score.eg = score.eg * scale / SCALE_NORMAL;
+        score.taper(state.phase)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::types::{Piece, Square};
+    use crate::eval::score::{MAX_PHASE, Score};
+
+    fn evaluate(board: &Board) -> i32 {
+        Evaluator::new().evaluate(board)
+    }
+
+    #[test]
+    fn start_position_is_balanced() {
+        let board =
+            Board::from_fen("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1").unwrap();
+        assert_eq!(board.game_state.phase, MAX_PHASE);
+        assert_eq!(evaluate(&board), 0);
+        let board =
+            Board::from_fen("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR b KQkq - 0 1").unwrap();
+        assert_eq!(evaluate(&board), 0);
+    }
+
+    #[test]
+    fn score_is_relative_to_side_to_move() {
+        let white = Board::from_fen("4k3/8/8/8/8/8/8/3QK3 w - - 0 1").unwrap();
+        let black = Board::from_fen("4k3/8/8/8/8/8/8/3QK3 b - - 0 1").unwrap();
+        assert!(evaluate(&white) > 800);
+        assert_eq!(evaluate(&white), -evaluate(&black));
+    }
+
+    #[test]
+    fn colour_flipped_positions_mirror() {
+        let board =
+            Board::from_fen("r3k2r/pp3ppp/2n5/3p4/8/2N2B2/PPP2PPP/R3K2R w - - 0 1").unwrap();
+        let flipped =
+            Board::from_fen("r3k2r/ppp2ppp/2n2b2/8/3P4/2N5/PP3PPP/R3K2R b - - 0 1").unwrap();
+        assert_eq!(evaluate(&board), evaluate(&flipped));
+    }
+
+    #[test]
+    fn incremental_update_matches_full_recompute() {
+        let mut board =
+            Board::from_fen("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1").unwrap();
+        // e2-e4 and the b8 knight taken off the board
+        board.move_piece(Piece::Pawn, Side::White, Square::E2, Square::E4);
+        board.remove_piece(Piece::Knight, Side::Black, Square::B8);
+        let expected =
+            Board::from_fen("r1bqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR w KQkq - 0 1").unwrap();
+        assert_eq!(board.game_state.psqt, expected.game_state.psqt);
+        assert_eq!(board.game_state.phase, expected.game_state.phase);
+        assert_eq!(evaluate(&board), evaluate(&expected));
+    }
+
+    #[test]
+    fn trace_reproduces_evaluation() {
+        let params = trace::default_params();
+        for fen in [
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+            "r3k2r/pp3ppp/2n5/3p4/8/2N2B2/PPP2PPP/R3K2R b - - 0 1",
+            "6k1/5ppp/2q5/8/1b6/6P1/5PBP/3R2K1 w - - 0 1",
+            "4k3/3b4/8/2p5/2P5/8/3B4/4K3 w - - 0 1",
+        ] {
+            let board = Board::from_fen(fen).unwrap();
+            let mut evaluator = Evaluator::new();
+            let trace = evaluator.trace(&board).unwrap();
+            let white_pov = match board.get_current_player() {
+                Side::White => evaluator.evaluate(&board),
+                Side::Black => -evaluator.evaluate(&board),
+            };
+            assert_eq!(trace::evaluate_trace(&trace, &params), white_pov, "{fen}");
+        }
+        // Scored by the KPK bitbase, not by the parameters
+        let kpk = Board::from_fen("8/4P3/8/8/8/8/k7/7K w - - 0 1").unwrap();
+        assert!(Evaluator::new().trace(&kpk).is_none());
+    }
+
+    #[test]
+    fn taper_blends_by_phase() {
+        let score = Score::new(100, -50);
+        assert_eq!(score.taper(MAX_PHASE), 100);
+        assert_eq!(score.taper(0), -50);
+        assert_eq!(score.taper(MAX_PHASE / 2), 25);
+        // Promotions can push the phase past its opening value
+        assert_eq!(score.taper(MAX_PHASE + 8), 100);
+    }
+}
@@ -0,0 +1,64 @@
+use crate::{
+    board::{
+        Board,
+        types::{Piece, Side},
+    },
+    eval::pawns::pawn_attacks,
+    movement::ATTACKS,
+    types::{Bitboard, EMPTY_BITBOARD, NumOf},
+};
+
+// Attack maps and king attack counters shared by the mobility, king safety and threat
+// terms. Pawn and king attacks are filled in up front, the other pieces are added as
+// evaluate_pieces walks over them.
+pub struct AttackInfo {
+    pub occupancy: Bitboard,
+    pub attacked_by: [[Bitboard; NumOf::PIECE_TYPES]; NumOf::SIDES],
+    pub attacked: [Bitboard; NumOf::SIDES],
+    // Squares a side's pieces get mobility credit for: not blocked by its own pawns or
+    // king and not covered by enemy pawns
+    pub mobility_area: [Bitboard; NumOf::SIDES],
+    // The king square and the squares around it
+    pub king_zone: [Bitboard; NumOf::SIDES],
+    // Indexed by the attacking side: how many pieces of each type hit the enemy king zone,
+    // and how many zone squares they hit in total
+    pub king_attackers: [[i32; NumOf::PIECE_TYPES]; NumOf::SIDES],
+    pub king_zone_attacks: [i32; NumOf::SIDES],
+}
+
+impl AttackInfo {
+    pub fn new(board: &Board) -> Self {
+        let mut info = Self {
+            occupancy: board.bb_sides[Side::White as usize] | board.bb_sides[Side::Black as usize],
+            attacked_by: [[EMPTY_BITBOARD; NumOf::PIECE_TYPES]; NumOf::SIDES],
+            attacked: [EMPTY_BITBOARD; NumOf::SIDES],
+            mobility_area: [EMPTY_BITBOARD; NumOf::SIDES],
+            king_zone: [EMPTY_BITBOARD; NumOf::SIDES],
+            king_attackers: [[0; NumOf::PIECE_TYPES]; NumOf::SIDES],
+            king_zone_attacks: [0; NumOf::SIDES],
+        };
+        for side in [Side::White, Side::Black] {
+            let pawns = pawn_attacks(board.get_pieces(side, Piece::Pawn), side);
+            info.add(side, Piece::Pawn, pawns);
+
+            let king = board.get_pieces(side, Piece::King);
+            if !king.is_empty() {
+                let king_attacks = ATTACKS.king_attacks[king.lsb()];
+                info.add(side, Piece::King, king_attacks);
+                info.king_zone[side as usize] = king | king_attacks;
+            }
+        }
+        for side in [Side::White, Side::Black] {
+            let blocked = board.get_pieces(side, Piece::Pawn) | board.get_pieces(side, Piece::King);
+            info.mobility_area[side as usize] =
+                !(blocked | info.attacked_by[side.other() as usize][Piece::Pawn]);
+        }
+        info
+    }
+
+    #[inline(always)]
+    pub fn add(&mut self, side: Side, piece: Piece, attacks: Bitboard) {
+        self.attacked_by[side as usize][piece] |= attacks;
+        self.attacked[side as usize] |= attacks;
+    }
+}
@@ -0,0 +1,389 @@
+use std::sync::OnceLock;
+
+use crate::{
+    board::{
+        Board,
+        types::{Files, Piece, Side, Square},
+    },
+    eval::kpk,
+    types::{Bitboard, FILE_MASKS, LIGHT_SQUARES, NumOf},
+};
+
+// Added to the score of a position the specialised evaluators know to be won, so that
+// the search always prefers it over any ordinary advantage
+pub const KNOWN_WIN: i32 = 10_000;
+
+// Scale factors apply to the endgame half of the score, in 1/SCALE_NORMAL units
+pub const SCALE_NORMAL: i32 = 64;
+const SCALE_DRAW: i32 = 0;
+const OPPOSITE_BISHOPS_SCALE: i32 = 24;
+const OPPOSITE_BISHOPS_WITH_PIECES_SCALE: i32 = 46;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Endgame {
+    Kpk,
+    Kbnk,
+    // Bare king against enough material to force mate: drive it to the edge
+    Kxk,
+}
+
+const KNOWN_ENDGAMES: [(&str, Endgame); 4] = [
+    ("KPK", Endgame::Kpk),
+    ("KBNK", Endgame::Kbnk),
+    ("KQK", Endgame::Kxk),
+    ("KRK", Endgame::Kxk),
+];
+
+static KNOWN_SIGNATURES: OnceLock<Vec<(MaterialSignature, Endgame)>> = OnceLock::new();
+
+// Piece counts for both sides, indexed like Board::bb_pieces
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MaterialSignature([[u8; NumOf::PIECE_TYPES]; NumOf::SIDES]);
+
+impl MaterialSignature {
+    pub fn new(board: &Board) -> Self {
+        let mut counts = [[0u8; NumOf::PIECE_TYPES]; NumOf::SIDES];
+        for (side_counts, side_pieces) in counts.iter_mut().zip(board.bb_pieces.iter()) {
+            for (count, bitboard) in side_counts.iter_mut().zip(side_pieces.iter()) {
+                *count = bitboard.popcount() as u8;
+            }
+        }
+        Self(counts)
+    }
+
+    // Parses the usual notation, White's pieces first: "KBNK" is king, bishop and knight
+    // against a bare king
+    pub fn from_code(code: &str) -> Option<Self> {
+        let weak_start = code.get(1..)?.find('K')? + 1;
+        let (white, black) = code.split_at(weak_start);
+        let mut counts = [[0u8; NumOf::PIECE_TYPES]; NumOf::SIDES];
+        for (side, pieces) in [(Side::White, white), (Side::Black, black)] {
+            if !pieces.starts_with('K') {
+                return None;
+            }
+            for c in pieces.chars() {
+                let piece = Piece::from_char(c)?;
+                counts[side as usize][piece] += 1;
+            }
+        }
+        Some(Self(counts))
+    }
+
+    pub fn flipped(&self) -> Self {
+        let mut counts = self.0;
+        counts.swap(Side::White as usize, Side::Black as usize);
+        Self(counts)
+    }
+
+    #[inline(always)]
+    pub fn count(&self, side: Side, piece: Piece) -> u8 {
+        self.0[side as usize][piece]
+    }
+
+    pub fn is_bare_king(&self, side: Side) -> bool {
+        Piece::ALL
+            .into_iter()
+            .all(|piece| piece == Piece::King || self.count(side, piece) == 0)
+    }
+
+    // Picks the specialised evaluator for this material, along with the strong side
+    pub fn endgame(&self) -> Option<(Endgame, Side)> {
+        let known = KNOWN_SIGNATURES.get_or_init(|| {
+            KNOWN_ENDGAMES
+                .iter()
+                .map(|(code, endgame)| (Self::from_code(code).unwrap(), *endgame))
+                .collect()
+        });
+        let flipped = self.flipped();
+        for (signature, endgame) in known {
+            if *signature == *self {
+                return Some((*endgame, Side::White));
+            }
+            if *signature == flipped {
+                return Some((*endgame, Side::Black));
+            }
+        }
+        // Bigger material than the table covers, e.g. KQRK or KRRPK
+        for strong in [Side::White, Side::Black] {
+            if self.is_bare_king(strong.other())
+                && self.count(strong, Piece::Queen) + self.count(strong, Piece::Rook) > 0
+            {
+                return Some((Endgame::Kxk, strong));
+            }
+        }
+        None
+    }
+}
+
+// Exact evaluation of the known endgames, in centipawns from White's point of view
+pub fn evaluate_endgame(board: &Board, signature: &MaterialSignature) -> Option<i32> {
+    let (endgame, strong) = signature.endgame()?;
+    let score = match endgame {
+        Endgame::Kpk => evaluate_kpk(board, strong),
+        Endgame::Kbnk => evaluate_kbnk(board, strong),
+        Endgame::Kxk => evaluate_kxk(board, strong),
+    };
+    Some(match strong {
+        Side::White => score,
+        Side::Black => -score,
+    })
+}
+
+#[inline(always)]
+fn king_square(board: &Board, side: Side) -> Square {
+    board.get_pieces(side, Piece::King).lsb()
+}
+
+fn square_distance(a: Square, b: Square) -> i32 {
+    let file_distance = a.file().abs_diff(b.file());
+    let rank_distance = a.rank().abs_diff(b.rank());
+    file_distance.max(rank_distance) as i32
+}
+
+fn manhattan_distance(a: Square, b: Square) -> i32 {
+    let file_distance = a.file().abs_diff(b.file());
+    let rank_distance = a.rank().abs_diff(b.rank());
+    (file_distance + rank_distance) as i32
+}
+
+// 0 in the centre up to 120 in the corners
+fn push_to_edge(square: Square) -> i32 {
+    let file = square.file() as i32;
+    let rank = square.rank() as i32;
+    let centre_distance = (3 - file).max(file - 4) + (3 - rank).max(rank - 4);
+    20 * centre_distance
+}
+
+// Rewards the strong king for closing in on the weak one
+fn push_close(a: Square, b: Square) -> i32 {
+    140 - 20 * square_distance(a, b)
+}
+
+fn material(board: &Board, side: Side) -> i32 {
+    board.bb_pieces[side as usize]
+        .iter()
+        .zip(board.params.params.piece_values.iter())
+        .map(|(bitboard, value)| bitboard.popcount() as i32 * value.eg)
+        .sum()
+}
+
+fn evaluate_kxk(board: &Board, strong: Side) -> i32 {
+    let strong_king = king_square(board, strong);
+    let weak_king = king_square(board, strong.other());
+    KNOWN_WIN
+        + material(board, strong)
+        + push_to_edge(weak_king)
+        + push_close(strong_king, weak_king)
+}
+
+// Mate can only be forced in the two corners the bishop controls
+fn evaluate_kbnk(board: &Board, strong: Side) -> i32 {
+    const DARK_CORNERS: [Square; 2] = [Square::A1, Square::H8];
+    const LIGHT_CORNERS: [Square; 2] = [Square::H1, Square::A8];
+    let strong_king = king_square(board, strong);
+    let weak_king = king_square(board, strong.other());
+    let corners = if !(board.get_pieces(strong, Piece::Bishop) & LIGHT_SQUARES).is_empty() {
+        LIGHT_CORNERS
+    } else {
+        DARK_CORNERS
+    };
+    let corner_distance = corners
+        .iter()
+        .map(|&corner| manhattan_distance(weak_king, corner))
+        .min()
+        .unwrap();
+    KNOWN_WIN + material(board, strong) + push_close(strong_king, weak_king) - 20 * corner_distance
+}
+
+fn evaluate_kpk(board: &Board, strong: Side) -> i32 {
+    // Normalise so the pawn belongs to White and sits on files a-d
+    let normalise = |square: Square| -> Square {
+        let square = match strong {
+            Side::White => square,
+            Side::Black => square.flip_rank(),
+        };
+        let pawn_file = match strong {
+            Side::White => board.get_pieces(strong, Piece::Pawn),
+            Side::Black => Bitboard(board.get_pieces(strong, Piece::Pawn).0.swap_bytes()),
+        }
+        .lsb()
+        .file();
+        if pawn_file >= Files::E as usize {
+            square.flip_file()
+        } else {
+            square
+        }
+    };
+    let pawn = normalise(board.get_pieces(strong, Piece::Pawn).lsb());
+    let strong_king = normalise(king_square(board, strong));
+    let weak_king = normalise(king_square(board, strong.other()));
+    let strong_to_move = board.get_current_player() == strong;
+
+    if kpk::probe(strong_to_move, strong_king, pawn, weak_king) {
+        KNOWN_WIN + board.params.params.piece_values[Piece::Pawn].eg + 10 * pawn.rank() as i32
+    } else {
+        0
+    }
+}
+
+// How much of the endgame score survives in drawish material configurations
+pub fn scale_factor(board: &Board, signature: &MaterialSignature) -> i32 {
+    for strong in [Side::White, Side::Black] {
+        if is_wrong_rook_pawn(board, signature, strong) {
+            return SCALE_DRAW;
+        }
+    }
+
+    let white_bishops = board.get_pieces(Side::White, Piece::Bishop);
+    let black_bishops = board.get_pieces(Side::Black, Piece::Bishop);
+    if white_bishops.popcount() == 1
+        && black_bishops.popcount() == 1
+        && (white_bishops & LIGHT_SQUARES).is_empty() != (black_bishops & LIGHT_SQUARES).is_empty()
+    {
+        let only_bishops = [Side::White, Side::Black].iter().all(|&side| {
+            signature.count(side, Piece::Queen)
+                + signature.count(side, Piece::Rook)
+                + signature.count(side, Piece::Knight)
+                == 0
+        });
+        return if only_bishops {
+            OPPOSITE_BISHOPS_SCALE
+        } else {
+            OPPOSITE_BISHOPS_WITH_PIECES_SCALE
+        };
+    }
+    SCALE_NORMAL
+}
+
+// King, bishop and rook pawns on a single edge file, against a bare king sitting next to
+// a promotion square the bishop does not control
+fn is_wrong_rook_pawn(board: &Board, signature: &MaterialSignature, strong: Side) -> bool {
+    let pawns = board.get_pieces(strong, Piece::Pawn);
+    let has_only_bishop_and_pawns = signature.count(strong, Piece::Bishop) == 1
+        && signature.count(strong, Piece::Queen) == 0
+        && signature.count(strong, Piece::Rook) == 0
+        && signature.count(strong, Piece::Knight) == 0
+        && !pawns.is_empty();
+    if !has_only_bishop_and_pawns || !signature.is_bare_king(strong.other()) {
+        return false;
+    }
+
+    let rook_file = if (pawns & !FILE_MASKS[Files::A as usize]).is_empty() {
+        Files::A as usize
+    } else if (pawns & !FILE_MASKS[Files::H as usize]).is_empty() {
+        Files::H as usize
+    } else {
+        return false;
+    };
+    let promotion_square = match strong {
+        Side::White => Square::from_coords(rook_file, NumOf::RANKS - 1),
+        Side::Black => Square::from_coords(rook_file, 0),
+    };
+    let bishop_on_light = !(board.get_pieces(strong, Piece::Bishop) & LIGHT_SQUARES).is_empty();
+    let promotion_on_light = LIGHT_SQUARES.is_set(promotion_square);
+    bishop_on_light != promotion_on_light
+        && square_distance(king_square(board, strong.other()), promotion_square) <= 1
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn endgame_score(fen: &str) -> Option<i32> {
+        let board = Board::from_fen(fen).unwrap();
+        evaluate_endgame(&board, &MaterialSignature::new(&board))
+    }
+
+    #[test]
+    fn signature_codes() {
+        let board = Board::from_fen("8/8/8/4k3/8/8/8/KBN5 w - - 0 1").unwrap();
+        let signature = MaterialSignature::new(&board);
+        assert_eq!(MaterialSignature::from_code("KBNK"), Some(signature));
+        assert_eq!(signature.endgame(), Some((Endgame::Kbnk, Side::White)));
+        assert_eq!(
+            signature.flipped().endgame(),
+            Some((Endgame::Kbnk, Side::Black))
+        );
+        assert_eq!(MaterialSignature::from_code("KRX"), None);
+        assert_eq!(MaterialSignature::from_code("QK"), None);
+        assert_eq!(
+            MaterialSignature::from_code("KQRK").unwrap().endgame(),
+            Some((Endgame::Kxk, Side::White))
+        );
+        assert_eq!(
+            MaterialSignature::from_code("KRKN").unwrap().endgame(),
+            None
+        );
+    }
+
+    #[test]
+    fn kpk_bitbase() {
+        // Unstoppable promotion, for either colour
+        assert!(endgame_score("8/4P3/8/8/8/8/k7/7K w - - 0 1").unwrap() > KNOWN_WIN);
+        assert!(endgame_score("7k/K7/8/8/8/8/4p3/8 b - - 0 1").unwrap() < -KNOWN_WIN);
+        // King on the sixth in front of its pawn wins whoever moves
+        assert!(endgame_score("4k3/8/4K3/4P3/8/8/8/8 w - - 0 1").unwrap() > KNOWN_WIN);
+        assert!(endgame_score("4k3/8/4K3/4P3/8/8/8/8 b - - 0 1").unwrap() > KNOWN_WIN);
+        // Defender in the rook pawn's corner, and a pawn that simply gets taken
+        assert_eq!(endgame_score("k7/8/8/8/8/8/P7/K7 w - - 0 1"), Some(0));
+        assert_eq!(endgame_score("8/8/8/8/8/3kP3/8/7K b - - 0 1"), Some(0));
+        // Mirrored onto the kingside
+        assert_eq!(endgame_score("7k/8/8/8/8/8/7P/7K w - - 0 1"), Some(0));
+    }
+
+    #[test]
+    fn mating_material_drives_king_to_the_edge() {
+        let centre = endgame_score("8/8/8/3k4/8/8/8/KR6 w - - 0 1").unwrap();
+        let edge = endgame_score("k7/8/8/8/8/8/8/KR6 w - - 0 1").unwrap();
+        assert!(centre > KNOWN_WIN);
+        assert!(edge > centre);
+        assert!(endgame_score("3k4/8/8/8/8/8/8/KQ6 b - - 0 1").unwrap() > KNOWN_WIN);
+    }
+
+    #[test]
+    fn material_comes_from_the_board_params() {
+        use crate::eval::params::{self, ActiveParams, PsqtParams};
+
+        let mut custom = PsqtParams::DEFAULT;
+        custom.piece_values[Piece::Rook].eg += 100;
+        custom.piece_values[Piece::Pawn].eg += 50;
+        let custom: &'static ActiveParams = Box::leak(Box::new(ActiveParams::new(custom)));
+        let score = |fen: &str, params: &'static ActiveParams| {
+            let mut board = Board::from_fen(fen).unwrap();
+            board.params = params;
+            evaluate_endgame(&board, &MaterialSignature::new(&board)).unwrap()
+        };
+        for (fen, bonus) in [
+            ("k7/8/8/8/8/8/8/KR6 w - - 0 1", 100),
+            ("8/4P3/8/8/8/8/k7/7K w - - 0 1", 50),
+        ] {
+            assert_eq!(
+                score(fen, custom) - score(fen, params::active()),
+                bonus,
+                "{fen}"
+            );
+        }
+    }
+
+    #[test]
+    fn kbnk_drives_king_to_the_bishop_corner() {
+        // Dark squared bishop: a1 and h8 are the mating corners
+        let right_corner = endgame_score("7k/8/8/8/8/8/8/1KB1N3 w - - 0 1").unwrap();
+        let wrong_corner = endgame_score("k7/8/8/8/8/8/8/1KB1N3 w - - 0 1").unwrap();
+        assert!(right_corner > wrong_corner);
+    }
+
+    #[test]
+    fn drawish_material_is_scaled_down() {
+        let wrong_bishop = Board::from_fen("k7/8/8/8/8/P7/8/1KB5 w - - 0 1").unwrap();
+        let right_bishop = Board::from_fen("k7/8/8/8/8/P7/8/1K1B4 w - - 0 1").unwrap();
+        let scale = |board: &Board| scale_factor(board, &MaterialSignature::new(board));
+        assert_eq!(scale(&wrong_bishop), SCALE_DRAW);
+        assert_eq!(scale(&right_bishop), SCALE_NORMAL);
+
+        let opposite_bishops = Board::from_fen("4k3/3b4/8/8/8/8/3B4/4K3 w - - 0 1").unwrap();
+        let same_bishops = Board::from_fen("4k3/4b3/8/8/8/8/3B4/4K3 w - - 0 1").unwrap();
+        assert_eq!(scale(&opposite_bishops), OPPOSITE_BISHOPS_SCALE);
+        assert_eq!(scale(&same_bishops), SCALE_NORMAL);
+    }
+}
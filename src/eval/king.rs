@@ -0,0 +1,202 @@
+use crate::{
+    board::{
+        Board,
+        types::{Piece, Side, Square},
+    },
+    eval::{
+        attacks::AttackInfo,
+        pawns::{PASSED_PAWN_MASKS, relative_rank},
+        score::Score,
+        trace::{Param, Tracer},
+    },
+    movement::ATTACKS,
+    types::{Bitboard, FILE_MASKS, NumOf},
+};
+
+// Indexed by the relative rank (0 = first rank) of the closest friendly pawn in front of the
+// king on each of the three shelter files. No pawn can stand on its own first rank, so
+// index 0 stands for a file with no shield pawn at all.
+pub(crate) const PAWN_SHIELD: [Score; NumOf::RANKS] = [
+    Score::new(-25, 0),
+    Score::new(25, 0),
+    Score::new(15, 0),
+    Score::new(5, 0),
+    Score::new(0, 0),
+    Score::new(0, 0),
+    Score::new(0, 0),
+    Score::new(0, 0),
+];
+// Same indexing, from the king's side of the board, for the closest enemy pawn coming down
+// each shelter file
+pub(crate) const PAWN_STORM: [Score; NumOf::RANKS] = [
+    Score::new(0, 0),
+    Score::new(-10, 0),
+    Score::new(-30, 0),
+    Score::new(-20, 0),
+    Score::new(-10, 0),
+    Score::new(-5, 0),
+    Score::new(0, 0),
+    Score::new(0, 0),
+];
+pub(crate) const KING_SEMI_OPEN_FILE: Score = Score::new(-10, 0);
+pub(crate) const KING_OPEN_FILE: Score = Score::new(-20, 0);
+
+// Indexed by Piece. Only counted once at least two pieces attack the zone.
+pub(crate) const KING_ATTACKER_WEIGHT: [Score; NumOf::PIECE_TYPES] = [
+    Score::new(0, 0),
+    Score::new(-25, -5),
+    Score::new(-15, -3),
+    Score::new(-10, -2),
+    Score::new(-10, -2),
+    Score::new(0, 0),
+];
+pub(crate) const KING_ZONE_ATTACK: Score = Score::new(-6, 0);
+const MIN_KING_ATTACKERS: i32 = 2;
+pub(crate) const SAFE_CHECK: [Score; NumOf::PIECE_TYPES] = [
+    Score::new(0, 0),
+    Score::new(-30, -10),
+    Score::new(-40, -10),
+    Score::new(-20, -5),
+    Score::new(-35, -10),
+    Score::new(0, 0),
+];
+
+// Pawn shield, pawn storm and open files around the king. Only pawns and kings are
+// involved, so this is computed once per pawn hash entry.
+pub fn evaluate_shelter<T: Tracer>(board: &Board, side: Side, trace: &mut T) -> Score {
+    let king = board.get_pieces(side, Piece::King);
+    if king.is_empty() {
+        return Score::ZERO;
+    }
+    let king_square = king.lsb();
+    let own_pawns = board.get_pieces(side, Piece::Pawn);
+    let enemy_pawns = board.get_pieces(side.other(), Piece::Pawn);
+
+    // Keep the three shelter files on the board for a king on the a or h file
+    let center_file = king_square.file().clamp(1, NumOf::FILES - 2);
+    let center_square = Square::from_coords(center_file, king_square.rank());
+    let in_front = PASSED_PAWN_MASKS[side as usize][center_square];
+
+    let mut score = Score::ZERO;
+    for file_mask in &FILE_MASKS[center_file - 1..=center_file + 1] {
+        let own_in_front = own_pawns & in_front & *file_mask;
+        let enemy_in_front = enemy_pawns & in_front & *file_mask;
+        let shield_rank = closest_rank(own_in_front, side);
+        let storm_rank = closest_rank(enemy_in_front, side);
+        score += PAWN_SHIELD[shield_rank] + PAWN_STORM[storm_rank];
+        trace.add(Param::PAWN_SHIELD + shield_rank, side, 1);
+        trace.add(Param::PAWN_STORM + storm_rank, side, 1);
+
+        if (own_pawns & *file_mask).is_empty() {
+            if (enemy_pawns & *file_mask).is_empty() {
+                score += KING_OPEN_FILE;
+                trace.add(Param::KING_OPEN_FILE, side, 1);
+            } else {
+                score += KING_SEMI_OPEN_FILE;
+                trace.add(Param::KING_SEMI_OPEN_FILE, side, 1);
+            }
+        }
+    }
+    score
+}
+
+// Relative rank, from `side`'s point of view, of the pawn closest to `side`'s back rank
+fn closest_rank(pawns: Bitboard, side: Side) -> usize {
+    if pawns.is_empty() {
+        return 0;
+    }
+    let square = match side {
+        Side::White => pawns.lsb(),
+        Side::Black => pawns.msb(),
+    };
+    relative_rank(side, square)
+}
+
+// Pieces swarming the king zone and safe checks available to the opponent. Needs the
+// attack maps of both sides, so it runs after evaluate_pieces.
+pub fn evaluate_king_danger<T: Tracer>(
+    board: &Board,
+    info: &AttackInfo,
+    side: Side,
+    trace: &mut T,
+) -> Score {
+    let enemy = side.other();
+    let mut score = Score::ZERO;
+
+    let attackers = &info.king_attackers[enemy as usize];
+    if attackers.iter().sum::<i32>() >= MIN_KING_ATTACKERS {
+        for (piece, (weight, count)) in KING_ATTACKER_WEIGHT
+            .iter()
+            .zip(attackers.iter())
+            .enumerate()
+        {
+            score += *weight * *count;
+            trace.add(Param::KING_ATTACKER_WEIGHT + piece, side, *count);
+        }
+        let zone_attacks = info.king_zone_attacks[enemy as usize];
+        score += KING_ZONE_ATTACK * zone_attacks;
+        trace.add(Param::KING_ZONE_ATTACK, side, zone_attacks);
+    }
+
+    let king = board.get_pieces(side, Piece::King);
+    if king.is_empty() {
+        return score;
+    }
+    let king_square = king.lsb();
+    let occupancy = info.occupancy;
+    let safe = !info.attacked[side as usize] & !board.bb_sides[enemy as usize];
+    let rook_checks = ATTACKS.get_rook_attacks(king_square, occupancy) & safe;
+    let bishop_checks = ATTACKS.get_bishop_attacks(king_square, occupancy) & safe;
+    let knight_checks = ATTACKS.knight_attacks[king_square] & safe;
+
+    let enemy_attacks = &info.attacked_by[enemy as usize];
+    if !((rook_checks | bishop_checks) & enemy_attacks[Piece::Queen]).is_empty() {
+        score += SAFE_CHECK[Piece::Queen];
+        trace.add(Param::SAFE_CHECK + Piece::Queen.index(), side, 1);
+    }
+    if !(rook_checks & enemy_attacks[Piece::Rook]).is_empty() {
+        score += SAFE_CHECK[Piece::Rook];
+        trace.add(Param::SAFE_CHECK + Piece::Rook.index(), side, 1);
+    }
+    if !(bishop_checks & enemy_attacks[Piece::Bishop]).is_empty() {
+        score += SAFE_CHECK[Piece::Bishop];
+        trace.add(Param::SAFE_CHECK + Piece::Bishop.index(), side, 1);
+    }
+    if !(knight_checks & enemy_attacks[Piece::Knight]).is_empty() {
+        score += SAFE_CHECK[Piece::Knight];
+        trace.add(Param::SAFE_CHECK + Piece::Knight.index(), side, 1);
+    }
+    score
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::eval::trace::NoTrace;
+
+    fn shelter(fen: &str, side: Side) -> Score {
+        let board = Board::from_fen(fen).unwrap();
+        evaluate_shelter(&board, side, &mut NoTrace)
+    }
+
+    #[test]
+    fn intact_shield_beats_broken_one() {
+        let intact = shelter("6k1/5ppp/8/8/8/8/5PPP/6K1 w - - 0 1", Side::White);
+        let advanced = shelter("6k1/5ppp/8/8/8/6PP/5P2/6K1 w - - 0 1", Side::White);
+        let missing = shelter("6k1/5ppp/8/8/8/8/5P1P/6K1 w - - 0 1", Side::White);
+        assert!(intact.mg > advanced.mg);
+        assert!(advanced.mg > missing.mg);
+        // Same structure seen from Black
+        assert_eq!(
+            intact,
+            shelter("6k1/5ppp/8/8/8/8/5PPP/6K1 w - - 0 1", Side::Black)
+        );
+    }
+
+    #[test]
+    fn storming_pawns_are_a_threat() {
+        let calm = shelter("6k1/8/8/8/8/8/5PPP/6K1 w - - 0 1", Side::White);
+        let storm = shelter("6k1/8/8/8/6p1/8/5PPP/6K1 w - - 0 1", Side::White);
+        assert!(storm.mg < calm.mg);
+    }
+}
@@ -0,0 +1,161 @@
+use std::sync::OnceLock;
+
+use crate::{
+    board::types::{Side, Square},
+    movement::ATTACKS,
+    types::NumOf,
+};
+
+// King and pawn vs king, with the pawn side normalised to White and the pawn mirrored onto
+// files a-d: 2 sides to move * 24 pawn squares * 64 * 64 king squares
+const MAX_INDEX: usize = 2 * 24 * NumOf::SQUARES * NumOf::SQUARES;
+const WORD_BITS: usize = u64::BITS as usize;
+
+// Outcomes are bit flags so the results of all successors can be OR-ed together
+type Outcome = u8;
+const INVALID: Outcome = 0b000;
+const UNKNOWN: Outcome = 0b001;
+const DRAW: Outcome = 0b010;
+const WIN: Outcome = 0b100;
+
+// One bit per position, set when White wins. Built on first use.
+static KPK_BITBASE: OnceLock<Vec<u64>> = OnceLock::new();
+
+// The pawn must already be on files a-d, see evaluate_kpk for the mirroring
+pub fn probe(white_to_move: bool, white_king: Square, pawn: Square, black_king: Square) -> bool {
+    debug_assert!(pawn.file() < 4, "Pawn not mirrored onto files a-d: {pawn}");
+    let bitbase = KPK_BITBASE.get_or_init(generate);
+    let idx = index(white_to_move, white_king, black_king, pawn);
+    bitbase[idx / WORD_BITS] & (1u64 << (idx % WORD_BITS)) != 0
+}
+
+#[inline(always)]
+fn index(white_to_move: bool, white_king: Square, black_king: Square, pawn: Square) -> usize {
+    white_king.index()
+        | (black_king.index() << 6)
+        | ((!white_to_move as usize) << 12)
+        | (pawn.file() << 13)
+        | ((NumOf::RANKS - 2 - pawn.rank()) << 15)
+}
+
+struct KpkPosition {
+    white_to_move: bool,
+    white_king: Square,
+    black_king: Square,
+    pawn: Square,
+    outcome: Outcome,
+}
+
+impl KpkPosition {
+    fn new(idx: usize) -> Self {
+        let king_attacks = &ATTACKS.king_attacks;
+        let white_king = Square::from_index(idx & 0x3f);
+        let black_king = Square::from_index((idx >> 6) & 0x3f);
+        let white_to_move = (idx >> 12) & 1 == 0;
+        let file = (idx >> 13) & 0x3;
+        let rank = NumOf::RANKS - 2 - ((idx >> 15) & 0x7);
+        let pawn = Square::from_coords(file, rank);
+        let promotion_square = pawn.step(8);
+        let pawn_attacks = ATTACKS.pawn_attacks[Side::White][pawn];
+
+        let outcome = if king_attacks[white_king].is_set(black_king)
+            || white_king == black_king
+            || white_king == pawn
+            || black_king == pawn
+            || (white_to_move && pawn_attacks.is_set(black_king))
+        {
+            INVALID
+        } else if white_to_move
+            && rank == NumOf::RANKS - 2
+            && white_king != promotion_square
+            && black_king != promotion_square
+            && (!king_attacks[black_king].is_set(promotion_square)
+                || king_attacks[white_king].is_set(promotion_square))
+        {
+            // Promotes and the new queen cannot be taken
+            WIN
+        } else if !white_to_move
+            && ((king_attacks[black_king] & !(king_attacks[white_king] | pawn_attacks)).is_empty()
+                || (king_attacks[black_king].is_set(pawn)
+                    && !king_attacks[white_king].is_set(pawn)))
+        {
+            // Stalemated, or the undefended pawn is taken
+            DRAW
+        } else {
+            UNKNOWN
+        };
+
+        Self {
+            white_to_move,
+            white_king,
+            black_king,
+            pawn,
+            outcome,
+        }
+    }
+
+    fn classify(&self, db: &[KpkPosition]) -> Outcome {
+        let king_attacks = &ATTACKS.king_attacks;
+        let mut successors = INVALID;
+        if self.white_to_move {
+            for square in king_attacks[self.white_king] {
+                successors |= db[index(false, square, self.black_king, self.pawn)].outcome;
+            }
+            let rank = self.pawn.rank();
+            let push = self.pawn.step(8);
+            if rank < NumOf::RANKS - 2 {
+                successors |= db[index(false, self.white_king, self.black_king, push)].outcome;
+            }
+            if rank == 1 && push != self.white_king && push != self.black_king {
+                let double_push = push.step(8);
+                successors |=
+                    db[index(false, self.white_king, self.black_king, double_push)].outcome;
+            }
+            if successors & WIN != 0 {
+                WIN
+            } else if successors & UNKNOWN != 0 {
+                UNKNOWN
+            } else {
+                DRAW
+            }
+        } else {
+            for square in king_attacks[self.black_king] {
+                successors |= db[index(true, self.white_king, square, self.pawn)].outcome;
+            }
+            if successors & DRAW != 0 {
+                DRAW
+            } else if successors & UNKNOWN != 0 {
+                UNKNOWN
+            } else {
+                WIN
+            }
+        }
+    }
+}
+
+// Retrograde analysis: start from the positions whose outcome is known immediately and
+// keep propagating until nothing changes. Whatever is still unknown then is a draw.
+fn generate() -> Vec<u64> {
+    let mut db: Vec<KpkPosition> = (0..MAX_INDEX).map(KpkPosition::new).collect();
+    let mut changed = true;
+    while changed {
+        changed = false;
+        for idx in 0..MAX_INDEX {
+            if db[idx].outcome == UNKNOWN {
+                let outcome = db[idx].classify(&db);
+                if outcome != UNKNOWN {
+                    db[idx].outcome = outcome;
+                    changed = true;
+                }
+            }
+        }
+    }
+
+    let mut bitbase = vec![0u64; MAX_INDEX.div_ceil(WORD_BITS)];
+    for (idx, position) in db.iter().enumerate() {
+        if position.outcome == WIN {
+            bitbase[idx / WORD_BITS] |= 1u64 << (idx % WORD_BITS);
+        }
+    }
+    bitbase
+}
@@ -0,0 +1,317 @@
+pub mod accumulator;
+mod simd;
+
+use std::{fmt::Display, path::Path, sync::Arc};
+
+use crate::{
+    board::{
+        Board,
+        types::{Piece, Side, Square},
+    },
+    eval::{params, psqt::PsqtTable, score::MAX_PHASE},
+    types::NumOf,
+};
+use accumulator::{Accumulator, AccumulatorStack};
+
+// (side relative to the perspective, piece, square) for each perspective: 2 * 6 * 64 inputs
+pub const INPUTS: usize = NumOf::SIDES * NumOf::PIECE_TYPES * NumOf::SQUARES;
+pub const HIDDEN: usize = 256;
+
+// Feature transformer outputs are quantised by QA, output weights by QB
+const QA: i32 = 255;
+const QB: i32 = 64;
+// Converts the network output to centipawns
+const SCALE: i32 = 400;
+
+const NETWORK_VALUES: usize = INPUTS * HIDDEN + HIDDEN + NumOf::SIDES * HIDDEN + 1;
+pub const NETWORK_BYTES: usize = NETWORK_VALUES * size_of::<i16>();
+
+// Hidden neurons per ramp in a psqt net, each passing a QA wide slice of the sum through
+// the clipped ReLU. Together they cover evaluations up to RAMPS * QA / 2 centipawns.
+const RAMPS: usize = 32;
+
+#[derive(Debug)]
+pub enum NnueError {
+    Io(std::io::Error),
+    WrongSize { expected: usize, found: usize },
+}
+
+impl Display for NnueError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            NnueError::Io(err) => write!(f, "Could not read the network file: {err}"),
+            NnueError::WrongSize { expected, found } => write!(
+                f,
+                "Network file has the wrong size: expected {expected} bytes, found {found}"
+            ),
+        }
+    }
+}
+
+// 768 -> 2x256 -> 1 perspective network with a clipped ReLU. The file is a flat list of
+// little-endian i16: feature weights (input-major), feature biases, output weights (side
+// to move's half first) and the output bias, quantised by QA * QB.
+pub struct Network {
+    feature_weights: Vec<i16>,
+    feature_bias: Vec<i16>,
+    output_weights: Vec<i16>,
+    output_bias: i16,
+}
+
+impl Network {
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, NnueError> {
+        if bytes.len() != NETWORK_BYTES {
+            return Err(NnueError::WrongSize {
+                expected: NETWORK_BYTES,
+                found: bytes.len(),
+            });
+        }
+        let mut values = bytes
+            .chunks_exact(size_of::<i16>())
+            .map(|pair| i16::from_le_bytes([pair[0], pair[1]]));
+        let mut take = |n: usize| -> Vec<i16> { values.by_ref().take(n).collect() };
+        let feature_weights = take(INPUTS * HIDDEN);
+        let feature_bias = take(HIDDEN);
+        let output_weights = take(NumOf::SIDES * HIDDEN);
+        let output_bias = take(1)[0];
+        Ok(Self {
+            feature_weights,
+            feature_bias,
+            output_weights,
+            output_bias,
+        })
+    }
+
+    pub fn load(path: &Path) -> Result<Self, NnueError> {
+        let bytes = std::fs::read(path).map_err(NnueError::Io)?;
+        Self::from_bytes(&bytes)
+    }
+
+    // No trained network exists yet, so the default one is built from the active square
+    // tables when it is asked for rather than shipped as a file
+    pub fn from_active_params() -> Self {
+        Self::from_psqt(&params::active().psqt)
+    }
+
+    // The network behind the EvalFile option: the file at `path`, or the default one for
+    // None. A file that fails to load is reported rather than quietly replaced, so a typo
+    // in a path never goes unnoticed.
+    pub fn for_eval_file(path: Option<&Path>) -> Result<Self, NnueError> {
+        match path {
+            Some(path) => Self::load(path),
+            None => Ok(Self::from_active_params()),
+        }
+    }
+
+    // A network that reproduces a square table sum, tapered halfway between middlegame and
+    // endgame. Each perspective accumulates its own pieces minus the opponent's, RAMPS
+    // neurons with staggered biases turn that into a piecewise linear ramp, and the output
+    // takes the side to move's ramp minus the opponent's. Evaluations come out about 2%
+    // low, as the output weight is rounded down from 20.4.
+    pub fn from_psqt(table: &PsqtTable) -> Self {
+        let mut feature_weights = vec![0i16; INPUTS * HIDDEN];
+        for piece in Piece::ALL {
+            for square in Square::ALL {
+                // Own pieces are seen from White's side of the board, see feature_index
+                let own = table[piece][square.flip_rank()].taper(MAX_PHASE / 2) as i16;
+                let theirs = -table[piece][square].taper(MAX_PHASE / 2) as i16;
+                for (side_offset, value) in [(0, own), (1, theirs)] {
+                    let feature = (side_offset * NumOf::PIECE_TYPES + piece.index())
+                        * NumOf::SQUARES
+                        + square.index();
+                    feature_weights[feature * HIDDEN..feature * HIDDEN + RAMPS].fill(value);
+                }
+            }
+        }
+        let half_range = (RAMPS as i32 / 2) * QA;
+        let mut feature_bias = vec![0i16; HIDDEN];
+        for (ramp, bias) in feature_bias[..RAMPS].iter_mut().enumerate() {
+            *bias = (half_range - ramp as i32 * QA) as i16;
+        }
+        let weight = (QA * QB / (2 * SCALE)) as i16;
+        let mut output_weights = vec![0i16; NumOf::SIDES * HIDDEN];
+        output_weights[..RAMPS].fill(weight);
+        output_weights[HIDDEN..HIDDEN + RAMPS].fill(-weight);
+        Self {
+            feature_weights,
+            feature_bias,
+            output_weights,
+            output_bias: 0,
+        }
+    }
+
+    // The inverse of from_bytes
+    pub fn to_bytes(&self) -> Vec<u8> {
+        self.feature_weights
+            .iter()
+            .chain(&self.feature_bias)
+            .chain(&self.output_weights)
+            .chain(std::iter::once(&self.output_bias))
+            .flat_map(|value| value.to_le_bytes())
+            .collect()
+    }
+
+    #[inline(always)]
+    pub fn feature_bias(&self) -> &[i16] {
+        &self.feature_bias
+    }
+
+    #[inline(always)]
+    pub fn feature_weights(&self, feature: usize) -> &[i16] {
+        &self.feature_weights[feature * HIDDEN..(feature + 1) * HIDDEN]
+    }
+
+    // Centipawns from the point of view of the side to move
+    pub fn evaluate(&self, accumulator: &Accumulator, side_to_move: Side) -> i32 {
+        let (own_weights, their_weights) = self.output_weights.split_at(HIDDEN);
+        let sum = simd::crelu_dot(&accumulator.values[side_to_move as usize], own_weights)
+            + simd::crelu_dot(
+                &accumulator.values[side_to_move.other() as usize],
+                their_weights,
+            );
+        (sum + self.output_bias as i32) * SCALE / (QA * QB)
+    }
+}
+
+// Input index of a piece as seen from `perspective`: own pieces come first and the board
+// is flipped vertically for Black, so both perspectives share the same weights
+#[inline(always)]
+pub fn feature_index(perspective: Side, side: Side, piece: Piece, square: Square) -> usize {
+    let (side_offset, square) = match perspective {
+        Side::White => (side as usize ^ 1, square),
+        Side::Black => (side as usize, square.flip_rank()),
+    };
+    (side_offset * NumOf::PIECE_TYPES + piece.index()) * NumOf::SQUARES + square.index()
+}
+
+// A network and the accumulators tracking the search path
+pub struct NnueState {
+    pub network: Arc<Network>,
+    pub accumulators: AccumulatorStack,
+}
+
+impl NnueState {
+    pub fn new(network: Arc<Network>, board: &mut Board) -> Self {
+        let mut accumulators = AccumulatorStack::new();
+        accumulators.refresh(&network, board);
+        Self {
+            network,
+            accumulators,
+        }
+    }
+
+    pub fn evaluate(&self, board: &Board) -> i32 {
+        debug_assert!(
+            board.feature_updates.is_empty(),
+            "Accumulator not pushed after the last move"
+        );
+        self.network
+            .evaluate(self.accumulators.current(), board.get_current_player())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::piece_movement::{Move, MoveFlag};
+    use rand::{Rng, SeedableRng};
+    use rand_pcg::Pcg64;
+
+    pub(super) fn random_network(seed: u64) -> Network {
+        let mut rng = Pcg64::seed_from_u64(seed);
+        let bytes: Vec<u8> = (0..NETWORK_VALUES)
+            .flat_map(|_| rng.random_range(-64i16..=64).to_le_bytes())
+            .collect();
+        Network::from_bytes(&bytes).unwrap()
+    }
+
+    #[test]
+    fn rejects_truncated_file() {
+        let result = Network::from_bytes(&vec![0u8; NETWORK_BYTES - 2]);
+        assert!(matches!(result, Err(NnueError::WrongSize { .. })));
+    }
+
+    #[test]
+    fn incremental_updates_match_refresh() {
+        let network = Arc::new(random_network(1));
+        let mut board =
+            Board::from_fen("r3k2r/p1pp1ppp/8/3p4/4P3/8/PPPP1PPP/R3K2R w KQkq - 0 1").unwrap();
+        let mut nnue = NnueState::new(network.clone(), &mut board);
+
+        // exd5, then ...O-O-O: a capture and a move of two pieces
+        board.make(Move::new(Square::E4, Square::D5, MoveFlag::Capture as u8));
+        nnue.accumulators.push(&network, &mut board);
+        board.make(Move::new(
+            Square::E8,
+            Square::C8,
+            MoveFlag::QueenSideCastle as u8,
+        ));
+        nnue.accumulators.push(&network, &mut board);
+
+        let incremental = nnue.evaluate(&board);
+        let mut fresh = board.clone();
+        assert_eq!(
+            incremental,
+            NnueState::new(network.clone(), &mut fresh).evaluate(&fresh)
+        );
+
+        // Popping brings back the accumulator of the earlier ply
+        nnue.accumulators.pop();
+        let mut earlier =
+            Board::from_fen("r3k2r/p1pp1ppp/8/3P4/8/8/PPPP1PPP/R3K2R b KQkq - 0 1").unwrap();
+        assert_eq!(
+            nnue.accumulators.current().values,
+            Accumulator::new(&network, &earlier).values
+        );
+        assert_eq!(
+            nnue.network
+                .evaluate(nnue.accumulators.current(), Side::Black),
+            NnueState::new(network, &mut earlier).evaluate(&earlier)
+        );
+    }
+
+    #[test]
+    fn default_net_follows_the_square_tables() {
+        let network = Arc::new(Network::from_active_params());
+        let bytes = network.to_bytes();
+        assert_eq!(Network::from_bytes(&bytes).unwrap().to_bytes(), bytes);
+        for fen in [
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+            "r1bqkbnr/pppp1ppp/2n5/4p3/4P3/5N2/PPPP1PPP/RNBQKB1R w KQkq - 2 3",
+            "4k3/8/8/8/8/8/8/3QK3 b - - 0 1",
+            "6k1/5ppp/8/8/8/8/1q3PPP/6K1 w - - 0 1",
+        ] {
+            let mut board = Board::from_fen(fen).unwrap();
+            let stm = board.get_current_player();
+            let psqt = (board.game_state.psqt[stm as usize]
+                - board.game_state.psqt[stm.other() as usize])
+                .taper(MAX_PHASE / 2);
+            let nnue = NnueState::new(network.clone(), &mut board).evaluate(&board);
+            assert!(
+                (nnue - psqt).abs() <= psqt.abs() / 40 + 1,
+                "{fen}: {nnue} vs {psqt}"
+            );
+        }
+    }
+
+    #[test]
+    fn eval_file_defaults_to_the_square_table_net() {
+        let network = Network::for_eval_file(None).unwrap();
+        assert_eq!(network.to_bytes(), Network::from_active_params().to_bytes());
+        let missing = Network::for_eval_file(Some(Path::new("no/such/net.nnue")));
+        assert!(matches!(missing, Err(NnueError::Io(_))));
+    }
+
+    #[test]
+    fn perspectives_are_symmetric() {
+        let network = Arc::new(random_network(2));
+        let mut white =
+            Board::from_fen("r3k2r/pp3ppp/2n5/3p4/8/2N2B2/PPP2PPP/R3K2R w - - 0 1").unwrap();
+        let mut black =
+            Board::from_fen("r3k2r/ppp2ppp/2n2b2/8/3P4/2N5/PP3PPP/R3K2R b - - 0 1").unwrap();
+        assert_eq!(
+            NnueState::new(network.clone(), &mut white).evaluate(&white),
+            NnueState::new(network, &mut black).evaluate(&black)
+        );
+    }
+}
@@ -0,0 +1,209 @@
+use crate::{
+    board::{
+        Board,
+        types::{Piece, Side, Square},
+    },
+    eval::nnue::{HIDDEN, Network, feature_index, simd},
+    types::NumOf,
+};
+
+// A move adds at most two pieces (the moved piece and a promotion or castling rook) and
+// removes at most three. Anything bigger, like setting up a position, asks for a refresh.
+const MAX_UPDATES: usize = 4;
+
+#[derive(Debug, Clone, Copy)]
+struct PieceSquare {
+    side: Side,
+    piece: Piece,
+    square: Square,
+}
+
+// Pieces put on and taken off the board since the accumulator was last brought up to
+// date. Recorded by put_piece/remove_piece, consumed by AccumulatorStack::push.
+#[derive(Debug, Clone, Copy)]
+pub struct FeatureUpdates {
+    added: [PieceSquare; MAX_UPDATES],
+    removed: [PieceSquare; MAX_UPDATES],
+    num_added: usize,
+    num_removed: usize,
+    needs_refresh: bool,
+}
+
+impl FeatureUpdates {
+    const EMPTY_SLOT: PieceSquare = PieceSquare {
+        side: Side::White,
+        piece: Piece::King,
+        square: Square::A1,
+    };
+
+    fn empty() -> Self {
+        Self {
+            added: [Self::EMPTY_SLOT; MAX_UPDATES],
+            removed: [Self::EMPTY_SLOT; MAX_UPDATES],
+            num_added: 0,
+            num_removed: 0,
+            needs_refresh: false,
+        }
+    }
+
+    // For a board whose pieces were placed from scratch
+    pub fn new() -> Self {
+        Self {
+            needs_refresh: true,
+            ..Self::empty()
+        }
+    }
+
+    #[inline(always)]
+    pub fn add(&mut self, side: Side, piece: Piece, square: Square) {
+        if self.num_added == MAX_UPDATES {
+            self.needs_refresh = true;
+        } else {
+            self.added[self.num_added] = PieceSquare {
+                side,
+                piece,
+                square,
+            };
+            self.num_added += 1;
+        }
+    }
+
+    #[inline(always)]
+    pub fn remove(&mut self, side: Side, piece: Piece, square: Square) {
+        if self.num_removed == MAX_UPDATES {
+            self.needs_refresh = true;
+        } else {
+            self.removed[self.num_removed] = PieceSquare {
+                side,
+                piece,
+                square,
+            };
+            self.num_removed += 1;
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.num_added == 0 && self.num_removed == 0 && !self.needs_refresh
+    }
+
+    pub(crate) fn take(&mut self) -> Self {
+        std::mem::replace(self, Self::empty())
+    }
+}
+
+impl Default for FeatureUpdates {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// Feature transformer output for both perspectives, indexed by Side
+#[derive(Clone, Copy)]
+#[repr(C, align(64))]
+pub struct Accumulator {
+    pub values: [[i16; HIDDEN]; NumOf::SIDES],
+}
+
+impl Accumulator {
+    pub fn new(network: &Network, board: &Board) -> Self {
+        let mut bias = [0i16; HIDDEN];
+        bias.copy_from_slice(network.feature_bias());
+        let mut accumulator = Self {
+            values: [bias; NumOf::SIDES],
+        };
+        for side in [Side::White, Side::Black] {
+            for piece in Piece::ALL {
+                for square in board.bb_pieces[side as usize][piece] {
+                    accumulator.add(
+                        network,
+                        PieceSquare {
+                            side,
+                            piece,
+                            square,
+                        },
+                    );
+                }
+            }
+        }
+        accumulator
+    }
+
+    fn add(&mut self, network: &Network, ps: PieceSquare) {
+        for perspective in [Side::White, Side::Black] {
+            let feature = feature_index(perspective, ps.side, ps.piece, ps.square);
+            simd::add_weights(
+                &mut self.values[perspective as usize],
+                network.feature_weights(feature),
+            );
+        }
+    }
+
+    fn remove(&mut self, network: &Network, ps: PieceSquare) {
+        for perspective in [Side::White, Side::Black] {
+            let feature = feature_index(perspective, ps.side, ps.piece, ps.square);
+            simd::sub_weights(
+                &mut self.values[perspective as usize],
+                network.feature_weights(feature),
+            );
+        }
+    }
+}
+
+// Deep enough for any search path, so the stack never reallocates
+const MAX_PLY: usize = 128;
+
+// One accumulator per ply of the current search path
+pub struct AccumulatorStack {
+    stack: Vec<Accumulator>,
+}
+
+impl AccumulatorStack {
+    pub fn new() -> Self {
+        Self {
+            stack: Vec::with_capacity(MAX_PLY),
+        }
+    }
+
+    // Starts over from the board's current position
+    pub fn refresh(&mut self, network: &Network, board: &mut Board) {
+        board.feature_updates.take();
+        self.stack.clear();
+        self.stack.push(Accumulator::new(network, board));
+    }
+
+    // Call after Board::make: copies the previous ply's accumulator and applies the pieces
+    // the move put on and took off the board
+    pub fn push(&mut self, network: &Network, board: &mut Board) {
+        let updates = board.feature_updates.take();
+        let accumulator = match self.stack.last() {
+            Some(previous) if !updates.needs_refresh => {
+                let mut accumulator = *previous;
+                for &ps in &updates.removed[..updates.num_removed] {
+                    accumulator.remove(network, ps);
+                }
+                for &ps in &updates.added[..updates.num_added] {
+                    accumulator.add(network, ps);
+                }
+                accumulator
+            }
+            _ => Accumulator::new(network, board),
+        };
+        self.stack.push(accumulator);
+    }
+
+    // Call on unmake
+    pub fn pop(&mut self) {
+        debug_assert!(self.stack.len() > 1, "Popped the root accumulator");
+        self.stack.pop();
+    }
+
+    pub fn current(&self) -> &Accumulator {
+        self.stack.last().expect("Accumulator stack not refreshed")
+    }
+}
+
+impl Default for AccumulatorStack {
+    fn default() -> Self {
+        Self::new()
+    }
+}
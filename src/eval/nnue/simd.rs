@@ -0,0 +1,158 @@
+use crate::eval::nnue::{HIDDEN, QA};
+
+// Sum of clamp(input, 0, QA) * weight over one accumulator half
+#[inline(always)]
+pub fn crelu_dot(inputs: &[i16; HIDDEN], weights: &[i16]) -> i32 {
+    #[cfg(target_arch = "x86_64")]
+    if std::arch::is_x86_feature_detected!("avx2") {
+        // SAFETY: AVX2 support was just checked
+        return unsafe { avx2::crelu_dot(inputs, weights) };
+    }
+    scalar::crelu_dot(inputs, weights)
+}
+
+// values += weights, for a piece put on the board
+#[inline(always)]
+pub fn add_weights(values: &mut [i16; HIDDEN], weights: &[i16]) {
+    #[cfg(target_arch = "x86_64")]
+    if std::arch::is_x86_feature_detected!("avx2") {
+        // SAFETY: AVX2 support was just checked
+        return unsafe { avx2::add_weights(values, weights) };
+    }
+    scalar::add_weights(values, weights)
+}
+
+// values -= weights, for a piece taken off the board
+#[inline(always)]
+pub fn sub_weights(values: &mut [i16; HIDDEN], weights: &[i16]) {
+    #[cfg(target_arch = "x86_64")]
+    if std::arch::is_x86_feature_detected!("avx2") {
+        // SAFETY: AVX2 support was just checked
+        return unsafe { avx2::sub_weights(values, weights) };
+    }
+    scalar::sub_weights(values, weights)
+}
+
+mod scalar {
+    use super::*;
+
+    pub fn crelu_dot(inputs: &[i16; HIDDEN], weights: &[i16]) -> i32 {
+        inputs
+            .iter()
+            .zip(weights)
+            .map(|(&input, &weight)| (input as i32).clamp(0, QA) * weight as i32)
+            .sum()
+    }
+
+    pub fn add_weights(values: &mut [i16; HIDDEN], weights: &[i16]) {
+        for (value, weight) in values.iter_mut().zip(weights) {
+            *value += weight;
+        }
+    }
+
+    pub fn sub_weights(values: &mut [i16; HIDDEN], weights: &[i16]) {
+        for (value, weight) in values.iter_mut().zip(weights) {
+            *value -= weight;
+        }
+    }
+}
+
+#[cfg(target_arch = "x86_64")]
+mod avx2 {
+    use super::*;
+    use std::arch::x86_64::*;
+
+    const LANES: usize = 16;
+
+    #[target_feature(enable = "avx2")]
+    pub unsafe fn crelu_dot(inputs: &[i16; HIDDEN], weights: &[i16]) -> i32 {
+        assert_eq!(weights.len(), HIDDEN);
+        let zero = _mm256_setzero_si256();
+        let qa = _mm256_set1_epi16(QA as i16);
+        let mut sum = _mm256_setzero_si256();
+        for (input, weight) in inputs.chunks_exact(LANES).zip(weights.chunks_exact(LANES)) {
+            // SAFETY: both chunks hold exactly LANES i16, and loadu has no alignment needs
+            let (input, weight) = unsafe {
+                (
+                    _mm256_loadu_si256(input.as_ptr().cast()),
+                    _mm256_loadu_si256(weight.as_ptr().cast()),
+                )
+            };
+            let clipped = _mm256_min_epi16(_mm256_max_epi16(input, zero), qa);
+            // Multiplies the i16 pairs and adds neighbours into i32 lanes
+            sum = _mm256_add_epi32(sum, _mm256_madd_epi16(clipped, weight));
+        }
+        let sum = _mm_add_epi32(
+            _mm256_castsi256_si128(sum),
+            _mm256_extracti128_si256::<1>(sum),
+        );
+        let sum = _mm_add_epi32(sum, _mm_shuffle_epi32::<0b01_00_11_10>(sum));
+        let sum = _mm_add_epi32(sum, _mm_shuffle_epi32::<0b10_11_00_01>(sum));
+        _mm_cvtsi128_si32(sum)
+    }
+
+    #[target_feature(enable = "avx2")]
+    pub unsafe fn add_weights(values: &mut [i16; HIDDEN], weights: &[i16]) {
+        assert_eq!(weights.len(), HIDDEN);
+        for (value, weight) in values
+            .chunks_exact_mut(LANES)
+            .zip(weights.chunks_exact(LANES))
+        {
+            // SAFETY: both chunks hold exactly LANES i16, and loadu/storeu have no
+            // alignment needs
+            unsafe {
+                let sum = _mm256_add_epi16(
+                    _mm256_loadu_si256(value.as_ptr().cast()),
+                    _mm256_loadu_si256(weight.as_ptr().cast()),
+                );
+                _mm256_storeu_si256(value.as_mut_ptr().cast(), sum);
+            }
+        }
+    }
+
+    #[target_feature(enable = "avx2")]
+    pub unsafe fn sub_weights(values: &mut [i16; HIDDEN], weights: &[i16]) {
+        assert_eq!(weights.len(), HIDDEN);
+        for (value, weight) in values
+            .chunks_exact_mut(LANES)
+            .zip(weights.chunks_exact(LANES))
+        {
+            // SAFETY: as in add_weights
+            unsafe {
+                let difference = _mm256_sub_epi16(
+                    _mm256_loadu_si256(value.as_ptr().cast()),
+                    _mm256_loadu_si256(weight.as_ptr().cast()),
+                );
+                _mm256_storeu_si256(value.as_mut_ptr().cast(), difference);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn simd_matches_scalar() {
+        let mut inputs = [0i16; HIDDEN];
+        let mut weights = [0i16; HIDDEN];
+        for i in 0..HIDDEN {
+            // Covers negative inputs and inputs above QA
+            inputs[i] = (i as i16 * 7) % 600 - 150;
+            weights[i] = (i as i16 * 13) % 255 - 127;
+        }
+        let expected = scalar::crelu_dot(&inputs, &weights);
+        assert_eq!(crelu_dot(&inputs, &weights), expected);
+
+        let mut expected = inputs;
+        scalar::add_weights(&mut expected, &weights);
+        let mut values = inputs;
+        add_weights(&mut values, &weights);
+        assert_eq!(values, expected);
+        scalar::sub_weights(&mut expected, &weights);
+        sub_weights(&mut values, &weights);
+        assert_eq!(values, inputs);
+        assert_eq!(values, expected);
+    }
+}
@@ -0,0 +1,420 @@
+use std::{
+    array,
+    fmt::{Display, Write},
+    path::Path,
+    ptr,
+    sync::atomic::{AtomicPtr, Ordering},
+};
+
+use crate::{
+    eval::{
+        psqt::{EG_TABLES, MG_TABLES, PIECE_VALUES, PsqtTable, Table, fold_psqt},
+        score::Score,
+    },
+    types::NumOf,
+};
+
+// Piece values and square tables can be swapped for a parameter file at runtime, so tuned
+// sets can be played against each other without recompiling. A file looks like
+//
+//     flying-goose-params 1
+//     # anything after a '#' is a comment
+//     PIECE_VALUES 0 0 1025 936 ...   (mg eg for each piece, king first)
+//     MG_KING -65 23 16 ...           (64 values, rank 8 first as in psqt.rs)
+//     EG_KING -74 -35 -18 ...
+//     ...
+//     checksum 711a67314e0338ef
+//
+// with every table present exactly once. The checksum is FNV-1a over the values, so
+// reformatting a file keeps it valid but a changed number does not.
+pub const PARAMS_VERSION: u32 = 1;
+const HEADER: &str = "flying-goose-params";
+const CHECKSUM: &str = "checksum";
+const PIECE_VALUES_NAME: &str = "PIECE_VALUES";
+const PIECE_NAMES: [&str; NumOf::PIECE_TYPES] =
+    ["KING", "QUEEN", "ROOK", "BISHOP", "KNIGHT", "PAWN"];
+
+#[derive(Debug)]
+pub enum ParamError {
+    Io(std::io::Error),
+    MissingHeader,
+    UnsupportedVersion(String),
+    UnknownTable(String),
+    DuplicateTable(String),
+    MissingTable(String),
+    WrongLength {
+        table: String,
+        expected: usize,
+        found: usize,
+    },
+    InvalidValue {
+        line: usize,
+        value: String,
+    },
+    MissingChecksum,
+    ChecksumMismatch {
+        expected: u64,
+        found: u64,
+    },
+}
+
+impl Display for ParamError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ParamError::Io(err) => write!(f, "Could not read the parameter file: {err}"),
+            ParamError::MissingHeader => write!(f, "Parameter file does not start with {HEADER}"),
+            ParamError::UnsupportedVersion(version) => write!(
+                f,
+                "Parameter file version {version} is not supported, expected {PARAMS_VERSION}"
+            ),
+            ParamError::UnknownTable(table) => write!(f, "Unknown table {table}"),
+            ParamError::DuplicateTable(table) => write!(f, "Table {table} appears twice"),
+            ParamError::MissingTable(table) => write!(f, "Table {table} is missing"),
+            ParamError::WrongLength {
+                table,
+                expected,
+                found,
+            } => write!(
+                f,
+                "Table {table} has the wrong length: expected {expected} values, found {found}"
+            ),
+            ParamError::InvalidValue { line, value } => {
+                write!(f, "Invalid value {value:?} on line {line}")
+            }
+            ParamError::MissingChecksum => write!(f, "Parameter file has no checksum"),
+            ParamError::ChecksumMismatch { expected, found } => write!(
+                f,
+                "Checksum mismatch: the file says {expected:016x}, the values give {found:016x}"
+            ),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct PsqtParams {
+    pub piece_values: [Score; NumOf::PIECE_TYPES],
+    pub mg_tables: [Table; NumOf::PIECE_TYPES],
+    pub eg_tables: [Table; NumOf::PIECE_TYPES],
+}
+
+impl PsqtParams {
+    pub const DEFAULT: Self = Self {
+        piece_values: PIECE_VALUES,
+        mg_tables: MG_TABLES,
+        eg_tables: EG_TABLES,
+    };
+
+    // Every value in the order the tables are written out
+    fn values(&self) -> impl Iterator<Item = i32> + '_ {
+        let piece_values = self.piece_values.iter().flat_map(|s| [s.mg, s.eg]);
+        let tables = self
+            .mg_tables
+            .iter()
+            .zip(self.eg_tables.iter())
+            .flat_map(|(mg, eg)| mg.iter().chain(eg.iter()).copied());
+        piece_values.chain(tables)
+    }
+
+    pub fn checksum(&self) -> u64 {
+        const FNV_OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+        const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+        self.values()
+            .flat_map(i32::to_le_bytes)
+            .fold(FNV_OFFSET, |hash, byte| {
+                (hash ^ byte as u64).wrapping_mul(FNV_PRIME)
+            })
+    }
+
+    pub fn parse(text: &str) -> Result<Self, ParamError> {
+        let mut lines = text
+            .lines()
+            .enumerate()
+            .map(|(index, line)| (index + 1, line.split('#').next().unwrap_or("").trim()))
+            .filter(|(_, line)| !line.is_empty());
+
+        let (_, header) = lines.next().ok_or(ParamError::MissingHeader)?;
+        match header.split_whitespace().collect::<Vec<_>>()[..] {
+            [HEADER, version] if version == PARAMS_VERSION.to_string() => {}
+            [HEADER, version] => return Err(ParamError::UnsupportedVersion(version.to_string())),
+            _ => return Err(ParamError::MissingHeader),
+        }
+
+        let mut piece_values = None;
+        let mut mg_tables = [None; NumOf::PIECE_TYPES];
+        let mut eg_tables = [None; NumOf::PIECE_TYPES];
+        let mut checksum = None;
+        for (line_number, line) in lines {
+            let mut words = line.split_whitespace();
+            let name = words.next().unwrap_or("");
+            if name == CHECKSUM {
+                let value = words.next().unwrap_or("");
+                let parsed =
+                    u64::from_str_radix(value, 16).map_err(|_| ParamError::InvalidValue {
+                        line: line_number,
+                        value: value.to_string(),
+                    })?;
+                checksum = Some(parsed);
+                continue;
+            }
+            let values = words
+                .map(|word| {
+                    word.parse::<i32>().map_err(|_| ParamError::InvalidValue {
+                        line: line_number,
+                        value: word.to_string(),
+                    })
+                })
+                .collect::<Result<Vec<i32>, _>>()?;
+
+            if name == PIECE_VALUES_NAME {
+                check_length(name, &values, 2 * NumOf::PIECE_TYPES)?;
+                let scores =
+                    array::from_fn(|piece| Score::new(values[2 * piece], values[2 * piece + 1]));
+                set_once(&mut piece_values, name, scores)?;
+                continue;
+            }
+            let (slots, piece_name) = match name.split_once('_') {
+                Some(("MG", piece_name)) => (&mut mg_tables, piece_name),
+                Some(("EG", piece_name)) => (&mut eg_tables, piece_name),
+                _ => return Err(ParamError::UnknownTable(name.to_string())),
+            };
+            let piece = PIECE_NAMES
+                .iter()
+                .position(|&piece| piece == piece_name)
+                .ok_or_else(|| ParamError::UnknownTable(name.to_string()))?;
+            check_length(name, &values, NumOf::SQUARES)?;
+            set_once(
+                &mut slots[piece],
+                name,
+                array::from_fn(|square| values[square]),
+            )?;
+        }
+
+        let params = Self {
+            piece_values: piece_values
+                .ok_or_else(|| ParamError::MissingTable(PIECE_VALUES_NAME.to_string()))?,
+            mg_tables: collect_tables(mg_tables, "MG")?,
+            eg_tables: collect_tables(eg_tables, "EG")?,
+        };
+        let expected = checksum.ok_or(ParamError::MissingChecksum)?;
+        let found = params.checksum();
+        if expected != found {
+            return Err(ParamError::ChecksumMismatch { expected, found });
+        }
+        Ok(params)
+    }
+
+    pub fn load(path: &Path) -> Result<Self, ParamError> {
+        let text = std::fs::read_to_string(path).map_err(ParamError::Io)?;
+        Self::parse(&text)
+    }
+
+    // The parameters in the format `parse` reads, checksum included
+    pub fn to_file_string(&self) -> String {
+        let mut out = format!("{HEADER} {PARAMS_VERSION}\n");
+        out.push_str(PIECE_VALUES_NAME);
+        for score in &self.piece_values {
+            let _ = write!(out, " {} {}", score.mg, score.eg);
+        }
+        out.push('\n');
+        for (piece, name) in PIECE_NAMES.iter().enumerate() {
+            for (prefix, table) in [
+                ("MG", &self.mg_tables[piece]),
+                ("EG", &self.eg_tables[piece]),
+            ] {
+                let _ = write!(out, "{prefix}_{name}");
+                for value in table {
+                    let _ = write!(out, " {value}");
+                }
+                out.push('\n');
+            }
+        }
+        let _ = writeln!(out, "{CHECKSUM} {:016x}", self.checksum());
+        out
+    }
+}
+
+fn check_length(name: &str, values: &[i32], expected: usize) -> Result<(), ParamError> {
+    if values.len() != expected {
+        return Err(ParamError::WrongLength {
+            table: name.to_string(),
+            expected,
+            found: values.len(),
+        });
+    }
+    Ok(())
+}
+
+fn set_once<T>(slot: &mut Option<T>, name: &str, value: T) -> Result<(), ParamError> {
+    if slot.is_some() {
+        return Err(ParamError::DuplicateTable(name.to_string()));
+    }
+    *slot = Some(value);
+    Ok(())
+}
+
+fn collect_tables(
+    tables: [Option<Table>; NumOf::PIECE_TYPES],
+    prefix: &str,
+) -> Result<[Table; NumOf::PIECE_TYPES], ParamError> {
+    let mut out = [[0; NumOf::SQUARES]; NumOf::PIECE_TYPES];
+    for (piece, table) in tables.into_iter().enumerate() {
+        out[piece] = table
+            .ok_or_else(|| ParamError::MissingTable(format!("{prefix}_{}", PIECE_NAMES[piece])))?;
+    }
+    Ok(out)
+}
+
+// A parameter set together with the tables the board reads while making moves
+#[derive(Debug)]
+pub struct ActiveParams {
+    pub params: PsqtParams,
+    pub psqt: PsqtTable,
+}
+
+impl ActiveParams {
+    pub(crate) fn new(params: PsqtParams) -> Self {
+        let psqt = fold_psqt(&params.piece_values, &params.mg_tables, &params.eg_tables);
+        Self { params, psqt }
+    }
+}
+
+static DEFAULT: ActiveParams = ActiveParams {
+    params: PsqtParams::DEFAULT,
+    psqt: fold_psqt(&PIECE_VALUES, &MG_TABLES, &EG_TABLES),
+};
+
+// Null until a parameter file is selected, so the compiled-in set needs no setting up
+static ACTIVE: AtomicPtr<ActiveParams> = AtomicPtr::new(ptr::null_mut());
+
+#[inline(always)]
+pub fn active() -> &'static ActiveParams {
+    let active = ACTIVE.load(Ordering::Acquire);
+    if active.is_null() {
+        &DEFAULT
+    } else {
+        // Only ever set from a leaked Box in `set_active`, so it lives for the whole process
+        unsafe { &*active }
+    }
+}
+
+// Boards hold on to the set that was active when they were set up and keep using it for
+// their running psqt sum, so a new set only applies to boards set up after the call.
+// Replaced sets are leaked on purpose: boards may still point at one, and they are swapped
+// a handful of times per process at most.
+pub fn set_active(params: PsqtParams) {
+    let active = if params == PsqtParams::DEFAULT {
+        ptr::null_mut()
+    } else {
+        Box::into_raw(Box::new(ActiveParams::new(params)))
+    };
+    ACTIVE.store(active, Ordering::Release);
+}
+
+// Switches to the parameters in `path`, or back to the compiled-in ones for None. A file
+// that fails to load leaves the compiled-in parameters active and returns the error for
+// the caller to report, so a typo in a path never goes unnoticed in a test run.
+pub fn select_file(path: Option<&Path>) -> Result<(), ParamError> {
+    let Some(path) = path else {
+        set_active(PsqtParams::DEFAULT);
+        return Ok(());
+    };
+    match PsqtParams::load(path) {
+        Ok(params) => {
+            set_active(params);
+            Ok(())
+        }
+        Err(err) => {
+            set_active(PsqtParams::DEFAULT);
+            Err(err)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn file_round_trips() {
+        let mut params = PsqtParams::DEFAULT;
+        params.piece_values[1] = Score::new(1000, 950);
+        params.eg_tables[5][20] = -7;
+        let text = params.to_file_string();
+        assert_eq!(PsqtParams::parse(&text).unwrap(), params);
+        // Comments and spacing do not matter
+        let reformatted = format!("# tuned set\n{}", text.replace(' ', "   "));
+        assert_eq!(PsqtParams::parse(&reformatted).unwrap(), params);
+    }
+
+    #[test]
+    fn rejects_bad_files() {
+        let text = PsqtParams::DEFAULT.to_file_string();
+        assert!(matches!(
+            PsqtParams::parse(&text.replace("PIECE_VALUES 0 0", "PIECE_VALUES 0 1")),
+            Err(ParamError::ChecksumMismatch { .. })
+        ));
+        assert!(matches!(
+            PsqtParams::parse(&text.replace("flying-goose-params 1", "flying-goose-params 2")),
+            Err(ParamError::UnsupportedVersion(_))
+        ));
+        let without_checksum: String = text
+            .lines()
+            .filter(|l| !l.starts_with(CHECKSUM))
+            .collect::<Vec<_>>()
+            .join("\n");
+        assert!(matches!(
+            PsqtParams::parse(&without_checksum),
+            Err(ParamError::MissingChecksum)
+        ));
+        let without_table: String = text
+            .lines()
+            .filter(|l| !l.starts_with("EG_PAWN"))
+            .collect::<Vec<_>>()
+            .join("\n");
+        assert!(matches!(
+            PsqtParams::parse(&without_table),
+            Err(ParamError::MissingTable(table)) if table == "EG_PAWN"
+        ));
+        assert!(matches!(
+            PsqtParams::parse(&text.replace("MG_KING -65 ", "MG_KING ")),
+            Err(ParamError::WrongLength { found: 63, .. })
+        ));
+    }
+
+    #[test]
+    fn boards_keep_the_set_they_were_set_up_with() {
+        use crate::board::{
+            Board,
+            types::{Piece, Side, Square},
+        };
+        let mut params = PsqtParams::DEFAULT;
+        params.mg_tables[Piece::Pawn.index()][Square::E4.flip_rank().index()] += 100;
+        let custom: &'static ActiveParams = Box::leak(Box::new(ActiveParams::new(params)));
+
+        // Swapping the global set would race the other tests, so point a board at the
+        // custom set directly, as setting it up after set_active would
+        let mut board = Board::init();
+        board.fen_setup(None).unwrap();
+        board.params = custom;
+        let before = board.game_state.psqt[Side::White as usize];
+        let chess_move = board.parse_uci("e2e4").unwrap();
+        board.make(chess_move);
+        let expected = custom.psqt[Piece::Pawn][Square::E4.flip_rank()]
+            - custom.psqt[Piece::Pawn][Square::E2.flip_rank()];
+        assert_eq!(
+            board.game_state.psqt[Side::White as usize] - before,
+            expected
+        );
+        assert_eq!(
+            expected.mg - DEFAULT.psqt[Piece::Pawn][Square::E4.flip_rank()].mg
+                + DEFAULT.psqt[Piece::Pawn][Square::E2.flip_rank()].mg,
+            100
+        );
+    }
+
+    #[test]
+    fn default_set_folds_like_the_compiled_tables() {
+        let folded = ActiveParams::new(PsqtParams::DEFAULT);
+        assert_eq!(folded.psqt, DEFAULT.psqt);
+    }
+}
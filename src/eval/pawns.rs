@@ -0,0 +1,357 @@
+use crate::{
+    board::{
+        Board,
+        types::{Piece, Side, Square},
+        zobrist::ZobristKey,
+    },
+    eval::{
+        king::evaluate_shelter,
+        score::Score,
+        trace::{NoTrace, Param, Tracer},
+    },
+    types::{Bitboard, EMPTY_BITBOARD, FILE_MASKS, NumOf, RANK_MASKS, SQUARE_MASKS},
+};
+
+// Indexed by the rank of the pawn as seen from its own side (0 = first rank)
+pub(crate) const PASSED_PAWN: [Score; NumOf::RANKS] = [
+    Score::new(0, 0),
+    Score::new(5, 10),
+    Score::new(5, 15),
+    Score::new(10, 25),
+    Score::new(25, 50),
+    Score::new(45, 90),
+    Score::new(70, 140),
+    Score::new(0, 0),
+];
+// On top of PASSED_PAWN when nothing stands on the squares in front of the passer
+pub(crate) const PASSED_PAWN_FREE_PATH: [Score; NumOf::RANKS] = [
+    Score::new(0, 0),
+    Score::new(0, 5),
+    Score::new(0, 5),
+    Score::new(5, 10),
+    Score::new(10, 20),
+    Score::new(15, 35),
+    Score::new(25, 60),
+    Score::new(0, 0),
+];
+pub(crate) const CANDIDATE_PASSER: [Score; NumOf::RANKS] = [
+    Score::new(0, 0),
+    Score::new(5, 5),
+    Score::new(5, 10),
+    Score::new(10, 15),
+    Score::new(15, 25),
+    Score::new(20, 40),
+    Score::new(0, 0),
+    Score::new(0, 0),
+];
+// Pawns defended by another pawn or standing next to one (phalanx)
+pub(crate) const CONNECTED_PAWN: [Score; NumOf::RANKS] = [
+    Score::new(0, 0),
+    Score::new(5, 0),
+    Score::new(7, 3),
+    Score::new(10, 6),
+    Score::new(15, 12),
+    Score::new(25, 25),
+    Score::new(40, 40),
+    Score::new(0, 0),
+];
+pub(crate) const ISOLATED_PAWN: Score = Score::new(-10, -12);
+pub(crate) const DOUBLED_PAWN: Score = Score::new(-8, -20);
+pub(crate) const BACKWARD_PAWN: Score = Score::new(-8, -10);
+
+// Squares in front of a pawn on its own file, from the pawn owner's point of view
+pub const FORWARD_FILE_MASKS: [[Bitboard; NumOf::SQUARES]; NumOf::SIDES] =
+    generate_forward_masks(false);
+// Squares in front of a pawn on its own and both adjacent files: a pawn with no enemy
+// pawns in there is passed
+pub const PASSED_PAWN_MASKS: [[Bitboard; NumOf::SQUARES]; NumOf::SIDES] =
+    generate_forward_masks(true);
+pub const ADJACENT_FILE_MASKS: [Bitboard; NumOf::FILES] = generate_adjacent_file_masks();
+
+const fn generate_adjacent_file_masks() -> [Bitboard; NumOf::FILES] {
+    let mut masks = [EMPTY_BITBOARD; NumOf::FILES];
+    let mut file = 0;
+    while file < NumOf::FILES {
+        if file > 0 {
+            masks[file].0 |= FILE_MASKS[file - 1].0;
+        }
+        if file < NumOf::FILES - 1 {
+            masks[file].0 |= FILE_MASKS[file + 1].0;
+        }
+        file += 1;
+    }
+    masks
+}
+
+const fn generate_forward_masks(
+    include_adjacent_files: bool,
+) -> [[Bitboard; NumOf::SQUARES]; NumOf::SIDES] {
+    let adjacent_file_masks = generate_adjacent_file_masks();
+    let mut masks = [[EMPTY_BITBOARD; NumOf::SQUARES]; NumOf::SIDES];
+    let mut square = 0;
+    while square < NumOf::SQUARES {
+        let file = square % NumOf::FILES;
+        let rank = square / NumOf::FILES;
+        let mut files = FILE_MASKS[file].0;
+        if include_adjacent_files {
+            files |= adjacent_file_masks[file].0;
+        }
+        let mut other_rank = 0;
+        while other_rank < NumOf::RANKS {
+            if other_rank > rank {
+                masks[Side::White as usize][square].0 |= files & RANK_MASKS[other_rank].0;
+            }
+            if other_rank < rank {
+                masks[Side::Black as usize][square].0 |= files & RANK_MASKS[other_rank].0;
+            }
+            other_rank += 1;
+        }
+        square += 1;
+    }
+    masks
+}
+
+#[inline(always)]
+pub fn relative_rank(side: Side, square: Square) -> usize {
+    match side {
+        Side::White => square.rank(),
+        Side::Black => NumOf::RANKS - 1 - square.rank(),
+    }
+}
+
+// Every square attacked by at least one of the given pawns
+#[inline(always)]
+pub fn pawn_attacks(pawns: Bitboard, side: Side) -> Bitboard {
+    let forward = match side {
+        Side::White => 1,
+        Side::Black => -1,
+    };
+    pawns.shift((1, forward)) | pawns.shift((-1, forward))
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PawnEntry {
+    pub key: ZobristKey,
+    // White's point of view
+    pub score: Score,
+    pub passed: [Bitboard; NumOf::SIDES],
+}
+
+impl PawnEntry {
+    fn new(board: &Board) -> Self {
+        Self::with_trace(board, &mut NoTrace)
+    }
+
+    // Bypasses the table, since cached entries do not carry a trace
+    pub fn with_trace<T: Tracer>(board: &Board, trace: &mut T) -> Self {
+        let white = evaluate_pawns(board, Side::White, trace);
+        let black = evaluate_pawns(board, Side::Black, trace);
+        let mut passed = [EMPTY_BITBOARD; NumOf::SIDES];
+        passed[Side::White as usize] = white.1;
+        passed[Side::Black as usize] = black.1;
+        let shelter = evaluate_shelter(board, Side::White, trace)
+            - evaluate_shelter(board, Side::Black, trace);
+        Self {
+            key: board.game_state.pawn_key,
+            score: white.0 - black.0 + shelter,
+            passed,
+        }
+    }
+}
+
+// Pawn structure only changes on pawn and king moves, so the terms that depend on it
+// alone (king shelter included) are cached here under GameState::pawn_key.
+pub struct PawnHashTable {
+    entries: Vec<PawnEntry>,
+}
+
+impl PawnHashTable {
+    pub const DEFAULT_ENTRIES: usize = 1 << 14;
+
+    pub fn new(entries: usize) -> Self {
+        let entries = entries.next_power_of_two();
+        Self {
+            entries: vec![PawnEntry::default(); entries],
+        }
+    }
+
+    pub fn probe(&mut self, board: &Board) -> &PawnEntry {
+        let key = board.game_state.pawn_key;
+        let index = key as usize & (self.entries.len() - 1);
+        if self.entries[index].key != key {
+            self.entries[index] = PawnEntry::new(board);
+        }
+        &self.entries[index]
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.fill(PawnEntry::default());
+    }
+}
+
+impl Default for PawnHashTable {
+    fn default() -> Self {
+        Self::new(Self::DEFAULT_ENTRIES)
+    }
+}
+
+fn evaluate_pawns<T: Tracer>(board: &Board, side: Side, trace: &mut T) -> (Score, Bitboard) {
+    let own_pawns = board.get_pieces(side, Piece::Pawn);
+    let enemy_pawns = board.get_pieces(side.other(), Piece::Pawn);
+    let own_attacks = pawn_attacks(own_pawns, side);
+    let enemy_attacks = pawn_attacks(enemy_pawns, side.other());
+
+    let mut score = Score::ZERO;
+    let mut passed = EMPTY_BITBOARD;
+    for square in own_pawns {
+        let rank = relative_rank(side, square);
+        let forward = FORWARD_FILE_MASKS[side as usize][square];
+        let ahead = PASSED_PAWN_MASKS[side as usize][square];
+        let behind = PASSED_PAWN_MASKS[side.other() as usize][square];
+        let adjacent = ADJACENT_FILE_MASKS[square.file()];
+
+        let doubled = !(own_pawns & forward).is_empty();
+        let isolated = (own_pawns & adjacent).is_empty();
+        let supported = own_attacks.is_set(square);
+        let phalanx = !(own_pawns & adjacent & RANK_MASKS[square.rank()]).is_empty();
+
+        if doubled {
+            score += DOUBLED_PAWN;
+            trace.add(Param::DOUBLED_PAWN, side, 1);
+        }
+        if isolated {
+            score += ISOLATED_PAWN;
+            trace.add(Param::ISOLATED_PAWN, side, 1);
+        }
+        if supported || phalanx {
+            score += CONNECTED_PAWN[rank];
+            trace.add(Param::CONNECTED_PAWN + rank, side, 1);
+        }
+
+        // No neighbour left behind that could come up to defend it, and it cannot advance
+        // safely either
+        if !isolated && !supported && !phalanx && (own_pawns & adjacent & behind).is_empty() {
+            let stop_square = match side {
+                Side::White => square.step(8),
+                Side::Black => square.step(-8),
+            };
+            if enemy_attacks.is_set(stop_square) {
+                score += BACKWARD_PAWN;
+                trace.add(Param::BACKWARD_PAWN, side, 1);
+            }
+        }
+
+        if !doubled && (enemy_pawns & ahead).is_empty() {
+            passed |= SQUARE_MASKS[square];
+            score += PASSED_PAWN[rank];
+            trace.add(Param::PASSED_PAWN + rank, side, 1);
+        } else if !doubled && (enemy_pawns & forward).is_empty() {
+            // Semi-open file ahead: a candidate if the pawns that can help it through
+            // outnumber the enemy pawns guarding its path
+            let sentries = (enemy_pawns & ahead).popcount();
+            let helpers = (own_pawns & adjacent & (behind | RANK_MASKS[square.rank()])).popcount();
+            if helpers >= sentries {
+                score += CANDIDATE_PASSER[rank];
+                trace.add(Param::CANDIDATE_PASSER + rank, side, 1);
+            }
+        }
+    }
+    (score, passed)
+}
+
+// The part of the passed pawn evaluation that depends on pieces other than pawns and
+// kings, so it cannot live in the pawn hash table
+pub fn evaluate_passed_pawns<T: Tracer>(
+    board: &Board,
+    passed: Bitboard,
+    side: Side,
+    trace: &mut T,
+) -> Score {
+    let occupancy = board.bb_sides[Side::White as usize] | board.bb_sides[Side::Black as usize];
+    let mut score = Score::ZERO;
+    for square in passed {
+        if (FORWARD_FILE_MASKS[side as usize][square] & occupancy).is_empty() {
+            let rank = relative_rank(side, square);
+            score += PASSED_PAWN_FREE_PATH[rank];
+            trace.add(Param::PASSED_PAWN_FREE_PATH + rank, side, 1);
+        }
+    }
+    score
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn masks_point_towards_promotion() {
+        // e4
+        assert_eq!(
+            FORWARD_FILE_MASKS[Side::White as usize][28],
+            FILE_MASKS[4] & !(RANK_MASKS[0] | RANK_MASKS[1] | RANK_MASKS[2] | RANK_MASKS[3])
+        );
+        assert_eq!(
+            FORWARD_FILE_MASKS[Side::Black as usize][28],
+            FILE_MASKS[4] & (RANK_MASKS[0] | RANK_MASKS[1] | RANK_MASKS[2])
+        );
+        assert_eq!(ADJACENT_FILE_MASKS[0], FILE_MASKS[1]);
+        assert_eq!(PASSED_PAWN_MASKS[Side::White as usize][48].popcount(), 2);
+        assert_eq!(relative_rank(Side::Black, Square::A7), 1);
+    }
+
+    #[test]
+    fn detects_passed_pawns() {
+        // White: a5 passed, e4 blocked by e5. Black: h3 passed
+        let board = Board::from_fen("4k3/8/8/P3p3/4P3/7p/8/4K3 w - - 0 1").unwrap();
+        let entry = PawnEntry::new(&board);
+        assert_eq!(entry.passed[Side::White as usize], SQUARE_MASKS[32]);
+        assert_eq!(entry.passed[Side::Black as usize], SQUARE_MASKS[23]);
+    }
+
+    #[test]
+    fn structural_weaknesses_are_penalised() {
+        let healthy = Board::from_fen("4k3/pppp4/8/8/8/8/PPPP4/4K3 w - - 0 1").unwrap();
+        let doubled = Board::from_fen("4k3/pppp4/8/8/8/2P5/PPP5/4K3 w - - 0 1").unwrap();
+        let isolated = Board::from_fen("4k3/pppp4/8/8/8/8/P1PP4/4K3 w - - 0 1").unwrap();
+        let healthy = PawnEntry::new(&healthy).score;
+        assert_eq!(healthy, Score::ZERO);
+        assert!(PawnEntry::new(&doubled).score.eg < healthy.eg);
+        assert!(PawnEntry::new(&isolated).score.mg < healthy.mg);
+    }
+
+    #[test]
+    fn free_path_is_scored_outside_the_cache() {
+        let free = Board::from_fen("4k3/8/1P6/8/8/8/8/4K3 w - - 0 1").unwrap();
+        let blocked = Board::from_fen("1n2k3/8/1P6/8/8/8/8/4K3 w - - 0 1").unwrap();
+        let passed = SQUARE_MASKS[41];
+        assert!(evaluate_passed_pawns(&free, passed, Side::White, &mut NoTrace) != Score::ZERO);
+        assert_eq!(
+            evaluate_passed_pawns(&blocked, passed, Side::White, &mut NoTrace),
+            Score::ZERO
+        );
+    }
+
+    #[test]
+    fn pawn_key_ignores_pieces_other_than_pawns_and_kings() {
+        let mut board = Board::from_fen("4k3/pp6/8/8/8/8/PP6/1N2K3 w - - 0 1").unwrap();
+        let key = board.game_state.pawn_key;
+        board.move_piece(Piece::Knight, Side::White, Square::B1, Square::C3);
+        assert_eq!(board.game_state.pawn_key, key);
+
+        board.move_piece(Piece::Pawn, Side::White, Square::A2, Square::A3);
+        assert_ne!(board.game_state.pawn_key, key);
+        let expected = Board::from_fen("4k3/pp6/8/8/8/P1N5/1P6/4K3 w - - 0 1").unwrap();
+        assert_eq!(board.game_state.pawn_key, expected.game_state.pawn_key);
+    }
+
+    #[test]
+    fn table_returns_cached_entry() {
+        let board = Board::from_fen("4k3/pp6/8/8/8/8/PP6/4K3 w - - 0 1").unwrap();
+        let mut table = PawnHashTable::new(16);
+        let first = *table.probe(&board);
+        assert_eq!(first.key, board.game_state.pawn_key);
+        let second = *table.probe(&board);
+        assert_eq!(first.score, second.score);
+        assert_eq!(first.passed, second.passed);
+    }
+}
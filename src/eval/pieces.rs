@@ -0,0 +1,258 @@
+use crate::{
+    board::{
+        Board,
+        types::{Piece, Side},
+    },
+    eval::{
+        attacks::AttackInfo,
+        pawns::{ADJACENT_FILE_MASKS, PASSED_PAWN_MASKS, relative_rank},
+        score::Score,
+        trace::{Param, Tracer},
+    },
+    movement::ATTACKS,
+    types::FILE_MASKS,
+};
+
+// Indexed by the number of safe squares the piece attacks
+pub(crate) const KNIGHT_MOBILITY: [Score; 9] = [
+    Score::new(-31, -39),
+    Score::new(-26, -28),
+    Score::new(-6, -15),
+    Score::new(-1, -8),
+    Score::new(2, 3),
+    Score::new(6, 6),
+    Score::new(11, 8),
+    Score::new(14, 10),
+    Score::new(16, 11),
+];
+pub(crate) const BISHOP_MOBILITY: [Score; 14] = [
+    Score::new(-23, -29),
+    Score::new(-10, -12),
+    Score::new(7, -4),
+    Score::new(14, 6),
+    Score::new(19, 10),
+    Score::new(26, 20),
+    Score::new(26, 28),
+    Score::new(30, 29),
+    Score::new(31, 32),
+    Score::new(34, 36),
+    Score::new(39, 39),
+    Score::new(41, 43),
+    Score::new(45, 44),
+    Score::new(48, 49),
+];
+pub(crate) const ROOK_MOBILITY: [Score; 15] = [
+    Score::new(-30, -41),
+    Score::new(-12, -7),
+    Score::new(0, 8),
+    Score::new(1, 21),
+    Score::new(2, 36),
+    Score::new(7, 50),
+    Score::new(10, 51),
+    Score::new(15, 61),
+    Score::new(20, 66),
+    Score::new(20, 69),
+    Score::new(20, 76),
+    Score::new(22, 80),
+    Score::new(28, 82),
+    Score::new(29, 85),
+    Score::new(33, 87),
+];
+pub(crate) const QUEEN_MOBILITY: [Score; 28] = [
+    Score::new(-14, -24),
+    Score::new(-8, -14),
+    Score::new(-4, -4),
+    Score::new(-4, 8),
+    Score::new(9, 19),
+    Score::new(12, 27),
+    Score::new(11, 29),
+    Score::new(18, 36),
+    Score::new(20, 38),
+    Score::new(27, 47),
+    Score::new(32, 47),
+    Score::new(34, 50),
+    Score::new(34, 62),
+    Score::new(35, 64),
+    Score::new(35, 66),
+    Score::new(35, 66),
+    Score::new(35, 68),
+    Score::new(36, 70),
+    Score::new(37, 73),
+    Score::new(38, 74),
+    Score::new(45, 76),
+    Score::new(52, 84),
+    Score::new(52, 85),
+    Score::new(53, 85),
+    Score::new(56, 89),
+    Score::new(57, 92),
+    Score::new(57, 93),
+    Score::new(59, 110),
+];
+
+pub(crate) const ROOK_OPEN_FILE: Score = Score::new(25, 10);
+pub(crate) const ROOK_SEMI_OPEN_FILE: Score = Score::new(10, 5);
+pub(crate) const BISHOP_PAIR: Score = Score::new(25, 50);
+pub(crate) const KNIGHT_OUTPOST: Score = Score::new(25, 15);
+pub(crate) const BISHOP_OUTPOST: Score = Score::new(15, 5);
+
+// Per enemy piece attacked by a cheaper one
+pub(crate) const THREAT_BY_PAWN: Score = Score::new(45, 40);
+pub(crate) const THREAT_BY_MINOR: Score = Score::new(30, 25);
+pub(crate) const THREAT_BY_ROOK: Score = Score::new(35, 20);
+
+// Mobility, rook files, bishop pair and outposts. Records the attacks of every piece in
+// `info` on the way, for the king safety and threat terms that follow.
+pub fn evaluate_pieces<T: Tracer>(
+    board: &Board,
+    info: &mut AttackInfo,
+    side: Side,
+    trace: &mut T,
+) -> Score {
+    let enemy = side.other();
+    let own_pawns = board.get_pieces(side, Piece::Pawn);
+    let enemy_pawns = board.get_pieces(enemy, Piece::Pawn);
+    let occupancy = info.occupancy;
+
+    let mut score = Score::ZERO;
+    for piece in [Piece::Knight, Piece::Bishop, Piece::Rook, Piece::Queen] {
+        for square in board.get_pieces(side, piece) {
+            let attacks = match piece {
+                Piece::Knight => ATTACKS.knight_attacks[square],
+                Piece::Bishop => ATTACKS.get_bishop_attacks(square, occupancy),
+                Piece::Rook => ATTACKS.get_rook_attacks(square, occupancy),
+                _ => ATTACKS.get_queen_attacks(square, occupancy),
+            };
+            info.add(side, piece, attacks);
+
+            let mobility = (attacks & info.mobility_area[side as usize]).popcount() as usize;
+            let (table, param): (&[Score], usize) = match piece {
+                Piece::Knight => (&KNIGHT_MOBILITY, Param::KNIGHT_MOBILITY),
+                Piece::Bishop => (&BISHOP_MOBILITY, Param::BISHOP_MOBILITY),
+                Piece::Rook => (&ROOK_MOBILITY, Param::ROOK_MOBILITY),
+                _ => (&QUEEN_MOBILITY, Param::QUEEN_MOBILITY),
+            };
+            score += table[mobility];
+            trace.add(param + mobility, side, 1);
+
+            let zone_attacks = attacks & info.king_zone[enemy as usize];
+            if !zone_attacks.is_empty() {
+                info.king_attackers[side as usize][piece] += 1;
+                info.king_zone_attacks[side as usize] += zone_attacks.popcount() as i32;
+            }
+
+            match piece {
+                Piece::Rook => {
+                    let file = FILE_MASKS[square.file()];
+                    if (file & own_pawns).is_empty() {
+                        if (file & enemy_pawns).is_empty() {
+                            score += ROOK_OPEN_FILE;
+                            trace.add(Param::ROOK_OPEN_FILE, side, 1);
+                        } else {
+                            score += ROOK_SEMI_OPEN_FILE;
+                            trace.add(Param::ROOK_SEMI_OPEN_FILE, side, 1);
+                        }
+                    }
+                }
+                Piece::Knight | Piece::Bishop => {
+                    // In the enemy half, defended by a pawn, and no enemy pawn can ever
+                    // come up to chase it away
+                    let rank = relative_rank(side, square);
+                    let defended = info.attacked_by[side as usize][Piece::Pawn].is_set(square);
+                    let chasers = PASSED_PAWN_MASKS[side as usize][square]
+                        & ADJACENT_FILE_MASKS[square.file()]
+                        & enemy_pawns;
+                    if (3..=5).contains(&rank) && defended && chasers.is_empty() {
+                        if piece == Piece::Knight {
+                            score += KNIGHT_OUTPOST;
+                            trace.add(Param::KNIGHT_OUTPOST, side, 1);
+                        } else {
+                            score += BISHOP_OUTPOST;
+                            trace.add(Param::BISHOP_OUTPOST, side, 1);
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    if board.get_pieces(side, Piece::Bishop).popcount() >= 2 {
+        score += BISHOP_PAIR;
+        trace.add(Param::BISHOP_PAIR, side, 1);
+    }
+    score
+}
+
+// Enemy pieces attacked by something worth less than them. Needs the attack maps
+// filled in by evaluate_pieces.
+pub fn evaluate_threats<T: Tracer>(
+    board: &Board,
+    info: &AttackInfo,
+    side: Side,
+    trace: &mut T,
+) -> Score {
+    let enemy = side.other();
+    let attacks = &info.attacked_by[side as usize];
+    let enemy_queens = board.get_pieces(enemy, Piece::Queen);
+    let enemy_majors = enemy_queens | board.get_pieces(enemy, Piece::Rook);
+    let enemy_minors =
+        board.get_pieces(enemy, Piece::Knight) | board.get_pieces(enemy, Piece::Bishop);
+
+    let by_pawn = attacks[Piece::Pawn] & (enemy_minors | enemy_majors);
+    let by_minor = (attacks[Piece::Knight] | attacks[Piece::Bishop]) & enemy_majors;
+    let by_rook = attacks[Piece::Rook] & enemy_queens;
+
+    let (by_pawn, by_minor, by_rook) = (
+        by_pawn.popcount() as i32,
+        by_minor.popcount() as i32,
+        by_rook.popcount() as i32,
+    );
+    trace.add(Param::THREAT_BY_PAWN, side, by_pawn);
+    trace.add(Param::THREAT_BY_MINOR, side, by_minor);
+    trace.add(Param::THREAT_BY_ROOK, side, by_rook);
+    THREAT_BY_PAWN * by_pawn + THREAT_BY_MINOR * by_minor + THREAT_BY_ROOK * by_rook
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::eval::trace::NoTrace;
+
+    fn white_pieces_and_threats(fen: &str) -> (Score, Score) {
+        let board = Board::from_fen(fen).unwrap();
+        let mut info = AttackInfo::new(&board);
+        let pieces = evaluate_pieces(&board, &mut info, Side::White, &mut NoTrace);
+        evaluate_pieces(&board, &mut info, Side::Black, &mut NoTrace);
+        (
+            pieces,
+            evaluate_threats(&board, &info, Side::White, &mut NoTrace),
+        )
+    }
+
+    #[test]
+    fn rooks_prefer_open_files() {
+        let (open, _) = white_pieces_and_threats("4k3/p7/8/8/8/8/P7/3RK3 w - - 0 1");
+        let (closed, _) = white_pieces_and_threats("4k3/3p4/8/8/8/8/3P4/3RK3 w - - 0 1");
+        assert!(open.mg > closed.mg);
+    }
+
+    #[test]
+    fn bishop_pair_and_outposts() {
+        let (pair, _) = white_pieces_and_threats("4k3/8/8/8/8/8/8/2B1KB2 w - - 0 1");
+        let (single, _) = white_pieces_and_threats("4k3/8/8/8/8/8/8/2B1K3 w - - 0 1");
+        assert!(pair.eg - single.eg >= BISHOP_PAIR.eg);
+
+        // d5 knight defended by e4, no black c/e pawn left to chase it
+        let (outpost, _) = white_pieces_and_threats("4k3/1p3p2/8/3N4/4P3/8/8/4K3 w - - 0 1");
+        let (chased, _) = white_pieces_and_threats("4k3/2p2p2/8/3N4/4P3/8/8/4K3 w - - 0 1");
+        assert!(outpost.mg > chased.mg);
+    }
+
+    #[test]
+    fn pawn_attacking_a_piece_is_a_threat() {
+        let (_, threat) = white_pieces_and_threats("4k3/8/8/2n5/3P4/8/8/4K3 w - - 0 1");
+        assert_eq!(threat, THREAT_BY_PAWN);
+        let (_, none) = white_pieces_and_threats("4k3/8/8/n7/3P4/8/8/4K3 w - - 0 1");
+        assert_eq!(none, Score::ZERO);
+    }
+}
@@ -0,0 +1,240 @@
+use crate::{
+    board::types::Side,
+    eval::{endgame::SCALE_NORMAL, king, params, pawns, pieces, score::Score},
+    types::NumOf,
+};
+
+// Index of every tunable evaluation term in one flat parameter vector. Tables take up
+// one slot per entry, in the order they are written in the source.
+pub struct Param;
+impl Param {
+    pub const PIECE_VALUES: usize = 0;
+    pub const PSQT: usize = Self::PIECE_VALUES + NumOf::PIECE_TYPES;
+    pub const PASSED_PAWN: usize = Self::PSQT + NumOf::PIECE_TYPES * NumOf::SQUARES;
+    pub const PASSED_PAWN_FREE_PATH: usize = Self::PASSED_PAWN + NumOf::RANKS;
+    pub const CANDIDATE_PASSER: usize = Self::PASSED_PAWN_FREE_PATH + NumOf::RANKS;
+    pub const CONNECTED_PAWN: usize = Self::CANDIDATE_PASSER + NumOf::RANKS;
+    pub const ISOLATED_PAWN: usize = Self::CONNECTED_PAWN + NumOf::RANKS;
+    pub const DOUBLED_PAWN: usize = Self::ISOLATED_PAWN + 1;
+    pub const BACKWARD_PAWN: usize = Self::DOUBLED_PAWN + 1;
+    pub const PAWN_SHIELD: usize = Self::BACKWARD_PAWN + 1;
+    pub const PAWN_STORM: usize = Self::PAWN_SHIELD + NumOf::RANKS;
+    pub const KING_SEMI_OPEN_FILE: usize = Self::PAWN_STORM + NumOf::RANKS;
+    pub const KING_OPEN_FILE: usize = Self::KING_SEMI_OPEN_FILE + 1;
+    pub const KING_ATTACKER_WEIGHT: usize = Self::KING_OPEN_FILE + 1;
+    pub const KING_ZONE_ATTACK: usize = Self::KING_ATTACKER_WEIGHT + NumOf::PIECE_TYPES;
+    pub const SAFE_CHECK: usize = Self::KING_ZONE_ATTACK + 1;
+    pub const KNIGHT_MOBILITY: usize = Self::SAFE_CHECK + NumOf::PIECE_TYPES;
+    pub const BISHOP_MOBILITY: usize = Self::KNIGHT_MOBILITY + 9;
+    pub const ROOK_MOBILITY: usize = Self::BISHOP_MOBILITY + 14;
+    pub const QUEEN_MOBILITY: usize = Self::ROOK_MOBILITY + 15;
+    pub const ROOK_OPEN_FILE: usize = Self::QUEEN_MOBILITY + 28;
+    pub const ROOK_SEMI_OPEN_FILE: usize = Self::ROOK_OPEN_FILE + 1;
+    pub const BISHOP_PAIR: usize = Self::ROOK_SEMI_OPEN_FILE + 1;
+    pub const KNIGHT_OUTPOST: usize = Self::BISHOP_PAIR + 1;
+    pub const BISHOP_OUTPOST: usize = Self::KNIGHT_OUTPOST + 1;
+    pub const THREAT_BY_PAWN: usize = Self::BISHOP_OUTPOST + 1;
+    pub const THREAT_BY_MINOR: usize = Self::THREAT_BY_PAWN + 1;
+    pub const THREAT_BY_ROOK: usize = Self::THREAT_BY_MINOR + 1;
+    pub const COUNT: usize = Self::THREAT_BY_ROOK + 1;
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Layout {
+    Single,
+    Table,
+    // One MG_ and one EG_ table of 64 squares, written rank 8 first
+    PieceSquare,
+}
+
+// Where each source constant lives in the parameter vector, so a tuner can write the
+// tables back out
+pub struct ParamTable {
+    pub name: &'static str,
+    pub offset: usize,
+    pub len: usize,
+    pub layout: Layout,
+}
+
+const fn table(name: &'static str, offset: usize, len: usize, layout: Layout) -> ParamTable {
+    ParamTable {
+        name,
+        offset,
+        len,
+        layout,
+    }
+}
+
+const fn piece_square(name: &'static str, piece: usize) -> ParamTable {
+    table(
+        name,
+        Param::PSQT + piece * NumOf::SQUARES,
+        NumOf::SQUARES,
+        Layout::PieceSquare,
+    )
+}
+
+const fn single(name: &'static str, offset: usize) -> ParamTable {
+    table(name, offset, 1, Layout::Single)
+}
+
+#[rustfmt::skip]
+pub const PARAM_TABLES: [ParamTable; 33] = [
+    table("PIECE_VALUES", Param::PIECE_VALUES, NumOf::PIECE_TYPES, Layout::Table),
+    piece_square("KING", 0),
+    piece_square("QUEEN", 1),
+    piece_square("ROOK", 2),
+    piece_square("BISHOP", 3),
+    piece_square("KNIGHT", 4),
+    piece_square("PAWN", 5),
+    table("PASSED_PAWN", Param::PASSED_PAWN, NumOf::RANKS, Layout::Table),
+    table("PASSED_PAWN_FREE_PATH", Param::PASSED_PAWN_FREE_PATH, NumOf::RANKS, Layout::Table),
+    table("CANDIDATE_PASSER", Param::CANDIDATE_PASSER, NumOf::RANKS, Layout::Table),
+    table("CONNECTED_PAWN", Param::CONNECTED_PAWN, NumOf::RANKS, Layout::Table),
+    single("ISOLATED_PAWN", Param::ISOLATED_PAWN),
+    single("DOUBLED_PAWN", Param::DOUBLED_PAWN),
+    single("BACKWARD_PAWN", Param::BACKWARD_PAWN),
+    table("PAWN_SHIELD", Param::PAWN_SHIELD, NumOf::RANKS, Layout::Table),
+    table("PAWN_STORM", Param::PAWN_STORM, NumOf::RANKS, Layout::Table),
+    single("KING_SEMI_OPEN_FILE", Param::KING_SEMI_OPEN_FILE),
+    single("KING_OPEN_FILE", Param::KING_OPEN_FILE),
+    table("KING_ATTACKER_WEIGHT", Param::KING_ATTACKER_WEIGHT, NumOf::PIECE_TYPES, Layout::Table),
+    single("KING_ZONE_ATTACK", Param::KING_ZONE_ATTACK),
+    table("SAFE_CHECK", Param::SAFE_CHECK, NumOf::PIECE_TYPES, Layout::Table),
+    table("KNIGHT_MOBILITY", Param::KNIGHT_MOBILITY, 9, Layout::Table),
+    table("BISHOP_MOBILITY", Param::BISHOP_MOBILITY, 14, Layout::Table),
+    table("ROOK_MOBILITY", Param::ROOK_MOBILITY, 15, Layout::Table),
+    table("QUEEN_MOBILITY", Param::QUEEN_MOBILITY, 28, Layout::Table),
+    single("ROOK_OPEN_FILE", Param::ROOK_OPEN_FILE),
+    single("ROOK_SEMI_OPEN_FILE", Param::ROOK_SEMI_OPEN_FILE),
+    single("BISHOP_PAIR", Param::BISHOP_PAIR),
+    single("KNIGHT_OUTPOST", Param::KNIGHT_OUTPOST),
+    single("BISHOP_OUTPOST", Param::BISHOP_OUTPOST),
+    single("THREAT_BY_PAWN", Param::THREAT_BY_PAWN),
+    single("THREAT_BY_MINOR", Param::THREAT_BY_MINOR),
+    single("THREAT_BY_ROOK", Param::THREAT_BY_ROOK),
+];
+
+// Collects how often each parameter contributes to the evaluation. The evaluation is
+// linear in its parameters, so these counts are all a tuner needs to recompute it.
+pub trait Tracer {
+    // Lets the evaluation skip caches whose entries carry no trace
+    const ENABLED: bool;
+
+    fn add(&mut self, param: usize, side: Side, count: i32);
+
+    fn set_taper(&mut self, _phase: i32, _scale: i32) {}
+}
+
+// Used by the normal evaluation, compiles down to nothing
+pub struct NoTrace;
+
+impl Tracer for NoTrace {
+    const ENABLED: bool = false;
+
+    #[inline(always)]
+    fn add(&mut self, _param: usize, _side: Side, _count: i32) {}
+}
+
+// White's uses of each parameter minus Black's, along with what the taper needs
+#[derive(Debug, Clone)]
+pub struct Trace {
+    pub coefficients: Vec<i32>,
+    pub phase: i32,
+    pub scale: i32,
+}
+
+impl Trace {
+    pub fn new() -> Self {
+        Self {
+            coefficients: vec![0; Param::COUNT],
+            phase: 0,
+            scale: 0,
+        }
+    }
+}
+
+impl Default for Trace {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Tracer for Trace {
+    const ENABLED: bool = true;
+
+    #[inline(always)]
+    fn add(&mut self, param: usize, side: Side, count: i32) {
+        self.coefficients[param] += match side {
+            Side::White => count,
+            Side::Black => -count,
+        };
+    }
+
+    fn set_taper(&mut self, phase: i32, scale: i32) {
+        self.phase = phase;
+        self.scale = scale;
+    }
+}
+
+// The parameter values the evaluation currently runs with: the piece values and square
+// tables in use, and the compiled-in constants for everything else
+pub fn default_params() -> Vec<Score> {
+    let mut params = vec![Score::ZERO; Param::COUNT];
+    let mut copy = |offset: usize, values: &[Score]| {
+        params[offset..offset + values.len()].copy_from_slice(values);
+    };
+    let psqt_params = &params::active().params;
+    copy(Param::PIECE_VALUES, &psqt_params.piece_values);
+    for (piece, (mg, eg)) in psqt_params
+        .mg_tables
+        .iter()
+        .zip(psqt_params.eg_tables.iter())
+        .enumerate()
+    {
+        let squares: Vec<Score> = mg
+            .iter()
+            .zip(eg)
+            .map(|(&mg, &eg)| Score::new(mg, eg))
+            .collect();
+        copy(Param::PSQT + piece * NumOf::SQUARES, &squares);
+    }
+    copy(Param::PASSED_PAWN, &pawns::PASSED_PAWN);
+    copy(Param::PASSED_PAWN_FREE_PATH, &pawns::PASSED_PAWN_FREE_PATH);
+    copy(Param::CANDIDATE_PASSER, &pawns::CANDIDATE_PASSER);
+    copy(Param::CONNECTED_PAWN, &pawns::CONNECTED_PAWN);
+    copy(Param::ISOLATED_PAWN, &[pawns::ISOLATED_PAWN]);
+    copy(Param::DOUBLED_PAWN, &[pawns::DOUBLED_PAWN]);
+    copy(Param::BACKWARD_PAWN, &[pawns::BACKWARD_PAWN]);
+    copy(Param::PAWN_SHIELD, &king::PAWN_SHIELD);
+    copy(Param::PAWN_STORM, &king::PAWN_STORM);
+    copy(Param::KING_SEMI_OPEN_FILE, &[king::KING_SEMI_OPEN_FILE]);
+    copy(Param::KING_OPEN_FILE, &[king::KING_OPEN_FILE]);
+    copy(Param::KING_ATTACKER_WEIGHT, &king::KING_ATTACKER_WEIGHT);
+    copy(Param::KING_ZONE_ATTACK, &[king::KING_ZONE_ATTACK]);
+    copy(Param::SAFE_CHECK, &king::SAFE_CHECK);
+    copy(Param::KNIGHT_MOBILITY, &pieces::KNIGHT_MOBILITY);
+    copy(Param::BISHOP_MOBILITY, &pieces::BISHOP_MOBILITY);
+    copy(Param::ROOK_MOBILITY, &pieces::ROOK_MOBILITY);
+    copy(Param::QUEEN_MOBILITY, &pieces::QUEEN_MOBILITY);
+    copy(Param::ROOK_OPEN_FILE, &[pieces::ROOK_OPEN_FILE]);
+    copy(Param::ROOK_SEMI_OPEN_FILE, &[pieces::ROOK_SEMI_OPEN_FILE]);
+    copy(Param::BISHOP_PAIR, &[pieces::BISHOP_PAIR]);
+    copy(Param::KNIGHT_OUTPOST, &[pieces::KNIGHT_OUTPOST]);
+    copy(Param::BISHOP_OUTPOST, &[pieces::BISHOP_OUTPOST]);
+    copy(Param::THREAT_BY_PAWN, &[pieces::THREAT_BY_PAWN]);
+    copy(Param::THREAT_BY_MINOR, &[pieces::THREAT_BY_MINOR]);
+    copy(Param::THREAT_BY_ROOK, &[pieces::THREAT_BY_ROOK]);
+    params
+}
+
+// Recomputes the evaluation from a trace, in centipawns from White's point of view. Same
+// integer arithmetic as Evaluator::evaluate, so the result matches it exactly.
+pub fn evaluate_trace(trace: &Trace, params: &[Score]) -> i32 {
+    let mut score = Score::ZERO;
+    for (&count, &param) in trace.coefficients.iter().zip(params) {
+        score += param * count;
+    }
+    score.eg = score.eg * trace.scale / SCALE_NORMAL;
+    score.taper(trace.phase)
+}
@@ -1,3 +1,8 @@
 pub mod board;
+pub mod book;
+pub mod eval;
 pub mod movement;
+pub mod pgn;
+pub mod search;
+pub mod tablebase;
 pub mod types;
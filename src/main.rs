@@ -1,37 +1,26 @@
 #![allow(warnings)]
 pub mod board;
+pub mod eval;
 pub mod movement;
 pub mod types;
 
 use crate::board::types::SquareCoord;
 use board::types::Side;
 use movement::{
-    MovementData, MovementDataInitError,
+    ATTACKS,
     sliders::{
         defs::{BISHOP_SLIDER, ROOK_SLIDER, get_all_blockers_subsets},
         magic_entries::{BISHOP_MAGICS, ROOK_MAGICS},
     },
 };
-use types::{BitBoard, NumOf, SQUARE_MASKS};
+use types::{Bitboard, NumOf, SQUARE_MASKS};
 use types::{EMPTY_BITBOARD, FILE_MASKS, RANK_MASKS};
 
-fn bb_to_rows(bb: BitBoard) -> Vec<String> {
-    const LAST: u64 = 63;
-    let mut rows = Vec::with_capacity(10);
-    for rank in 0..8u64 {
-        let mut row = format!("{}  ", NumOf::RANKS as u64 - rank);
-        for file in (0..8u64).rev() {
-            let mask = 1u64 << (LAST - rank * 8 - file);
-            row.push_str(if mask & bb != 0 { "1 " } else { "0 " });
-        }
-        rows.push(row);
-    }
-    rows.push(String::new());
-    rows.push("   a b c d e f g h".to_string());
-    rows
+fn bb_to_rows(bb: Bitboard) -> Vec<String> {
+    bb.to_string().lines().map(str::to_string).collect()
 }
 
-fn print_boards_side_by_side(labels: &[&str], boards: &[BitBoard]) {
+fn print_boards_side_by_side(labels: &[&str], boards: &[Bitboard]) {
     const COL_WIDTH: usize = 24;
     for label in labels {
         print!("{:<COL_WIDTH$}", label);
@@ -46,17 +35,14 @@ fn print_boards_side_by_side(labels: &[&str], boards: &[BitBoard]) {
     }
 }
 
-fn main() -> Result<(), MovementDataInitError> {
-    let mut movement_data = MovementData::new();
-    movement_data.init()?;
-
+fn main() {
     // for (sq_idx, &magic_entry) in BISHOP_MAGICS.iter().enumerate() {
-    //     let sq = SquareCoord::try_from(sq_idx as u8).unwrap();
+    //     let sq = Square::from_index(sq_idx);
     //     let blocker_mask = magic_entry.blocker_mask;
 
     //     let expected = BISHOP_SLIDER.get_moves(sq, blocker_mask);
     //     let table_idx = magic_entry.get_magic_index(blocker_mask) + magic_entry.offset as usize;
-    //     let actual = movement_data.bishop_attacks[table_idx];
+    //     let actual = ATTACKS.magic.bishop_attacks[table_idx];
 
     //     println!("=== Square {} ===", sq_idx);
     //     print_boards_side_by_side(
@@ -77,12 +63,10 @@ fn main() -> Result<(), MovementDataInitError> {
                 &["Pawn position", "Pawn attacks"],
                 &[
                     SQUARE_MASKS[square_idx],
-                    movement_data.pawn_attacks[color][square_idx],
+                    ATTACKS.pawn_attacks[color][square_idx],
                 ],
             );
             println!();
         }
     }
-
-    Ok(())
 }
@@ -1,129 +1,83 @@
-mod nonsliders;
+pub(crate) mod nonsliders;
 pub mod sliders;
-use std::fmt::Display;
 
 use nonsliders::{get_king_attacks, get_knight_attacks, get_pawn_attacks};
-use sliders::{
-    defs::{BISHOP_SLIDER, ROOK_SLIDER, get_all_blockers_subsets},
-    magic_entries::{BISHOP_MAGICS, ROOK_MAGICS},
-    magics::{MAX_BISHOP_TABLE_SIZE, MAX_ROOK_TABLE_SIZE},
-};
+#[cfg(feature = "pext")]
+use sliders::pext::{PextAttacks, has_bmi2};
+use sliders::{defs::SliderAttacks, magics::MagicAttacks};
 
 use crate::{
-    board::types::SquareCoord,
-    types::{BitBoard, NumOf},
-};
-use crate::{
-    board::types::{BySide, Side},
-    types::EMPTY_BITBOARD,
+    board::types::{BySide, Side, Square},
+    types::{Bitboard, EMPTY_BITBOARD, NumOf},
 };
 
-#[derive(Debug)]
-pub enum MovementDataInitError {
-    RookAttackCollisionError,
-    BishopAttackCollisionError,
-}
-
-impl Display for MovementDataInitError {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let err = match self {
-            MovementDataInitError::RookAttackCollisionError => {
-                "Collision detected while initializing rook attack table"
-            }
-            MovementDataInitError::BishopAttackCollisionError => {
-                "Collision detected while initializing bishop attack table"
-            }
-        };
-        write!(f, "{err}")
-    }
-}
+// Every attack table, worked out while compiling so nothing has to be set up at startup.
+// With PEXT as well that takes long enough for rustc to start warning about it.
+#[allow(long_running_const_eval)]
+pub static ATTACKS: MovementData = MovementData::generate();
 
 pub struct MovementData {
-    pub king_attacks: [BitBoard; NumOf::SQUARES],
-    pub knight_attacks: [BitBoard; NumOf::SQUARES],
-    pub pawn_attacks: BySide<[BitBoard; NumOf::SQUARES]>,
-    // pub pawn_attacks: [[BitBoard; NumOf::SQUARES]; NumOf::SIDES],
-    pub rook_attacks: Vec<BitBoard>,
-    pub bishop_attacks: Vec<BitBoard>,
-}
-
-impl MovementData {
-    pub fn new() -> Self {
-        Self {
-            king_attacks: [EMPTY_BITBOARD; NumOf::SQUARES],
-            knight_attacks: [EMPTY_BITBOARD; NumOf::SQUARES],
-            pawn_attacks: BySide::new([EMPTY_BITBOARD; NumOf::SQUARES]),
-            rook_attacks: vec![EMPTY_BITBOARD; MAX_ROOK_TABLE_SIZE],
-            bishop_attacks: vec![EMPTY_BITBOARD; MAX_BISHOP_TABLE_SIZE],
-        }
-    }
-
-    pub fn init(&mut self) -> Result<(), MovementDataInitError> {
-        self.init_king_attacks();
-        self.init_knight_attacks();
-        self.init_pawn_attacks();
-        self.init_rook_attacks()?;
-        self.init_bishop_attacks()?;
-        Ok(())
-    }
+    pub king_attacks: [Bitboard; NumOf::SQUARES],
+    pub knight_attacks: [Bitboard; NumOf::SQUARES],
+    pub pawn_attacks: BySide<[Bitboard; NumOf::SQUARES]>,
+    pub magic: MagicAttacks,
+    // Opt-in rather than picked by CPU detection alone, as PEXT is microcoded and slow on
+    // AMD before Zen 3
+    #[cfg(feature = "pext")]
+    pub pext: PextAttacks,
 }
 
+// Slider lookups go through PEXT when the feature is on and the CPU has BMI2, and through
+// the magic tables otherwise
 impl MovementData {
-    fn init_king_attacks(&mut self) -> () {
-        for (square_idx, king_attack_entry) in self.king_attacks.iter_mut().enumerate() {
-            *king_attack_entry = get_king_attacks(square_idx);
+    #[inline(always)]
+    pub fn get_rook_attacks(&self, square: Square, occupancy: Bitboard) -> Bitboard {
+        #[cfg(feature = "pext")]
+        if has_bmi2() {
+            return self.pext.rook_attacks(square, occupancy);
         }
+        self.magic.rook_attacks(square, occupancy)
     }
 
-    fn init_knight_attacks(&mut self) -> () {
-        for (square_idx, knight_attack) in self.knight_attacks.iter_mut().enumerate() {
-            *knight_attack = get_knight_attacks(square_idx);
+    #[inline(always)]
+    pub fn get_bishop_attacks(&self, square: Square, occupancy: Bitboard) -> Bitboard {
+        #[cfg(feature = "pext")]
+        if has_bmi2() {
+            return self.pext.bishop_attacks(square, occupancy);
         }
+        self.magic.bishop_attacks(square, occupancy)
     }
 
-    fn init_pawn_attacks(&mut self) -> () {
-        for square_idx in 0..NumOf::SQUARES {
-            self.pawn_attacks[Side::White][square_idx] = get_pawn_attacks(square_idx, Side::White);
-            self.pawn_attacks[Side::Black][square_idx] = get_pawn_attacks(square_idx, Side::Black);
-        }
+    #[inline(always)]
+    pub fn get_queen_attacks(&self, square: Square, occupancy: Bitboard) -> Bitboard {
+        self.get_rook_attacks(square, occupancy) | self.get_bishop_attacks(square, occupancy)
     }
+}
 
-    fn init_rook_attacks(&mut self) -> Result<(), MovementDataInitError> {
-        for (square_idx, &magic_entry) in ROOK_MAGICS.iter().enumerate() {
-            let square_coord = SquareCoord::try_from(square_idx as u8).unwrap();
-            let blocker_mask = magic_entry.blocker_mask;
-            for blocker_subset in get_all_blockers_subsets(blocker_mask) {
-                let eligible_rook_moves = ROOK_SLIDER.get_moves(square_coord, blocker_subset);
-                let magic_index = magic_entry.get_magic_index(blocker_subset);
-                let rook_attack_slot =
-                    &mut self.rook_attacks[magic_index + magic_entry.offset as usize];
-                if *rook_attack_slot != EMPTY_BITBOARD && *rook_attack_slot != eligible_rook_moves {
-                    return Err(MovementDataInitError::RookAttackCollisionError);
-                }
-                *rook_attack_slot = eligible_rook_moves;
-            }
+impl MovementData {
+    const fn generate() -> Self {
+        let mut king_attacks = [EMPTY_BITBOARD; NumOf::SQUARES];
+        let mut knight_attacks = [EMPTY_BITBOARD; NumOf::SQUARES];
+        let mut white_pawn_attacks = [EMPTY_BITBOARD; NumOf::SQUARES];
+        let mut black_pawn_attacks = [EMPTY_BITBOARD; NumOf::SQUARES];
+        let mut square_idx = 0;
+        while square_idx < NumOf::SQUARES {
+            let square = Square::from_index(square_idx);
+            king_attacks[square_idx] = get_king_attacks(square);
+            knight_attacks[square_idx] = get_knight_attacks(square);
+            white_pawn_attacks[square_idx] = get_pawn_attacks(square, Side::White);
+            black_pawn_attacks[square_idx] = get_pawn_attacks(square, Side::Black);
+            square_idx += 1;
         }
-        Ok(())
-    }
 
-    fn init_bishop_attacks(&mut self) -> Result<(), MovementDataInitError> {
-        for (square_idx, &magic_entry) in BISHOP_MAGICS.iter().enumerate() {
-            let square_coord = SquareCoord::try_from(square_idx as u8).unwrap();
-            let blocker_mask = magic_entry.blocker_mask;
-            for blocker_subset in get_all_blockers_subsets(blocker_mask) {
-                let eligible_bishop_moves = BISHOP_SLIDER.get_moves(square_coord, blocker_subset);
-                let magic_index = magic_entry.get_magic_index(blocker_subset);
-                let bishop_attack_slot =
-                    &mut self.bishop_attacks[magic_index + magic_entry.offset as usize];
-                if *bishop_attack_slot != EMPTY_BITBOARD
-                    && *bishop_attack_slot != eligible_bishop_moves
-                {
-                    return Err(MovementDataInitError::BishopAttackCollisionError);
-                }
-                *bishop_attack_slot = eligible_bishop_moves;
-            }
+        Self {
+            king_attacks,
+            knight_attacks,
+            pawn_attacks: BySide::from_sides(white_pawn_attacks, black_pawn_attacks),
+            magic: MagicAttacks::new(),
+            #[cfg(feature = "pext")]
+            pext: PextAttacks::new(),
         }
-        Ok(())
     }
 }
 
@@ -136,28 +90,53 @@ mod tests {
     };
 
     #[test]
-    fn rook_init_has_no_collisions() {
-        let mut md = MovementData::new();
-        assert!(md.init_rook_attacks().is_ok());
+    fn slider_lookups_match_ray_walk() {
+        // Blockers that are not on the slider's rays must not change the result
+        let occupancy = Bitboard(0x0042_1800_2400_8100);
+        for sq in Square::ALL {
+            let rook = ROOK_SLIDER.get_moves(sq, occupancy);
+            let bishop = BISHOP_SLIDER.get_moves(sq, occupancy);
+            assert_eq!(ATTACKS.get_rook_attacks(sq, occupancy), rook);
+            assert_eq!(ATTACKS.get_bishop_attacks(sq, occupancy), bishop);
+            assert_eq!(ATTACKS.get_queen_attacks(sq, occupancy), rook | bishop);
+        }
+    }
+
+    #[test]
+    fn king_attacks_include_diagonals() {
+        // a1: a2, b1, b2
+        assert_eq!(ATTACKS.king_attacks[0], Bitboard(0x0302));
+        // e4 is surrounded by all eight neighbours
+        assert_eq!(ATTACKS.king_attacks[28], Bitboard(0x0038_2838_0000));
+        // h8: g8, g7, h7
+        assert_eq!(ATTACKS.king_attacks[63], Bitboard(0x40c0_0000_0000_0000));
     }
 
     #[test]
-    fn bishop_init_has_no_collisions() {
-        let mut md = MovementData::new();
-        assert!(md.init_bishop_attacks().is_ok());
+    fn pawn_attacks_point_forward() {
+        assert_eq!(
+            ATTACKS.pawn_attacks[Side::White][Square::E4],
+            Bitboard(0x28_0000_0000)
+        );
+        assert_eq!(
+            ATTACKS.pawn_attacks[Side::Black][Square::E4],
+            Bitboard(0x28_0000)
+        );
+        assert_eq!(
+            ATTACKS.pawn_attacks[Side::White][Square::A2],
+            Bitboard(0x2_0000)
+        );
     }
 
     #[test]
     fn rook_lookup_returns_correct_moves() {
-        let mut md = MovementData::new();
-        md.init_rook_attacks().unwrap();
         for (sq_idx, &magic_entry) in ROOK_MAGICS.iter().enumerate() {
-            let sq = SquareCoord::try_from(sq_idx as u8).unwrap();
+            let sq = Square::from_index(sq_idx);
             for blocker_subset in get_all_blockers_subsets(magic_entry.blocker_mask) {
                 let expected = ROOK_SLIDER.get_moves(sq, blocker_subset);
                 let idx = magic_entry.get_magic_index(blocker_subset);
                 assert_eq!(
-                    md.rook_attacks[idx + magic_entry.offset as usize],
+                    ATTACKS.magic.rook_attacks[idx + magic_entry.offset as usize],
                     expected,
                     "rook sq {sq_idx} blockers {blocker_subset:#018x}"
                 );
@@ -167,15 +146,13 @@ mod tests {
 
     #[test]
     fn bishop_lookup_returns_correct_moves() {
-        let mut md = MovementData::new();
-        md.init_bishop_attacks().unwrap();
         for (sq_idx, &magic_entry) in BISHOP_MAGICS.iter().enumerate() {
-            let sq = SquareCoord::try_from(sq_idx as u8).unwrap();
+            let sq = Square::from_index(sq_idx);
             for blocker_subset in get_all_blockers_subsets(magic_entry.blocker_mask) {
                 let expected = BISHOP_SLIDER.get_moves(sq, blocker_subset);
                 let idx = magic_entry.get_magic_index(blocker_subset);
                 assert_eq!(
-                    md.bishop_attacks[idx + magic_entry.offset as usize],
+                    ATTACKS.magic.bishop_attacks[idx + magic_entry.offset as usize],
                     expected,
                     "bishop sq {sq_idx} blockers {blocker_subset:#018x}"
                 );
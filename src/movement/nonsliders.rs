@@ -1,6 +1,6 @@
 use crate::{
     board::types::{Files, Side, Square},
-    types::{BitBoard, EMPTY_BITBOARD, FILE_MASKS, NumOf, RANK_MASKS, SQUARE_MASKS},
+    types::{Bitboard, EMPTY_BITBOARD, FILE_MASKS, NumOf, RANK_MASKS, SQUARE_MASKS},
 };
 
 #[repr(u8)]
@@ -18,16 +18,16 @@ impl KingDirections {
     // pub const _EAST_SHIFT: i8 = 1;
     // pub const _WEST_SHIFT: i8 = -1;
 
-    fn shift(&self, bb: BitBoard) -> BitBoard {
+    const fn shift(&self, bb: Bitboard) -> Bitboard {
         const NORTH_SHIFT: u8 = 8;
         const SOUTH_SHIFT: u8 = 8;
         const EAST_SHIFT: u8 = 1;
         const WEST_SHIFT: u8 = 1;
         match self {
-            KingDirections::North => bb << NORTH_SHIFT,
-            KingDirections::South => bb >> SOUTH_SHIFT,
-            KingDirections::EAST => bb << EAST_SHIFT,
-            KingDirections::WEST => bb >> WEST_SHIFT,
+            KingDirections::North => Bitboard(bb.0 << NORTH_SHIFT),
+            KingDirections::South => Bitboard(bb.0 >> SOUTH_SHIFT),
+            KingDirections::EAST => Bitboard(bb.0 << EAST_SHIFT),
+            KingDirections::WEST => Bitboard(bb.0 >> WEST_SHIFT),
         }
     }
 }
@@ -69,7 +69,7 @@ impl KnightDirections {
     ];
 
     #[inline]
-    fn shift(&self, bb: BitBoard) -> BitBoard {
+    const fn shift(&self, bb: Bitboard) -> Bitboard {
         const NORTH_EAST: u8 = 17;
         const NORTH_WEST: u8 = 15;
         const SOUTH_EAST: u8 = 15;
@@ -79,21 +79,21 @@ impl KnightDirections {
         const WEST_NORTH: u8 = 6;
         const WEST_SOUTH: u8 = 10;
 
-        const NOT_FILE_A: BitBoard = !(FILE_MASKS[Files::A as usize]);
-        const NOT_FILE_H: BitBoard = !(FILE_MASKS[Files::H as usize]);
-        const NOT_FILE_AB: BitBoard =
-            !(FILE_MASKS[Files::A as usize] | FILE_MASKS[Files::B as usize]);
-        const NOT_FILE_GH: BitBoard =
-            !(FILE_MASKS[Files::G as usize] | FILE_MASKS[Files::H as usize]);
+        const NOT_FILE_A: Bitboard = Bitboard(!FILE_MASKS[Files::A as usize].0);
+        const NOT_FILE_H: Bitboard = Bitboard(!FILE_MASKS[Files::H as usize].0);
+        const NOT_FILE_AB: Bitboard =
+            Bitboard(!(FILE_MASKS[Files::A as usize].0 | FILE_MASKS[Files::B as usize].0));
+        const NOT_FILE_GH: Bitboard =
+            Bitboard(!(FILE_MASKS[Files::G as usize].0 | FILE_MASKS[Files::H as usize].0));
         match self {
-            KnightDirections::NorthEast => (bb & NOT_FILE_H) << NORTH_EAST,
-            KnightDirections::NorthWest => (bb & NOT_FILE_A) << NORTH_WEST,
-            KnightDirections::SouthEast => (bb & NOT_FILE_H) >> SOUTH_EAST,
-            KnightDirections::SouthWest => (bb & NOT_FILE_A) >> SOUTH_WEST,
-            KnightDirections::EastNorth => (bb & NOT_FILE_GH) << EAST_NORTH,
-            KnightDirections::EastSouth => (bb & NOT_FILE_GH) >> EAST_SOUTH,
-            KnightDirections::WestNorth => (bb & NOT_FILE_AB) << WEST_NORTH,
-            KnightDirections::WestSouth => (bb & NOT_FILE_AB) >> WEST_SOUTH,
+            KnightDirections::NorthEast => Bitboard((bb.0 & NOT_FILE_H.0) << NORTH_EAST),
+            KnightDirections::NorthWest => Bitboard((bb.0 & NOT_FILE_A.0) << NORTH_WEST),
+            KnightDirections::SouthEast => Bitboard((bb.0 & NOT_FILE_H.0) >> SOUTH_EAST),
+            KnightDirections::SouthWest => Bitboard((bb.0 & NOT_FILE_A.0) >> SOUTH_WEST),
+            KnightDirections::EastNorth => Bitboard((bb.0 & NOT_FILE_GH.0) << EAST_NORTH),
+            KnightDirections::EastSouth => Bitboard((bb.0 & NOT_FILE_GH.0) >> EAST_SOUTH),
+            KnightDirections::WestNorth => Bitboard((bb.0 & NOT_FILE_AB.0) << WEST_NORTH),
+            KnightDirections::WestSouth => Bitboard((bb.0 & NOT_FILE_AB.0) >> WEST_SOUTH),
         }
     }
 }
@@ -113,74 +113,90 @@ impl PawnDirections {
         PawnDirections::SouthWest,
     ];
 
-    fn shift(&self, bb: BitBoard, side: Side) -> Option<BitBoard> {
+    const fn shift(&self, bb: Bitboard, side: Side) -> Option<Bitboard> {
         const NORTH_EAST: u8 = 9;
         const NORTH_WEST: u8 = 7;
         const SOUTH_EAST: u8 = 7;
         const SOUTH_WEST: u8 = 9;
-        const NOT_FILE_A: BitBoard = !FILE_MASKS[Files::A as usize];
-        const NOT_FILE_H: BitBoard = !FILE_MASKS[Files::H as usize];
+        const NOT_FILE_A: Bitboard = Bitboard(!FILE_MASKS[Files::A as usize].0);
+        const NOT_FILE_H: Bitboard = Bitboard(!FILE_MASKS[Files::H as usize].0);
         match side {
             Side::Black => match self {
-                PawnDirections::SouthEast => Some((bb & NOT_FILE_H) >> SOUTH_EAST),
-                PawnDirections::SouthWest => Some((bb & NOT_FILE_A) >> SOUTH_WEST),
+                PawnDirections::SouthEast => Some(Bitboard((bb.0 & NOT_FILE_H.0) >> SOUTH_EAST)),
+                PawnDirections::SouthWest => Some(Bitboard((bb.0 & NOT_FILE_A.0) >> SOUTH_WEST)),
                 _ => None,
             },
             Side::White => match self {
-                PawnDirections::NorthEast => Some((bb & NOT_FILE_H) << NORTH_EAST),
-                PawnDirections::NorthWest => Some((bb & NOT_FILE_A) << NORTH_WEST),
+                PawnDirections::NorthEast => Some(Bitboard((bb.0 & NOT_FILE_H.0) << NORTH_EAST)),
+                PawnDirections::NorthWest => Some(Bitboard((bb.0 & NOT_FILE_A.0) << NORTH_WEST)),
                 _ => None,
             },
         }
     }
 }
 
-pub fn get_pawn_attacks(square_idx: Square, color: Side) -> BitBoard {
-    let pawn_position = SQUARE_MASKS[square_idx];
+// These are const so the attack tables can be built while compiling, which is also why
+// they stick to plain u64 operations instead of the Bitboard operators
+pub const fn get_pawn_attacks(square_idx: Square, color: Side) -> Bitboard {
+    let pawn_position = SQUARE_MASKS[square_idx.index()];
     let mut pawn_attack = EMPTY_BITBOARD;
-    PawnDirections::ALL.iter().for_each(|d| {
-        if let Some(attack) = d.shift(pawn_position, color) {
-            pawn_attack |= attack;
+    let mut i = 0;
+    while i < PawnDirections::ALL.len() {
+        if let Some(attack) = PawnDirections::ALL[i].shift(pawn_position, color) {
+            pawn_attack.0 |= attack.0;
         }
-    });
+        i += 1;
+    }
     pawn_attack
 }
 
-pub fn get_knight_attacks(knight_square_idx: Square) -> BitBoard {
-    let knight_position = SQUARE_MASKS[knight_square_idx];
+pub const fn get_knight_attacks(knight_square_idx: Square) -> Bitboard {
+    let knight_position = SQUARE_MASKS[knight_square_idx.index()];
     let mut knight_attacks = EMPTY_BITBOARD;
 
-    for knight_direction in KnightDirections::ALL {
-        knight_attacks |= knight_direction.shift(knight_position);
+    let mut i = 0;
+    while i < KnightDirections::ALL.len() {
+        knight_attacks.0 |= KnightDirections::ALL[i].shift(knight_position).0;
+        i += 1;
     }
 
     knight_attacks
 }
 
-pub fn get_king_attacks(king_square_idx: Square) -> BitBoard {
-    const NOT_RANK_8: BitBoard = !RANK_MASKS[NumOf::RANKS - 1];
-    const NOT_RANK_1: BitBoard = !RANK_MASKS[0];
-    let king_position = SQUARE_MASKS[king_square_idx];
+pub const fn get_king_attacks(king_square_idx: Square) -> Bitboard {
+    const NOT_RANK_8: Bitboard = Bitboard(!RANK_MASKS[NumOf::RANKS - 1].0);
+    const NOT_RANK_1: Bitboard = Bitboard(!RANK_MASKS[0].0);
+    let king_position = SQUARE_MASKS[king_square_idx.index()];
     let mut king_attacks = get_king_attacks_east_west(king_position);
+    // Shift the whole row north and south so the diagonals are covered too
+    let king_row = king_position.0 | king_attacks.0;
 
     // North direction:
-    king_attacks |= KingDirections::North.shift(king_position & NOT_RANK_8);
+    king_attacks.0 |= KingDirections::North
+        .shift(Bitboard(king_row & NOT_RANK_8.0))
+        .0;
 
     // South direction:
-    king_attacks |= KingDirections::South.shift(king_position & NOT_RANK_1);
+    king_attacks.0 |= KingDirections::South
+        .shift(Bitboard(king_row & NOT_RANK_1.0))
+        .0;
 
     king_attacks
 }
 
 #[inline(always)]
-fn get_king_attacks_east_west(king_position: BitBoard) -> BitBoard {
-    const NOT_FILE_H: BitBoard = !FILE_MASKS[NumOf::FILES - 1];
-    const NOT_FILE_A: BitBoard = !FILE_MASKS[0];
+const fn get_king_attacks_east_west(king_position: Bitboard) -> Bitboard {
+    const NOT_FILE_H: Bitboard = Bitboard(!FILE_MASKS[NumOf::FILES - 1].0);
+    const NOT_FILE_A: Bitboard = Bitboard(!FILE_MASKS[0].0);
     let mut attacks = EMPTY_BITBOARD;
     // East direction:
-    attacks |= KingDirections::EAST.shift(king_position & NOT_FILE_H);
+    attacks.0 |= KingDirections::EAST
+        .shift(Bitboard(king_position.0 & NOT_FILE_H.0))
+        .0;
     // West direction:
-    attacks |= KingDirections::WEST.shift(king_position & NOT_FILE_A);
+    attacks.0 |= KingDirections::WEST
+        .shift(Bitboard(king_position.0 & NOT_FILE_A.0))
+        .0;
 
     attacks
 }
@@ -1,3 +1,4 @@
 pub mod defs;
 pub mod magic_entries;
 pub mod magics;
+pub mod pext;
@@ -1,5 +1,5 @@
-use crate::board::types::{Direction, MAX_DIRECTIONS, SquareCoord};
-use crate::types::{BitBoard, EMPTY_BITBOARD, NumOf, SQUARE_MASKS};
+use crate::board::types::{Direction, MAX_DIRECTIONS, Square};
+use crate::types::{Bitboard, EMPTY_BITBOARD, NumOf};
 
 pub struct Slider {
     pub directions: [Direction; MAX_DIRECTIONS],
@@ -14,54 +14,125 @@ pub const BISHOP_SLIDER: Slider = Slider {
 };
 
 impl Slider {
-    pub fn get_moves(&self, square: SquareCoord, blocker_mask: BitBoard) -> BitBoard {
-        let mut move_bitboard: BitBoard = EMPTY_BITBOARD;
-        self.directions.iter().for_each(|direction| {
-            let mut current_square = square;
-            while let Ok(next_square) = current_square.next(*direction) {
-                move_bitboard |= SQUARE_MASKS[next_square.to_usize()];
-                if SQUARE_MASKS[next_square.to_usize()] & blocker_mask != 0 {
+    // const so the attack tables can be filled in while compiling. The walk sticks to
+    // plain file and rank arithmetic, which keeps that evaluation quick.
+    pub const fn get_moves(&self, square: Square, blocker_mask: Bitboard) -> Bitboard {
+        let mut moves = 0u64;
+        let mut i = 0;
+        while i < MAX_DIRECTIONS {
+            let (file_step, rank_step) = self.directions[i];
+            let mut file = square.file() as i8 + file_step;
+            let mut rank = square.rank() as i8 + rank_step;
+            while file >= 0 && file < NumOf::FILES as i8 && rank >= 0 && rank < NumOf::RANKS as i8 {
+                let bit = 1u64 << (rank as usize * NumOf::FILES + file as usize);
+                moves |= bit;
+                if blocker_mask.0 & bit != 0 {
                     break;
                 }
-                current_square = next_square;
+                file += file_step;
+                rank += rank_step;
             }
-        });
-        move_bitboard
+            i += 1;
+        }
+        Bitboard(moves)
     }
 
-    pub fn get_blocker_mask(&self, square: SquareCoord) -> BitBoard {
+    pub const fn get_blocker_mask(&self, square: Square) -> Bitboard {
         let mut blockers_mask = EMPTY_BITBOARD;
-        self.directions.iter().for_each(|direction| {
-            let mut current_square = square;
-            while let Ok(next_square) = current_square.next(*direction) {
-                if let Ok(_next_next_square) = next_square.next(*direction) {
-                    blockers_mask |= SQUARE_MASKS[next_square.to_usize()];
+        let mut i = 0;
+        while i < MAX_DIRECTIONS {
+            let mut current_square = square.offset(self.directions[i]);
+            while let Some(next_square) = current_square {
+                current_square = next_square.offset(self.directions[i]);
+                // The last square of a ray never blocks anything behind it
+                if current_square.is_some() {
+                    blockers_mask.0 |= next_square.bitboard().0;
                 }
-                current_square = next_square;
             }
-        });
+            i += 1;
+        }
         blockers_mask
     }
 
-    pub fn get_all_blockers(&self) -> [BitBoard; NumOf::SQUARES] {
-        let mut slider_blockers_masks: [BitBoard; NumOf::SQUARES] =
-            [EMPTY_BITBOARD; NumOf::SQUARES];
-        for (square_idx, blocker_mask) in slider_blockers_masks.iter_mut().enumerate() {
-            let current_square = SquareCoord::try_from(square_idx as u8).unwrap();
-            *blocker_mask = self.get_blocker_mask(current_square);
+    pub fn get_all_blockers(&self) -> [Bitboard; NumOf::SQUARES] {
+        Square::ALL.map(|square| self.get_blocker_mask(square))
+    }
+}
+
+// A way of looking up slider attacks for any occupancy
+pub trait SliderAttacks {
+    fn rook_attacks(&self, square: Square, occupancy: Bitboard) -> Bitboard;
+    fn bishop_attacks(&self, square: Square, occupancy: Bitboard) -> Bitboard;
+
+    #[inline(always)]
+    fn queen_attacks(&self, square: Square, occupancy: Bitboard) -> Bitboard {
+        Bitboard(self.rook_attacks(square, occupancy).0 | self.bishop_attacks(square, occupancy).0)
+    }
+}
+
+// What a slider sees from every square of an empty board, one table per direction. The
+// lookup tables are built from these: a ray is cut at its first blocker with the ray seen
+// from that blocker, a lot cheaper to evaluate at compile time than stepping along it.
+pub(crate) struct SliderRays {
+    directions: [Direction; MAX_DIRECTIONS],
+    rays: [[u64; NumOf::SQUARES]; MAX_DIRECTIONS],
+}
+
+impl SliderRays {
+    pub(crate) const fn new(slider: &Slider) -> Self {
+        let mut rays = [[0u64; NumOf::SQUARES]; MAX_DIRECTIONS];
+        let mut direction = 0;
+        while direction < MAX_DIRECTIONS {
+            let mut square_idx = 0;
+            while square_idx < NumOf::SQUARES {
+                let mut current =
+                    Square::from_index(square_idx).offset(slider.directions[direction]);
+                while let Some(square) = current {
+                    rays[direction][square_idx] |= square.bitboard().0;
+                    current = square.offset(slider.directions[direction]);
+                }
+                square_idx += 1;
+            }
+            direction += 1;
+        }
+        Self {
+            directions: slider.directions,
+            rays,
+        }
+    }
+
+    pub(crate) const fn attacks(&self, square_idx: usize, blockers: u64) -> u64 {
+        let mut attacks = 0u64;
+        let mut direction = 0;
+        while direction < MAX_DIRECTIONS {
+            let (file_step, rank_step) = self.directions[direction];
+            let ray = self.rays[direction][square_idx];
+            let on_ray = ray & blockers;
+            attacks |= if on_ray == 0 {
+                ray
+            } else {
+                // Rays that run towards higher indices meet their lowest blocker first
+                let first = if rank_step > 0 || (rank_step == 0 && file_step > 0) {
+                    on_ray.trailing_zeros() as usize
+                } else {
+                    63 - on_ray.leading_zeros() as usize
+                };
+                ray ^ self.rays[direction][first]
+            };
+            direction += 1;
         }
-        slider_blockers_masks
+        attacks
     }
 }
 
-pub fn get_all_blockers_subsets(blocker_mask: BitBoard) -> Vec<BitBoard> {
+pub fn get_all_blockers_subsets(blocker_mask: Bitboard) -> Vec<Bitboard> {
     // This is just to reduce the number of allocations
-    let mut subsets: Vec<BitBoard> = Vec::with_capacity(NumOf::SQUARES);
+    let mut subsets: Vec<Bitboard> = Vec::with_capacity(NumOf::SQUARES);
     subsets.push(EMPTY_BITBOARD);
     while let Some(current_subset) = subsets.last()
         && *current_subset != blocker_mask
     {
-        let next_subset: BitBoard = current_subset.wrapping_sub(blocker_mask) & blocker_mask;
+        let next_subset = Bitboard(current_subset.0.wrapping_sub(blocker_mask.0)) & blocker_mask;
         subsets.push(next_subset);
     }
     subsets
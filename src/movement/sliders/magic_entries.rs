@@ -1,515 +1,515 @@
 use crate::movement::sliders::magics::MagicEntry;
-use crate::types::NumOf;
+use crate::types::{Bitboard, NumOf};
 
 pub const ROOK_MAGICS: [MagicEntry; NumOf::SQUARES] = [
     MagicEntry {
         number: 6953558386969411585,
-        blocker_mask: 0x000101010101017e,
-        inverse_blocker_mask: 0xfffefefefefefe81,
+        blocker_mask: Bitboard(0x000101010101017e),
+        inverse_blocker_mask: Bitboard(0xfffefefefefefe81),
         offset: 0,
         index_bits: 12,
         shift: 52,
     },
     MagicEntry {
         number: 1603283941782458371,
-        blocker_mask: 0x000202020202027c,
-        inverse_blocker_mask: 0xfffdfdfdfdfdfd83,
+        blocker_mask: Bitboard(0x000202020202027c),
+        inverse_blocker_mask: Bitboard(0xfffdfdfdfdfdfd83),
         offset: 16374,
         index_bits: 11,
         shift: 53,
     },
     MagicEntry {
         number: 72079721726230532,
-        blocker_mask: 0x000404040404047a,
-        inverse_blocker_mask: 0xfffbfbfbfbfbfb85,
+        blocker_mask: Bitboard(0x000404040404047a),
+        inverse_blocker_mask: Bitboard(0xfffbfbfbfbfbfb85),
         offset: 18422,
         index_bits: 11,
         shift: 53,
     },
     MagicEntry {
         number: 144126186413494304,
-        blocker_mask: 0x0008080808080876,
-        inverse_blocker_mask: 0xfff7f7f7f7f7f789,
+        blocker_mask: Bitboard(0x0008080808080876),
+        inverse_blocker_mask: Bitboard(0xfff7f7f7f7f7f789),
         offset: 20470,
         index_bits: 11,
         shift: 53,
     },
     MagicEntry {
         number: 10448355606694723840,
-        blocker_mask: 0x001010101010106e,
-        inverse_blocker_mask: 0xffefefefefefef91,
+        blocker_mask: Bitboard(0x001010101010106e),
+        inverse_blocker_mask: Bitboard(0xffefefefefefef91),
         offset: 22515,
         index_bits: 11,
         shift: 53,
     },
     MagicEntry {
         number: 9259405234101289088,
-        blocker_mask: 0x002020202020205e,
-        inverse_blocker_mask: 0xffdfdfdfdfdfdfa1,
+        blocker_mask: Bitboard(0x002020202020205e),
+        inverse_blocker_mask: Bitboard(0xffdfdfdfdfdfdfa1),
         offset: 24562,
         index_bits: 11,
         shift: 53,
     },
     MagicEntry {
         number: 9511606970372587558,
-        blocker_mask: 0x004040404040403e,
-        inverse_blocker_mask: 0xffbfbfbfbfbfbfc1,
+        blocker_mask: Bitboard(0x004040404040403e),
+        inverse_blocker_mask: Bitboard(0xffbfbfbfbfbfbfc1),
         offset: 26605,
         index_bits: 11,
         shift: 53,
     },
     MagicEntry {
         number: 9367487439834447873,
-        blocker_mask: 0x008080808080807e,
-        inverse_blocker_mask: 0xff7f7f7f7f7f7f81,
+        blocker_mask: Bitboard(0x008080808080807e),
+        inverse_blocker_mask: Bitboard(0xff7f7f7f7f7f7f81),
         offset: 4093,
         index_bits: 12,
         shift: 52,
     },
     MagicEntry {
         number: 1482106492055191564,
-        blocker_mask: 0x0001010101017e00,
-        inverse_blocker_mask: 0xfffefefefefe81ff,
+        blocker_mask: Bitboard(0x0001010101017e00),
+        inverse_blocker_mask: Bitboard(0xfffefefefefe81ff),
         offset: 28650,
         index_bits: 11,
         shift: 53,
     },
     MagicEntry {
         number: 9183190105718792,
-        blocker_mask: 0x0002020202027c00,
-        inverse_blocker_mask: 0xfffdfdfdfdfd83ff,
+        blocker_mask: Bitboard(0x0002020202027c00),
+        inverse_blocker_mask: Bitboard(0xfffdfdfdfdfd83ff),
         offset: 65387,
         index_bits: 10,
         shift: 54,
     },
     MagicEntry {
         number: 563062729869440,
-        blocker_mask: 0x0004040404047a00,
-        inverse_blocker_mask: 0xfffbfbfbfbfb85ff,
+        blocker_mask: Bitboard(0x0004040404047a00),
+        inverse_blocker_mask: Bitboard(0xfffbfbfbfbfb85ff),
         offset: 66410,
         index_bits: 10,
         shift: 54,
     },
     MagicEntry {
         number: 5066618870841346,
-        blocker_mask: 0x0008080808087600,
-        inverse_blocker_mask: 0xfff7f7f7f7f789ff,
+        blocker_mask: Bitboard(0x0008080808087600),
+        inverse_blocker_mask: Bitboard(0xfff7f7f7f7f789ff),
         offset: 67433,
         index_bits: 10,
         shift: 54,
     },
     MagicEntry {
         number: 72620566025077248,
-        blocker_mask: 0x0010101010106e00,
-        inverse_blocker_mask: 0xffefefefefef91ff,
+        blocker_mask: Bitboard(0x0010101010106e00),
+        inverse_blocker_mask: Bitboard(0xffefefefefef91ff),
         offset: 68456,
         index_bits: 10,
         shift: 54,
     },
     MagicEntry {
         number: 3460171924338966529,
-        blocker_mask: 0x0020202020205e00,
-        inverse_blocker_mask: 0xffdfdfdfdfdfa1ff,
+        blocker_mask: Bitboard(0x0020202020205e00),
+        inverse_blocker_mask: Bitboard(0xffdfdfdfdfdfa1ff),
         offset: 69480,
         index_bits: 10,
         shift: 54,
     },
     MagicEntry {
         number: 1316177060930257032,
-        blocker_mask: 0x0040404040403e00,
-        inverse_blocker_mask: 0xffbfbfbfbfbfc1ff,
+        blocker_mask: Bitboard(0x0040404040403e00),
+        inverse_blocker_mask: Bitboard(0xffbfbfbfbfbfc1ff),
         offset: 70504,
         index_bits: 10,
         shift: 54,
     },
     MagicEntry {
         number: 144678141049186819,
-        blocker_mask: 0x0080808080807e00,
-        inverse_blocker_mask: 0xff7f7f7f7f7f81ff,
+        blocker_mask: Bitboard(0x0080808080807e00),
+        inverse_blocker_mask: Bitboard(0xff7f7f7f7f7f81ff),
         offset: 30698,
         index_bits: 11,
         shift: 53,
     },
     MagicEntry {
         number: 1351365762341929088,
-        blocker_mask: 0x00010101017e0100,
-        inverse_blocker_mask: 0xfffefefefe81feff,
+        blocker_mask: Bitboard(0x00010101017e0100),
+        inverse_blocker_mask: Bitboard(0xfffefefefe81feff),
         offset: 32745,
         index_bits: 11,
         shift: 53,
     },
     MagicEntry {
         number: 18014536083701769,
-        blocker_mask: 0x00020202027c0200,
-        inverse_blocker_mask: 0xfffdfdfdfd83fdff,
+        blocker_mask: Bitboard(0x00020202027c0200),
+        inverse_blocker_mask: Bitboard(0xfffdfdfdfd83fdff),
         offset: 71528,
         index_bits: 10,
         shift: 54,
     },
     MagicEntry {
         number: 2323268606689290,
-        blocker_mask: 0x00040404047a0400,
-        inverse_blocker_mask: 0xfffbfbfbfb85fbff,
+        blocker_mask: Bitboard(0x00040404047a0400),
+        inverse_blocker_mask: Bitboard(0xfffbfbfbfb85fbff),
         offset: 72551,
         index_bits: 10,
         shift: 54,
     },
     MagicEntry {
         number: 565149249576964,
-        blocker_mask: 0x0008080808760800,
-        inverse_blocker_mask: 0xfff7f7f7f789f7ff,
+        blocker_mask: Bitboard(0x0008080808760800),
+        inverse_blocker_mask: Bitboard(0xfff7f7f7f789f7ff),
         offset: 73575,
         index_bits: 10,
         shift: 54,
     },
     MagicEntry {
         number: 4786174250029058,
-        blocker_mask: 0x00101010106e1000,
-        inverse_blocker_mask: 0xffefefefef91efff,
+        blocker_mask: Bitboard(0x00101010106e1000),
+        inverse_blocker_mask: Bitboard(0xffefefefef91efff),
         offset: 74599,
         index_bits: 10,
         shift: 54,
     },
     MagicEntry {
         number: 1126449734615168,
-        blocker_mask: 0x00202020205e2000,
-        inverse_blocker_mask: 0xffdfdfdfdfa1dfff,
+        blocker_mask: Bitboard(0x00202020205e2000),
+        inverse_blocker_mask: Bitboard(0xffdfdfdfdfa1dfff),
         offset: 75623,
         index_bits: 10,
         shift: 54,
     },
     MagicEntry {
         number: 5197580580636590178,
-        blocker_mask: 0x00404040403e4000,
-        inverse_blocker_mask: 0xffbfbfbfbfc1bfff,
+        blocker_mask: Bitboard(0x00404040403e4000),
+        inverse_blocker_mask: Bitboard(0xffbfbfbfbfc1bfff),
         offset: 76647,
         index_bits: 10,
         shift: 54,
     },
     MagicEntry {
         number: 6757598556724225,
-        blocker_mask: 0x00808080807e8000,
-        inverse_blocker_mask: 0xff7f7f7f7f817fff,
+        blocker_mask: Bitboard(0x00808080807e8000),
+        inverse_blocker_mask: Bitboard(0xff7f7f7f7f817fff),
         offset: 34793,
         index_bits: 11,
         shift: 53,
     },
     MagicEntry {
         number: 4630267782120931456,
-        blocker_mask: 0x000101017e010100,
-        inverse_blocker_mask: 0xfffefefe81fefeff,
+        blocker_mask: Bitboard(0x000101017e010100),
+        inverse_blocker_mask: Bitboard(0xfffefefe81fefeff),
         offset: 36840,
         index_bits: 11,
         shift: 53,
     },
     MagicEntry {
         number: 1161103610480769,
-        blocker_mask: 0x000202027c020200,
-        inverse_blocker_mask: 0xfffdfdfd83fdfdff,
+        blocker_mask: Bitboard(0x000202027c020200),
+        inverse_blocker_mask: Bitboard(0xfffdfdfd83fdfdff),
         offset: 77671,
         index_bits: 10,
         shift: 54,
     },
     MagicEntry {
         number: 45071184941302017,
-        blocker_mask: 0x000404047a040400,
-        inverse_blocker_mask: 0xfffbfbfb85fbfbff,
+        blocker_mask: Bitboard(0x000404047a040400),
+        inverse_blocker_mask: Bitboard(0xfffbfbfb85fbfbff),
         offset: 78695,
         index_bits: 10,
         shift: 54,
     },
     MagicEntry {
         number: 1495201681947770912,
-        blocker_mask: 0x0008080876080800,
-        inverse_blocker_mask: 0xfff7f7f789f7f7ff,
+        blocker_mask: Bitboard(0x0008080876080800),
+        inverse_blocker_mask: Bitboard(0xfff7f7f789f7f7ff),
         offset: 79719,
         index_bits: 10,
         shift: 54,
     },
     MagicEntry {
         number: 324261380785965072,
-        blocker_mask: 0x001010106e101000,
-        inverse_blocker_mask: 0xffefefef91efefff,
+        blocker_mask: Bitboard(0x001010106e101000),
+        inverse_blocker_mask: Bitboard(0xffefefef91efefff),
         offset: 80742,
         index_bits: 10,
         shift: 54,
     },
     MagicEntry {
         number: 9367488328737752064,
-        blocker_mask: 0x002020205e202000,
-        inverse_blocker_mask: 0xffdfdfdfa1dfdfff,
+        blocker_mask: Bitboard(0x002020205e202000),
+        inverse_blocker_mask: Bitboard(0xffdfdfdfa1dfdfff),
         offset: 81766,
         index_bits: 10,
         shift: 54,
     },
     MagicEntry {
         number: 11538503758954758148,
-        blocker_mask: 0x004040403e404000,
-        inverse_blocker_mask: 0xffbfbfbfc1bfbfff,
+        blocker_mask: Bitboard(0x004040403e404000),
+        inverse_blocker_mask: Bitboard(0xffbfbfbfc1bfbfff),
         offset: 82790,
         index_bits: 10,
         shift: 54,
     },
     MagicEntry {
         number: 5643587059780,
-        blocker_mask: 0x008080807e808000,
-        inverse_blocker_mask: 0xff7f7f7f817f7fff,
+        blocker_mask: Bitboard(0x008080807e808000),
+        inverse_blocker_mask: Bitboard(0xff7f7f7f817f7fff),
         offset: 38888,
         index_bits: 11,
         shift: 53,
     },
     MagicEntry {
         number: 4683886566223921408,
-        blocker_mask: 0x0001017e01010100,
-        inverse_blocker_mask: 0xfffefe81fefefeff,
+        blocker_mask: Bitboard(0x0001017e01010100),
+        inverse_blocker_mask: Bitboard(0xfffefe81fefefeff),
         offset: 40935,
         index_bits: 11,
         shift: 53,
     },
     MagicEntry {
         number: 567362025718018,
-        blocker_mask: 0x0002027c02020200,
-        inverse_blocker_mask: 0xfffdfd83fdfdfdff,
+        blocker_mask: Bitboard(0x0002027c02020200),
+        inverse_blocker_mask: Bitboard(0xfffdfd83fdfdfdff),
         offset: 83814,
         index_bits: 10,
         shift: 54,
     },
     MagicEntry {
         number: 576465201895833696,
-        blocker_mask: 0x0004047a04040400,
-        inverse_blocker_mask: 0xfffbfb85fbfbfbff,
+        blocker_mask: Bitboard(0x0004047a04040400),
+        inverse_blocker_mask: Bitboard(0xfffbfb85fbfbfbff),
         offset: 84805,
         index_bits: 10,
         shift: 54,
     },
     MagicEntry {
         number: 4611690699975303344,
-        blocker_mask: 0x0008087608080800,
-        inverse_blocker_mask: 0xfff7f789f7f7f7ff,
+        blocker_mask: Bitboard(0x0008087608080800),
+        inverse_blocker_mask: Bitboard(0xfff7f789f7f7f7ff),
         offset: 85797,
         index_bits: 10,
         shift: 54,
     },
     MagicEntry {
         number: 4611688217886855328,
-        blocker_mask: 0x0010106e10101000,
-        inverse_blocker_mask: 0xffefef91efefefff,
+        blocker_mask: Bitboard(0x0010106e10101000),
+        inverse_blocker_mask: Bitboard(0xffefef91efefefff),
         offset: 86821,
         index_bits: 10,
         shift: 54,
     },
     MagicEntry {
         number: 1188950992320730112,
-        blocker_mask: 0x0020205e20202000,
-        inverse_blocker_mask: 0xffdfdfa1dfdfdfff,
+        blocker_mask: Bitboard(0x0020205e20202000),
+        inverse_blocker_mask: Bitboard(0xffdfdfa1dfdfdfff),
         offset: 87841,
         index_bits: 10,
         shift: 54,
     },
     MagicEntry {
         number: 281477132583424,
-        blocker_mask: 0x0040403e40404000,
-        inverse_blocker_mask: 0xffbfbfc1bfbfbfff,
+        blocker_mask: Bitboard(0x0040403e40404000),
+        inverse_blocker_mask: Bitboard(0xffbfbfc1bfbfbfff),
         offset: 88865,
         index_bits: 10,
         shift: 54,
     },
     MagicEntry {
         number: 144678242452766976,
-        blocker_mask: 0x0080807e80808000,
-        inverse_blocker_mask: 0xff7f7f817f7f7fff,
+        blocker_mask: Bitboard(0x0080807e80808000),
+        inverse_blocker_mask: Bitboard(0xff7f7f817f7f7fff),
         offset: 42982,
         index_bits: 11,
         shift: 53,
     },
     MagicEntry {
         number: 9223380008457764864,
-        blocker_mask: 0x00017e0101010100,
-        inverse_blocker_mask: 0xfffe81fefefefeff,
+        blocker_mask: Bitboard(0x00017e0101010100),
+        inverse_blocker_mask: Bitboard(0xfffe81fefefefeff),
         offset: 45029,
         index_bits: 11,
         shift: 53,
     },
     MagicEntry {
         number: 3372170633216,
-        blocker_mask: 0x00027c0202020200,
-        inverse_blocker_mask: 0xfffd83fdfdfdfdff,
+        blocker_mask: Bitboard(0x00027c0202020200),
+        inverse_blocker_mask: Bitboard(0xfffd83fdfdfdfdff),
         offset: 89884,
         index_bits: 10,
         shift: 54,
     },
     MagicEntry {
         number: 576463655835672576,
-        blocker_mask: 0x00047a0404040400,
-        inverse_blocker_mask: 0xfffb85fbfbfbfbff,
+        blocker_mask: Bitboard(0x00047a0404040400),
+        inverse_blocker_mask: Bitboard(0xfffb85fbfbfbfbff),
         offset: 90907,
         index_bits: 10,
         shift: 54,
     },
     MagicEntry {
         number: 2346480967604109328,
-        blocker_mask: 0x0008760808080800,
-        inverse_blocker_mask: 0xfff789f7f7f7f7ff,
+        blocker_mask: Bitboard(0x0008760808080800),
+        inverse_blocker_mask: Bitboard(0xfff789f7f7f7f7ff),
         offset: 91929,
         index_bits: 10,
         shift: 54,
     },
     MagicEntry {
         number: 18015508087504912,
-        blocker_mask: 0x00106e1010101000,
-        inverse_blocker_mask: 0xffef91efefefefff,
+        blocker_mask: Bitboard(0x00106e1010101000),
+        inverse_blocker_mask: Bitboard(0xffef91efefefefff),
         offset: 92953,
         index_bits: 10,
         shift: 54,
     },
     MagicEntry {
         number: 285873040064584,
-        blocker_mask: 0x00205e2020202000,
-        inverse_blocker_mask: 0xffdfa1dfdfdfdfff,
+        blocker_mask: Bitboard(0x00205e2020202000),
+        inverse_blocker_mask: Bitboard(0xffdfa1dfdfdfdfff),
         offset: 93976,
         index_bits: 10,
         shift: 54,
     },
     MagicEntry {
         number: 280387141644,
-        blocker_mask: 0x00403e4040404000,
-        inverse_blocker_mask: 0xffbfc1bfbfbfbfff,
+        blocker_mask: Bitboard(0x00403e4040404000),
+        inverse_blocker_mask: Bitboard(0xffbfc1bfbfbfbfff),
         offset: 94999,
         index_bits: 10,
         shift: 54,
     },
     MagicEntry {
         number: 36028870301900802,
-        blocker_mask: 0x00807e8080808000,
-        inverse_blocker_mask: 0xff7f817f7f7f7fff,
+        blocker_mask: Bitboard(0x00807e8080808000),
+        inverse_blocker_mask: Bitboard(0xff7f817f7f7f7fff),
         offset: 47076,
         index_bits: 11,
         shift: 53,
     },
     MagicEntry {
         number: 2305852012560155136,
-        blocker_mask: 0x007e010101010100,
-        inverse_blocker_mask: 0xff81fefefefefeff,
+        blocker_mask: Bitboard(0x007e010101010100),
+        inverse_blocker_mask: Bitboard(0xff81fefefefefeff),
         offset: 49109,
         index_bits: 11,
         shift: 53,
     },
     MagicEntry {
         number: 1273526100112,
-        blocker_mask: 0x007c020202020200,
-        inverse_blocker_mask: 0xff83fdfdfdfdfdff,
+        blocker_mask: Bitboard(0x007c020202020200),
+        inverse_blocker_mask: Bitboard(0xff83fdfdfdfdfdff),
         offset: 96017,
         index_bits: 10,
         shift: 54,
     },
     MagicEntry {
         number: 360290457378488512,
-        blocker_mask: 0x007a040404040400,
-        inverse_blocker_mask: 0xff85fbfbfbfbfbff,
+        blocker_mask: Bitboard(0x007a040404040400),
+        inverse_blocker_mask: Bitboard(0xff85fbfbfbfbfbff),
         offset: 96900,
         index_bits: 10,
         shift: 54,
     },
     MagicEntry {
         number: 2305844457863512576,
-        blocker_mask: 0x0076080808080800,
-        inverse_blocker_mask: 0xff89f7f7f7f7f7ff,
+        blocker_mask: Bitboard(0x0076080808080800),
+        inverse_blocker_mask: Bitboard(0xff89f7f7f7f7f7ff),
         offset: 97871,
         index_bits: 10,
         shift: 54,
     },
     MagicEntry {
         number: 1189513252406856192,
-        blocker_mask: 0x006e101010101000,
-        inverse_blocker_mask: 0xff91efefefefefff,
+        blocker_mask: Bitboard(0x006e101010101000),
+        inverse_blocker_mask: Bitboard(0xff91efefefefefff),
         offset: 98889,
         index_bits: 10,
         shift: 54,
     },
     MagicEntry {
         number: 72058695160316000,
-        blocker_mask: 0x005e202020202000,
-        inverse_blocker_mask: 0xffa1dfdfdfdfdfff,
+        blocker_mask: Bitboard(0x005e202020202000),
+        inverse_blocker_mask: Bitboard(0xffa1dfdfdfdfdfff),
         offset: 99911,
         index_bits: 10,
         shift: 54,
     },
     MagicEntry {
         number: 74309531467124768,
-        blocker_mask: 0x003e404040404000,
-        inverse_blocker_mask: 0xffc1bfbfbfbfbfff,
+        blocker_mask: Bitboard(0x003e404040404000),
+        inverse_blocker_mask: Bitboard(0xffc1bfbfbfbfbfff),
         offset: 100906,
         index_bits: 10,
         shift: 54,
     },
     MagicEntry {
         number: 4035225407925670400,
-        blocker_mask: 0x007e808080808000,
-        inverse_blocker_mask: 0xff817f7f7f7f7fff,
+        blocker_mask: Bitboard(0x007e808080808000),
+        inverse_blocker_mask: Bitboard(0xff817f7f7f7f7fff),
         offset: 51156,
         index_bits: 11,
         shift: 53,
     },
     MagicEntry {
         number: 554084391442,
-        blocker_mask: 0x7e01010101010100,
-        inverse_blocker_mask: 0x81fefefefefefeff,
+        blocker_mask: Bitboard(0x7e01010101010100),
+        inverse_blocker_mask: Bitboard(0x81fefefefefefeff),
         offset: 8188,
         index_bits: 12,
         shift: 52,
     },
     MagicEntry {
         number: 4899916673761161250,
-        blocker_mask: 0x7c02020202020200,
-        inverse_blocker_mask: 0x83fdfdfdfdfdfdff,
+        blocker_mask: Bitboard(0x7c02020202020200),
+        inverse_blocker_mask: Bitboard(0x83fdfdfdfdfdfdff),
         offset: 53204,
         index_bits: 11,
         shift: 53,
     },
     MagicEntry {
         number: 279443444762,
-        blocker_mask: 0x7a04040404040400,
-        inverse_blocker_mask: 0x85fbfbfbfbfbfbff,
+        blocker_mask: Bitboard(0x7a04040404040400),
+        inverse_blocker_mask: Bitboard(0x85fbfbfbfbfbfbff),
         offset: 55194,
         index_bits: 11,
         shift: 53,
     },
     MagicEntry {
         number: 144116339270754370,
-        blocker_mask: 0x7608080808080800,
-        inverse_blocker_mask: 0x89f7f7f7f7f7f7ff,
+        blocker_mask: Bitboard(0x7608080808080800),
+        inverse_blocker_mask: Bitboard(0x89f7f7f7f7f7f7ff),
         offset: 57226,
         index_bits: 11,
         shift: 53,
     },
     MagicEntry {
         number: 270497453869123585,
-        blocker_mask: 0x6e10101010101000,
-        inverse_blocker_mask: 0x91efefefefefefff,
+        blocker_mask: Bitboard(0x6e10101010101000),
+        inverse_blocker_mask: Bitboard(0x91efefefefefefff),
         offset: 59250,
         index_bits: 11,
         shift: 53,
     },
     MagicEntry {
         number: 2814750174563354,
-        blocker_mask: 0x5e20202020202000,
-        inverse_blocker_mask: 0xa1dfdfdfdfdfdfff,
+        blocker_mask: Bitboard(0x5e20202020202000),
+        inverse_blocker_mask: Bitboard(0xa1dfdfdfdfdfdfff),
         offset: 61295,
         index_bits: 11,
         shift: 53,
     },
     MagicEntry {
         number: 290482210879841796,
-        blocker_mask: 0x3e40404040404000,
-        inverse_blocker_mask: 0xc1bfbfbfbfbfbfff,
+        blocker_mask: Bitboard(0x3e40404040404000),
+        inverse_blocker_mask: Bitboard(0xc1bfbfbfbfbfbfff),
         offset: 63342,
         index_bits: 11,
         shift: 53,
     },
     MagicEntry {
         number: 144115229148644354,
-        blocker_mask: 0x7e80808080808000,
-        inverse_blocker_mask: 0x817f7f7f7f7f7fff,
+        blocker_mask: Bitboard(0x7e80808080808000),
+        inverse_blocker_mask: Bitboard(0x817f7f7f7f7f7fff),
         offset: 12278,
         index_bits: 12,
         shift: 52,
@@ -518,512 +518,512 @@ pub const ROOK_MAGICS: [MagicEntry; NumOf::SQUARES] = [
 pub const BISHOP_MAGICS: [MagicEntry; NumOf::SQUARES] = [
     MagicEntry {
         number: 4623024251195253769,
-        blocker_mask: 0x0040201008040200,
-        inverse_blocker_mask: 0xffbfdfeff7fbfdff,
+        blocker_mask: Bitboard(0x0040201008040200),
+        inverse_blocker_mask: Bitboard(0xffbfdfeff7fbfdff),
         offset: 0,
         index_bits: 6,
         shift: 58,
     },
     MagicEntry {
         number: 2473059215997666304,
-        blocker_mask: 0x0000402010080400,
-        inverse_blocker_mask: 0xffffbfdfeff7fbff,
+        blocker_mask: Bitboard(0x0000402010080400),
+        inverse_blocker_mask: Bitboard(0xffffbfdfeff7fbff),
         offset: 231,
         index_bits: 5,
         shift: 59,
     },
     MagicEntry {
         number: 4543459206103048,
-        blocker_mask: 0x0000004020100a00,
-        inverse_blocker_mask: 0xffffffbfdfeff5ff,
+        blocker_mask: Bitboard(0x0000004020100a00),
+        inverse_blocker_mask: Bitboard(0xffffffbfdfeff5ff),
         offset: 259,
         index_bits: 5,
         shift: 59,
     },
     MagicEntry {
         number: 6346205537395540000,
-        blocker_mask: 0x0000000040221400,
-        inverse_blocker_mask: 0xffffffffbfddebff,
+        blocker_mask: Bitboard(0x0000000040221400),
+        inverse_blocker_mask: Bitboard(0xffffffffbfddebff),
         offset: 289,
         index_bits: 5,
         shift: 59,
     },
     MagicEntry {
         number: 1199573628520960,
-        blocker_mask: 0x0000000002442800,
-        inverse_blocker_mask: 0xfffffffffdbbd7ff,
+        blocker_mask: Bitboard(0x0000000002442800),
+        inverse_blocker_mask: Bitboard(0xfffffffffdbbd7ff),
         offset: 320,
         index_bits: 5,
         shift: 59,
     },
     MagicEntry {
         number: 594653411308224544,
-        blocker_mask: 0x0000000204085000,
-        inverse_blocker_mask: 0xfffffffdfbf7afff,
+        blocker_mask: Bitboard(0x0000000204085000),
+        inverse_blocker_mask: Bitboard(0xfffffffdfbf7afff),
         offset: 347,
         index_bits: 5,
         shift: 59,
     },
     MagicEntry {
         number: 4614667928472657924,
-        blocker_mask: 0x0000020408102000,
-        inverse_blocker_mask: 0xfffffdfbf7efdfff,
+        blocker_mask: Bitboard(0x0000020408102000),
+        inverse_blocker_mask: Bitboard(0xfffffdfbf7efdfff),
         offset: 377,
         index_bits: 5,
         shift: 59,
     },
     MagicEntry {
         number: 3684017101630177296,
-        blocker_mask: 0x0002040810204000,
-        inverse_blocker_mask: 0xfffdfbf7efdfbfff,
+        blocker_mask: Bitboard(0x0002040810204000),
+        inverse_blocker_mask: Bitboard(0xfffdfbf7efdfbfff),
         offset: 55,
         index_bits: 6,
         shift: 58,
     },
     MagicEntry {
         number: 1164189885226600456,
-        blocker_mask: 0x0020100804020000,
-        inverse_blocker_mask: 0xffdfeff7fbfdffff,
+        blocker_mask: Bitboard(0x0020100804020000),
+        inverse_blocker_mask: Bitboard(0xffdfeff7fbfdffff),
         offset: 403,
         index_bits: 5,
         shift: 59,
     },
     MagicEntry {
         number: 288354703134466052,
-        blocker_mask: 0x0040201008040000,
-        inverse_blocker_mask: 0xffbfdfeff7fbffff,
+        blocker_mask: Bitboard(0x0040201008040000),
+        inverse_blocker_mask: Bitboard(0xffbfdfeff7fbffff),
         offset: 917,
         index_bits: 5,
         shift: 59,
     },
     MagicEntry {
         number: 22535676501166336,
-        blocker_mask: 0x00004020100a0000,
-        inverse_blocker_mask: 0xffffbfdfeff5ffff,
+        blocker_mask: Bitboard(0x00004020100a0000),
+        inverse_blocker_mask: Bitboard(0xffffbfdfeff5ffff),
         offset: 949,
         index_bits: 5,
         shift: 59,
     },
     MagicEntry {
         number: 2295919328888080,
-        blocker_mask: 0x0000004022140000,
-        inverse_blocker_mask: 0xffffffbfddebffff,
+        blocker_mask: Bitboard(0x0000004022140000),
+        inverse_blocker_mask: Bitboard(0xffffffbfddebffff),
         offset: 979,
         index_bits: 5,
         shift: 59,
     },
     MagicEntry {
         number: 578995704295587973,
-        blocker_mask: 0x0000000244280000,
-        inverse_blocker_mask: 0xfffffffdbbd7ffff,
+        blocker_mask: Bitboard(0x0000000244280000),
+        inverse_blocker_mask: Bitboard(0xfffffffdbbd7ffff),
         offset: 1011,
         index_bits: 5,
         shift: 59,
     },
     MagicEntry {
         number: 576786905148990466,
-        blocker_mask: 0x0000020408500000,
-        inverse_blocker_mask: 0xfffffdfbf7afffff,
+        blocker_mask: Bitboard(0x0000020408500000),
+        inverse_blocker_mask: Bitboard(0xfffffdfbf7afffff),
         offset: 1037,
         index_bits: 5,
         shift: 59,
     },
     MagicEntry {
         number: 584254554144,
-        blocker_mask: 0x0002040810200000,
-        inverse_blocker_mask: 0xfffdfbf7efdfffff,
+        blocker_mask: Bitboard(0x0002040810200000),
+        inverse_blocker_mask: Bitboard(0xfffdfbf7efdfffff),
         offset: 1068,
         index_bits: 5,
         shift: 59,
     },
     MagicEntry {
         number: 972777875008913477,
-        blocker_mask: 0x0004081020400000,
-        inverse_blocker_mask: 0xfffbf7efdfbfffff,
+        blocker_mask: Bitboard(0x0004081020400000),
+        inverse_blocker_mask: Bitboard(0xfffbf7efdfbfffff),
         offset: 432,
         index_bits: 5,
         shift: 59,
     },
     MagicEntry {
         number: 4652219248570085393,
-        blocker_mask: 0x0010080402000200,
-        inverse_blocker_mask: 0xffeff7fbfdfffdff,
+        blocker_mask: Bitboard(0x0010080402000200),
+        inverse_blocker_mask: Bitboard(0xffeff7fbfdfffdff),
         offset: 450,
         index_bits: 5,
         shift: 59,
     },
     MagicEntry {
         number: 1160841392227360784,
-        blocker_mask: 0x0020100804000400,
-        inverse_blocker_mask: 0xffdfeff7fbfffbff,
+        blocker_mask: Bitboard(0x0020100804000400),
+        inverse_blocker_mask: Bitboard(0xffdfeff7fbfffbff),
         offset: 1091,
         index_bits: 5,
         shift: 59,
     },
     MagicEntry {
         number: 4503677005022209,
-        blocker_mask: 0x004020100a000a00,
-        inverse_blocker_mask: 0xffbfdfeff5fff5ff,
+        blocker_mask: Bitboard(0x004020100a000a00),
+        inverse_blocker_mask: Bitboard(0xffbfdfeff5fff5ff),
         offset: 1122,
         index_bits: 7,
         shift: 57,
     },
     MagicEntry {
         number: 1172062112329499140,
-        blocker_mask: 0x0000402214001400,
-        inverse_blocker_mask: 0xffffbfddebffebff,
+        blocker_mask: Bitboard(0x0000402214001400),
+        inverse_blocker_mask: Bitboard(0xffffbfddebffebff),
         offset: 1249,
         index_bits: 7,
         shift: 57,
     },
     MagicEntry {
         number: 19422358049652864,
-        blocker_mask: 0x0000024428002800,
-        inverse_blocker_mask: 0xfffffdbbd7ffd7ff,
+        blocker_mask: Bitboard(0x0000024428002800),
+        inverse_blocker_mask: Bitboard(0xfffffdbbd7ffd7ff),
         offset: 1377,
         index_bits: 7,
         shift: 57,
     },
     MagicEntry {
         number: 4683884350895292512,
-        blocker_mask: 0x0002040850005000,
-        inverse_blocker_mask: 0xfffdfbf7afffafff,
+        blocker_mask: Bitboard(0x0002040850005000),
+        inverse_blocker_mask: Bitboard(0xfffdfbf7afffafff),
         offset: 1499,
         index_bits: 7,
         shift: 57,
     },
     MagicEntry {
         number: 3377708480954434,
-        blocker_mask: 0x0004081020002000,
-        inverse_blocker_mask: 0xfffbf7efdfffdfff,
+        blocker_mask: Bitboard(0x0004081020002000),
+        inverse_blocker_mask: Bitboard(0xfffbf7efdfffdfff),
         offset: 1624,
         index_bits: 5,
         shift: 59,
     },
     MagicEntry {
         number: 6926820502796320800,
-        blocker_mask: 0x0008102040004000,
-        inverse_blocker_mask: 0xfff7efdfbfffbfff,
+        blocker_mask: Bitboard(0x0008102040004000),
+        inverse_blocker_mask: Bitboard(0xfff7efdfbfffbfff),
         offset: 482,
         index_bits: 5,
         shift: 59,
     },
     MagicEntry {
         number: 6865359295643712,
-        blocker_mask: 0x0008040200020400,
-        inverse_blocker_mask: 0xfff7fbfdfffdfbff,
+        blocker_mask: Bitboard(0x0008040200020400),
+        inverse_blocker_mask: Bitboard(0xfff7fbfdfffdfbff),
         offset: 506,
         index_bits: 5,
         shift: 59,
     },
     MagicEntry {
         number: 74555754334142498,
-        blocker_mask: 0x0010080400040800,
-        inverse_blocker_mask: 0xffeff7fbfffbf7ff,
+        blocker_mask: Bitboard(0x0010080400040800),
+        inverse_blocker_mask: Bitboard(0xffeff7fbfffbf7ff),
         offset: 1656,
         index_bits: 5,
         shift: 59,
     },
     MagicEntry {
         number: 19142510393165830,
-        blocker_mask: 0x0020100a000a1000,
-        inverse_blocker_mask: 0xffdfeff5fff5efff,
+        blocker_mask: Bitboard(0x0020100a000a1000),
+        inverse_blocker_mask: Bitboard(0xffdfeff5fff5efff),
         offset: 1688,
         index_bits: 7,
         shift: 57,
     },
     MagicEntry {
         number: 13835093310588387488,
-        blocker_mask: 0x0040221400142200,
-        inverse_blocker_mask: 0xffbfddebffebddff,
+        blocker_mask: Bitboard(0x0040221400142200),
+        inverse_blocker_mask: Bitboard(0xffbfddebffebddff),
         offset: 1787,
         index_bits: 9,
         shift: 55,
     },
     MagicEntry {
         number: 144260327914151940,
-        blocker_mask: 0x0002442800284400,
-        inverse_blocker_mask: 0xfffdbbd7ffd7bbff,
+        blocker_mask: Bitboard(0x0002442800284400),
+        inverse_blocker_mask: Bitboard(0xfffdbbd7ffd7bbff),
         offset: 2299,
         index_bits: 9,
         shift: 55,
     },
     MagicEntry {
         number: 18582296333385792,
-        blocker_mask: 0x0004085000500800,
-        inverse_blocker_mask: 0xfffbf7afffaff7ff,
+        blocker_mask: Bitboard(0x0004085000500800),
+        inverse_blocker_mask: Bitboard(0xfffbf7afffaff7ff),
         offset: 2810,
         index_bits: 7,
         shift: 57,
     },
     MagicEntry {
         number: 2252917857075266,
-        blocker_mask: 0x0008102000201000,
-        inverse_blocker_mask: 0xfff7efdfffdfefff,
+        blocker_mask: Bitboard(0x0008102000201000),
+        inverse_blocker_mask: Bitboard(0xfff7efdfffdfefff),
         offset: 2938,
         index_bits: 5,
         shift: 59,
     },
     MagicEntry {
         number: 9260669670333222946,
-        blocker_mask: 0x0010204000402000,
-        inverse_blocker_mask: 0xffefdfbfffbfdfff,
+        blocker_mask: Bitboard(0x0010204000402000),
+        inverse_blocker_mask: Bitboard(0xffefdfbfffbfdfff),
         offset: 537,
         index_bits: 5,
         shift: 59,
     },
     MagicEntry {
         number: 576606459071046660,
-        blocker_mask: 0x0004020002040800,
-        inverse_blocker_mask: 0xfffbfdfffdfbf7ff,
+        blocker_mask: Bitboard(0x0004020002040800),
+        inverse_blocker_mask: Bitboard(0xfffbfdfffdfbf7ff),
         offset: 569,
         index_bits: 5,
         shift: 59,
     },
     MagicEntry {
         number: 576557793870876675,
-        blocker_mask: 0x0008040004081000,
-        inverse_blocker_mask: 0xfff7fbfffbf7efff,
+        blocker_mask: Bitboard(0x0008040004081000),
+        inverse_blocker_mask: Bitboard(0xfff7fbfffbf7efff),
         offset: 2970,
         index_bits: 5,
         shift: 59,
     },
     MagicEntry {
         number: 36033367133652098,
-        blocker_mask: 0x00100a000a102000,
-        inverse_blocker_mask: 0xffeff5fff5efdfff,
+        blocker_mask: Bitboard(0x00100a000a102000),
+        inverse_blocker_mask: Bitboard(0xffeff5fff5efdfff),
         offset: 2999,
         index_bits: 7,
         shift: 57,
     },
     MagicEntry {
         number: 4899934021678793728,
-        blocker_mask: 0x0022140014224000,
-        inverse_blocker_mask: 0xffddebffebddbfff,
+        blocker_mask: Bitboard(0x0022140014224000),
+        inverse_blocker_mask: Bitboard(0xffddebffebddbfff),
         offset: 3127,
         index_bits: 9,
         shift: 55,
     },
     MagicEntry {
         number: 288270044469674240,
-        blocker_mask: 0x0044280028440200,
-        inverse_blocker_mask: 0xffbbd7ffd7bbfdff,
+        blocker_mask: Bitboard(0x0044280028440200),
+        inverse_blocker_mask: Bitboard(0xffbbd7ffd7bbfdff),
         offset: 3639,
         index_bits: 9,
         shift: 55,
     },
     MagicEntry {
         number: 54060929448968321,
-        blocker_mask: 0x0008500050080400,
-        inverse_blocker_mask: 0xfff7afffaff7fbff,
+        blocker_mask: Bitboard(0x0008500050080400),
+        inverse_blocker_mask: Bitboard(0xfff7afffaff7fbff),
         offset: 4150,
         index_bits: 7,
         shift: 57,
     },
     MagicEntry {
         number: 79441057415680,
-        blocker_mask: 0x0010200020100800,
-        inverse_blocker_mask: 0xffefdfffdfeff7ff,
+        blocker_mask: Bitboard(0x0010200020100800),
+        inverse_blocker_mask: Bitboard(0xffefdfffdfeff7ff),
         offset: 4277,
         index_bits: 5,
         shift: 59,
     },
     MagicEntry {
         number: 38077712073730,
-        blocker_mask: 0x0020400040201000,
-        inverse_blocker_mask: 0xffdfbfffbfdfefff,
+        blocker_mask: Bitboard(0x0020400040201000),
+        inverse_blocker_mask: Bitboard(0xffdfbfffbfdfefff),
         offset: 599,
         index_bits: 5,
         shift: 59,
     },
     MagicEntry {
         number: 153160081042579456,
-        blocker_mask: 0x0002000204081000,
-        inverse_blocker_mask: 0xfffdfffdfbf7efff,
+        blocker_mask: Bitboard(0x0002000204081000),
+        inverse_blocker_mask: Bitboard(0xfffdfffdfbf7efff),
         offset: 631,
         index_bits: 5,
         shift: 59,
     },
     MagicEntry {
         number: 288237042749449232,
-        blocker_mask: 0x0004000408102000,
-        inverse_blocker_mask: 0xfffbfffbf7efdfff,
+        blocker_mask: Bitboard(0x0004000408102000),
+        inverse_blocker_mask: Bitboard(0xfffbfffbf7efdfff),
         offset: 4308,
         index_bits: 5,
         shift: 59,
     },
     MagicEntry {
         number: 18016546665399296,
-        blocker_mask: 0x000a000a10204000,
-        inverse_blocker_mask: 0xfff5fff5efdfbfff,
+        blocker_mask: Bitboard(0x000a000a10204000),
+        inverse_blocker_mask: Bitboard(0xfff5fff5efdfbfff),
         offset: 4338,
         index_bits: 7,
         shift: 57,
     },
     MagicEntry {
         number: 30804109953024,
-        blocker_mask: 0x0014001422400000,
-        inverse_blocker_mask: 0xffebffebddbfffff,
+        blocker_mask: Bitboard(0x0014001422400000),
+        inverse_blocker_mask: Bitboard(0xffebffebddbfffff),
         offset: 4459,
         index_bits: 7,
         shift: 57,
     },
     MagicEntry {
         number: 9946200331774995456,
-        blocker_mask: 0x0028002844020000,
-        inverse_blocker_mask: 0xffd7ffd7bbfdffff,
+        blocker_mask: Bitboard(0x0028002844020000),
+        inverse_blocker_mask: Bitboard(0xffd7ffd7bbfdffff),
         offset: 4586,
         index_bits: 7,
         shift: 57,
     },
     MagicEntry {
         number: 4683778848446747904,
-        blocker_mask: 0x0050005008040200,
-        inverse_blocker_mask: 0xffafffaff7fbfdff,
+        blocker_mask: Bitboard(0x0050005008040200),
+        inverse_blocker_mask: Bitboard(0xffafffaff7fbfdff),
         offset: 4714,
         index_bits: 7,
         shift: 57,
     },
     MagicEntry {
         number: 36997452308864,
-        blocker_mask: 0x0020002010080400,
-        inverse_blocker_mask: 0xffdfffdfeff7fbff,
+        blocker_mask: Bitboard(0x0020002010080400),
+        inverse_blocker_mask: Bitboard(0xffdfffdfeff7fbff),
         offset: 4836,
         index_bits: 5,
         shift: 59,
     },
     MagicEntry {
         number: 9079837365109248,
-        blocker_mask: 0x0040004020100800,
-        inverse_blocker_mask: 0xffbfffbfdfeff7ff,
+        blocker_mask: Bitboard(0x0040004020100800),
+        inverse_blocker_mask: Bitboard(0xffbfffbfdfeff7ff),
         offset: 660,
         index_bits: 5,
         shift: 59,
     },
     MagicEntry {
         number: 450362731047036968,
-        blocker_mask: 0x0000020408102000,
-        inverse_blocker_mask: 0xfffffdfbf7efdfff,
+        blocker_mask: Bitboard(0x0000020408102000),
+        inverse_blocker_mask: Bitboard(0xfffffdfbf7efdfff),
         offset: 682,
         index_bits: 5,
         shift: 59,
     },
     MagicEntry {
         number: 5044032003664060416,
-        blocker_mask: 0x0000040810204000,
-        inverse_blocker_mask: 0xfffffbf7efdfbfff,
+        blocker_mask: Bitboard(0x0000040810204000),
+        inverse_blocker_mask: Bitboard(0xfffffbf7efdfbfff),
         offset: 4868,
         index_bits: 5,
         shift: 59,
     },
     MagicEntry {
         number: 288934068025296896,
-        blocker_mask: 0x00000a1020400000,
-        inverse_blocker_mask: 0xfffff5efdfbfffff,
+        blocker_mask: Bitboard(0x00000a1020400000),
+        inverse_blocker_mask: Bitboard(0xfffff5efdfbfffff),
         offset: 4899,
         index_bits: 5,
         shift: 59,
     },
     MagicEntry {
         number: 1154364682691413060,
-        blocker_mask: 0x0000142240000000,
-        inverse_blocker_mask: 0xffffebddbfffffff,
+        blocker_mask: Bitboard(0x0000142240000000),
+        inverse_blocker_mask: Bitboard(0xffffebddbfffffff),
         offset: 4931,
         index_bits: 5,
         shift: 59,
     },
     MagicEntry {
         number: 2324984411487224321,
-        blocker_mask: 0x0000284402000000,
-        inverse_blocker_mask: 0xffffd7bbfdffffff,
+        blocker_mask: Bitboard(0x0000284402000000),
+        inverse_blocker_mask: Bitboard(0xffffd7bbfdffffff),
         offset: 4961,
         index_bits: 5,
         shift: 59,
     },
     MagicEntry {
         number: 6756929795825985,
-        blocker_mask: 0x0000500804020000,
-        inverse_blocker_mask: 0xffffaff7fbfdffff,
+        blocker_mask: Bitboard(0x0000500804020000),
+        inverse_blocker_mask: Bitboard(0xffffaff7fbfdffff),
         offset: 4986,
         index_bits: 5,
         shift: 59,
     },
     MagicEntry {
         number: 9511971857538220097,
-        blocker_mask: 0x0000201008040200,
-        inverse_blocker_mask: 0xffffdfeff7fbfdff,
+        blocker_mask: Bitboard(0x0000201008040200),
+        inverse_blocker_mask: Bitboard(0xffffdfeff7fbfdff),
         offset: 5017,
         index_bits: 5,
         shift: 59,
     },
     MagicEntry {
         number: 72602163905060,
-        blocker_mask: 0x0000402010080400,
-        inverse_blocker_mask: 0xffffbfdfeff7fbff,
+        blocker_mask: Bitboard(0x0000402010080400),
+        inverse_blocker_mask: Bitboard(0xffffbfdfeff7fbff),
         offset: 709,
         index_bits: 5,
         shift: 59,
     },
     MagicEntry {
         number: 144117389259452740,
-        blocker_mask: 0x0002040810204000,
-        inverse_blocker_mask: 0xfffdfbf7efdfbfff,
+        blocker_mask: Bitboard(0x0002040810204000),
+        inverse_blocker_mask: Bitboard(0xfffdfbf7efdfbfff),
         offset: 105,
         index_bits: 6,
         shift: 58,
     },
     MagicEntry {
         number: 432345573396586752,
-        blocker_mask: 0x0004081020400000,
-        inverse_blocker_mask: 0xfffbf7efdfbfffff,
+        blocker_mask: Bitboard(0x0004081020400000),
+        inverse_blocker_mask: Bitboard(0xfffbf7efdfbfffff),
         offset: 740,
         index_bits: 5,
         shift: 59,
     },
     MagicEntry {
         number: 1135795663030281,
-        blocker_mask: 0x000a102040000000,
-        inverse_blocker_mask: 0xfff5efdfbfffffff,
+        blocker_mask: Bitboard(0x000a102040000000),
+        inverse_blocker_mask: Bitboard(0xfff5efdfbfffffff),
         offset: 771,
         index_bits: 5,
         shift: 59,
     },
     MagicEntry {
         number: 2305851805307506864,
-        blocker_mask: 0x0014224000000000,
-        inverse_blocker_mask: 0xffebddbfffffffff,
+        blocker_mask: Bitboard(0x0014224000000000),
+        inverse_blocker_mask: Bitboard(0xffebddbfffffffff),
         offset: 803,
         index_bits: 5,
         shift: 59,
     },
     MagicEntry {
         number: 36028865757380928,
-        blocker_mask: 0x0028440200000000,
-        inverse_blocker_mask: 0xffd7bbfdffffffff,
+        blocker_mask: Bitboard(0x0028440200000000),
+        inverse_blocker_mask: Bitboard(0xffd7bbfdffffffff),
         offset: 835,
         index_bits: 5,
         shift: 59,
     },
     MagicEntry {
         number: 13835058606150521184,
-        blocker_mask: 0x0050080402000000,
-        inverse_blocker_mask: 0xffaff7fbfdffffff,
+        blocker_mask: Bitboard(0x0050080402000000),
+        inverse_blocker_mask: Bitboard(0xffaff7fbfdffffff),
         offset: 863,
         index_bits: 5,
         shift: 59,
     },
     MagicEntry {
         number: 9994698458861825,
-        blocker_mask: 0x0020100804020000,
-        inverse_blocker_mask: 0xffdfeff7fbfdffff,
+        blocker_mask: Bitboard(0x0020100804020000),
+        inverse_blocker_mask: Bitboard(0xffdfeff7fbfdffff),
         offset: 886,
         index_bits: 5,
         shift: 59,
     },
     MagicEntry {
         number: 90635097190049832,
-        blocker_mask: 0x0040201008040200,
-        inverse_blocker_mask: 0xffbfdfeff7fbfdff,
+        blocker_mask: Bitboard(0x0040201008040200),
+        inverse_blocker_mask: Bitboard(0xffbfdfeff7fbfdff),
         offset: 167,
         index_bits: 6,
         shift: 58,
@@ -2,7 +2,14 @@ use rand::Rng;
 use rand_pcg::Pcg64;
 use std::fmt::Display;
 
-use crate::types::{BitBoard, EMPTY_BITBOARD, FULL_BITBOARD, NumOf};
+use crate::{
+    board::types::Square,
+    movement::sliders::{
+        defs::{BISHOP_SLIDER, ROOK_SLIDER, Slider, SliderAttacks, SliderRays},
+        magic_entries::{BISHOP_MAGICS, ROOK_MAGICS},
+    },
+    types::{Bitboard, EMPTY_BITBOARD, FULL_BITBOARD, NumOf},
+};
 
 pub const MAX_ROOK_TABLE_SIZE: usize = 102400;
 pub const MAX_BISHOP_TABLE_SIZE: usize = 5248;
@@ -10,8 +17,8 @@ pub const MAX_BISHOP_TABLE_SIZE: usize = 5248;
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub struct MagicEntry {
     pub number: u64,
-    pub blocker_mask: BitBoard,
-    pub inverse_blocker_mask: BitBoard,
+    pub blocker_mask: Bitboard,
+    pub inverse_blocker_mask: Bitboard,
     pub offset: u32,
     pub index_bits: u8,
     pub shift: u8,
@@ -41,13 +48,8 @@ impl Default for MagicEntry {
 }
 
 impl MagicEntry {
-    pub fn new(rng: &mut Pcg64, blocker_mask: BitBoard) -> Self {
-        let mut number_of_bits_set = 0;
-        let mut temp_blocker = blocker_mask;
-        while temp_blocker != 0 {
-            number_of_bits_set += 1;
-            temp_blocker &= temp_blocker - 1;
-        }
+    pub fn new(rng: &mut Pcg64, blocker_mask: Bitboard) -> Self {
+        let number_of_bits_set = blocker_mask.popcount() as u8;
         let mut magic_numbers = [0u64; 3];
         rng.fill(&mut magic_numbers);
         let magic_number: u64 = magic_numbers.into_iter().reduce(|acc, m| acc & m).unwrap();
@@ -63,7 +65,78 @@ impl MagicEntry {
     }
 
     #[inline(always)]
-    pub fn get_magic_index(&self, occupancy: BitBoard) -> usize {
-        ((occupancy | self.inverse_blocker_mask).wrapping_mul(self.number) >> self.shift) as usize
+    pub const fn get_magic_index(&self, occupancy: Bitboard) -> usize {
+        ((occupancy.0 | self.inverse_blocker_mask.0).wrapping_mul(self.number) >> self.shift)
+            as usize
+    }
+}
+
+// Slider attacks looked up by magic multiplication, see magic_entries.rs
+pub struct MagicAttacks {
+    pub rook_attacks: [Bitboard; MAX_ROOK_TABLE_SIZE],
+    pub bishop_attacks: [Bitboard; MAX_BISHOP_TABLE_SIZE],
+}
+
+impl MagicAttacks {
+    pub const fn new() -> Self {
+        let mut rook_attacks = [EMPTY_BITBOARD; MAX_ROOK_TABLE_SIZE];
+        let mut bishop_attacks = [EMPTY_BITBOARD; MAX_BISHOP_TABLE_SIZE];
+        fill_magic_table(&ROOK_SLIDER, &ROOK_MAGICS, &mut rook_attacks);
+        fill_magic_table(&BISHOP_SLIDER, &BISHOP_MAGICS, &mut bishop_attacks);
+        Self {
+            rook_attacks,
+            bishop_attacks,
+        }
+    }
+}
+
+impl Default for MagicAttacks {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SliderAttacks for MagicAttacks {
+    #[inline(always)]
+    fn rook_attacks(&self, square: Square, occupancy: Bitboard) -> Bitboard {
+        let magic_entry = &ROOK_MAGICS[square];
+        self.rook_attacks[magic_entry.get_magic_index(occupancy) + magic_entry.offset as usize]
+    }
+
+    #[inline(always)]
+    fn bishop_attacks(&self, square: Square, occupancy: Bitboard) -> Bitboard {
+        let magic_entry = &BISHOP_MAGICS[square];
+        self.bishop_attacks[magic_entry.get_magic_index(occupancy) + magic_entry.offset as usize]
+    }
+}
+
+// Walks every blocker subset of every square, so a bad set of magics stops the build
+// instead of handing out wrong attacks
+const fn fill_magic_table(
+    slider: &Slider,
+    magics: &[MagicEntry; NumOf::SQUARES],
+    table: &mut [Bitboard],
+) {
+    let rays = SliderRays::new(slider);
+    let mut square_idx = 0;
+    while square_idx < NumOf::SQUARES {
+        let magic_entry = &magics[square_idx];
+        let blocker_mask = magic_entry.blocker_mask.0;
+        // Carry-rippler: steps through every subset of the mask, starting and ending at 0
+        let mut blocker_subset = 0u64;
+        loop {
+            let attacks = rays.attacks(square_idx, blocker_subset);
+            let slot =
+                magic_entry.get_magic_index(Bitboard(blocker_subset)) + magic_entry.offset as usize;
+            if table[slot].0 != 0 && table[slot].0 != attacks {
+                panic!("Collision detected while building a slider attack table");
+            }
+            table[slot] = Bitboard(attacks);
+            blocker_subset = blocker_subset.wrapping_sub(blocker_mask) & blocker_mask;
+            if blocker_subset == 0 {
+                break;
+            }
+        }
+        square_idx += 1;
     }
 }
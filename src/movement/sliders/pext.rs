@@ -0,0 +1,191 @@
+use crate::{
+    board::types::Square,
+    movement::sliders::{
+        defs::{BISHOP_SLIDER, ROOK_SLIDER, Slider, SliderAttacks, SliderRays},
+        magics::{MAX_BISHOP_TABLE_SIZE, MAX_ROOK_TABLE_SIZE},
+    },
+    types::{Bitboard, EMPTY_BITBOARD, NumOf},
+};
+
+// Slider attacks indexed by PEXT of the occupancy under each square's blocker mask. The
+// index is perfect, so the tables come out exactly as large as the magic ones.
+pub struct PextAttacks {
+    rook: PextTable<MAX_ROOK_TABLE_SIZE>,
+    bishop: PextTable<MAX_BISHOP_TABLE_SIZE>,
+}
+
+struct PextTable<const SIZE: usize> {
+    blocker_masks: [Bitboard; NumOf::SQUARES],
+    offsets: [u32; NumOf::SQUARES],
+    attacks: [Bitboard; SIZE],
+}
+
+impl PextAttacks {
+    pub const fn new() -> Self {
+        Self {
+            rook: PextTable::new(&ROOK_SLIDER),
+            bishop: PextTable::new(&BISHOP_SLIDER),
+        }
+    }
+}
+
+impl Default for PextAttacks {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SliderAttacks for PextAttacks {
+    #[inline(always)]
+    fn rook_attacks(&self, square: Square, occupancy: Bitboard) -> Bitboard {
+        self.rook.lookup(square, occupancy)
+    }
+
+    #[inline(always)]
+    fn bishop_attacks(&self, square: Square, occupancy: Bitboard) -> Bitboard {
+        self.bishop.lookup(square, occupancy)
+    }
+}
+
+impl<const SIZE: usize> PextTable<SIZE> {
+    const fn new(slider: &Slider) -> Self {
+        let rays = SliderRays::new(slider);
+        let mut blocker_masks = [EMPTY_BITBOARD; NumOf::SQUARES];
+        let mut offsets = [0u32; NumOf::SQUARES];
+        let mut attacks = [EMPTY_BITBOARD; SIZE];
+        let mut offset = 0;
+        let mut square_idx = 0;
+        while square_idx < NumOf::SQUARES {
+            let blocker_mask = slider.get_blocker_mask(Square::from_index(square_idx)).0;
+            blocker_masks[square_idx] = Bitboard(blocker_mask);
+            offsets[square_idx] = offset as u32;
+            // The carry-rippler counts through the subsets in the same order PEXT numbers
+            // them, so each one simply takes the next slot
+            let mut blocker_subset = 0u64;
+            loop {
+                attacks[offset] = Bitboard(rays.attacks(square_idx, blocker_subset));
+                offset += 1;
+                blocker_subset = blocker_subset.wrapping_sub(blocker_mask) & blocker_mask;
+                if blocker_subset == 0 {
+                    break;
+                }
+            }
+            square_idx += 1;
+        }
+        assert!(
+            offset == SIZE,
+            "PEXT table size does not match the blocker masks"
+        );
+        Self {
+            blocker_masks,
+            offsets,
+            attacks,
+        }
+    }
+
+    #[inline(always)]
+    fn lookup(&self, square: Square, occupancy: Bitboard) -> Bitboard {
+        let index = pext(occupancy.0, self.blocker_masks[square].0);
+        self.attacks[self.offsets[square] as usize + index as usize]
+    }
+}
+
+// Whether this CPU has the PEXT instruction. Known at compile time when building with
+// -C target-feature=+bmi2, otherwise detected once and cached by std.
+#[inline(always)]
+pub fn has_bmi2() -> bool {
+    #[cfg(target_arch = "x86_64")]
+    {
+        cfg!(target_feature = "bmi2") || std::arch::is_x86_feature_detected!("bmi2")
+    }
+    #[cfg(not(target_arch = "x86_64"))]
+    {
+        false
+    }
+}
+
+#[inline(always)]
+fn pext(value: u64, mask: u64) -> u64 {
+    #[cfg(target_arch = "x86_64")]
+    if has_bmi2() {
+        // SAFETY: the CPU was just checked for BMI2
+        return unsafe { pext_bmi2(value, mask) };
+    }
+    software_pext(value, mask)
+}
+
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "bmi2")]
+fn pext_bmi2(value: u64, mask: u64) -> u64 {
+    std::arch::x86_64::_pext_u64(value, mask)
+}
+
+// The same bit gathering one mask bit at a time, for CPUs without BMI2
+const fn software_pext(value: u64, mut mask: u64) -> u64 {
+    let mut result = 0;
+    let mut bit = 1;
+    while mask != 0 {
+        if value & mask & mask.wrapping_neg() != 0 {
+            result |= bit;
+        }
+        mask &= mask - 1;
+        bit <<= 1;
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::movement::{
+        ATTACKS,
+        sliders::{
+            defs::get_all_blockers_subsets,
+            magic_entries::{BISHOP_MAGICS, ROOK_MAGICS},
+        },
+    };
+
+    static PEXT_ATTACKS: PextAttacks = PextAttacks::new();
+
+    #[test]
+    fn pext_and_magic_lookups_agree() {
+        let magic = &ATTACKS.magic;
+        for square in Square::ALL {
+            let rook_mask = PEXT_ATTACKS.rook.blocker_masks[square];
+            let bishop_mask = PEXT_ATTACKS.bishop.blocker_masks[square];
+            assert_eq!(rook_mask, ROOK_MAGICS[square].blocker_mask);
+            assert_eq!(bishop_mask, BISHOP_MAGICS[square].blocker_mask);
+            // Squares outside the masks never change the attacks, so the subsets cover
+            // every occupancy that matters
+            for occupancy in get_all_blockers_subsets(rook_mask) {
+                assert_eq!(
+                    PEXT_ATTACKS.rook_attacks(square, occupancy),
+                    magic.rook_attacks(square, occupancy),
+                    "rook on {square} occupancy {occupancy:#018x}"
+                );
+            }
+            for occupancy in get_all_blockers_subsets(bishop_mask) {
+                assert_eq!(
+                    PEXT_ATTACKS.bishop_attacks(square, occupancy),
+                    magic.bishop_attacks(square, occupancy),
+                    "bishop on {square} occupancy {occupancy:#018x}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn software_pext_gathers_masked_bits() {
+        assert_eq!(software_pext(0b1011_0110, 0b1111_0000), 0b1011);
+        assert_eq!(software_pext(0b1011_0110, 0b0101_0101), 0b0110);
+        assert_eq!(software_pext(u64::MAX, 0), 0);
+        #[cfg(target_arch = "x86_64")]
+        if has_bmi2() {
+            let (value, mask) = (0x0123_4567_89ab_cdef, 0x00ff_00f0_f0f0_0f0f);
+            // SAFETY: checked for BMI2 just above
+            assert_eq!(software_pext(value, mask), unsafe {
+                pext_bmi2(value, mask)
+            });
+        }
+    }
+}
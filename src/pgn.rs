@@ -0,0 +1,228 @@
+use std::fmt::Display;
+
+// Just enough PGN for building books and reading openings: the tag pairs, the mainline
+// SAN moves and the result. Comments, variations, NAGs and move numbers are skipped.
+#[derive(Debug, Default)]
+pub struct Game {
+    pub tags: Vec<(String, String)>,
+    pub moves: Vec<String>,
+    pub result: Option<GameResult>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum GameResult {
+    WhiteWin,
+    Draw,
+    BlackWin,
+}
+
+// Export lines stay within 80 columns
+const MAX_LINE: usize = 79;
+
+impl Display for GameResult {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GameResult::WhiteWin => write!(f, "1-0"),
+            GameResult::Draw => write!(f, "1/2-1/2"),
+            GameResult::BlackWin => write!(f, "0-1"),
+        }
+    }
+}
+
+impl Game {
+    pub fn tag(&self, name: &str) -> Option<&str> {
+        self.tags
+            .iter()
+            .find(|(tag, _)| tag == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    // The tags in the order given, then the moves numbered from the FEN tag if there is one
+    pub fn to_pgn(&self) -> String {
+        let mut pgn = String::new();
+        for (name, value) in &self.tags {
+            let value = value.replace('\\', "\\\\").replace('"', "\\\"");
+            pgn.push_str(&format!("[{name} \"{value}\"]\n"));
+        }
+        pgn.push('\n');
+
+        let (mut number, mut white_to_move) = self.tag("FEN").map_or((1, true), |fen| {
+            let fields: Vec<&str> = fen.split_whitespace().collect();
+            let number = fields.get(5).and_then(|n| n.parse().ok()).unwrap_or(1);
+            (number, fields.get(1) != Some(&"b"))
+        });
+        let mut tokens = Vec::new();
+        for (ply, san) in self.moves.iter().enumerate() {
+            if white_to_move {
+                tokens.push(format!("{number}."));
+            } else if ply == 0 {
+                tokens.push(format!("{number}..."));
+            }
+            tokens.push(san.clone());
+            if !white_to_move {
+                number += 1;
+            }
+            white_to_move = !white_to_move;
+        }
+        tokens.push(
+            self.result
+                .map_or("*".to_string(), |result| result.to_string()),
+        );
+
+        let mut line = String::new();
+        for token in tokens {
+            if !line.is_empty() && line.len() + 1 + token.len() > MAX_LINE {
+                pgn.push_str(&line);
+                pgn.push('\n');
+                line.clear();
+            }
+            if !line.is_empty() {
+                line.push(' ');
+            }
+            line.push_str(&token);
+        }
+        pgn.push_str(&line);
+        pgn.push_str("\n\n");
+        pgn
+    }
+}
+
+fn parse_result(token: &str) -> Option<Option<GameResult>> {
+    match token {
+        "1-0" => Some(Some(GameResult::WhiteWin)),
+        "0-1" => Some(Some(GameResult::BlackWin)),
+        "1/2-1/2" => Some(Some(GameResult::Draw)),
+        "*" => Some(None),
+        _ => None,
+    }
+}
+
+// A tag pair line such as `[White "Carlsen, Magnus"]`
+fn parse_tag(line: &str) -> Option<(String, String)> {
+    let inner = line.strip_prefix('[')?.trim_end().strip_suffix(']')?;
+    let (name, value) = inner.split_once(char::is_whitespace)?;
+    Some((name.to_string(), value.trim().trim_matches('"').to_string()))
+}
+
+pub fn parse_games(text: &str) -> Vec<Game> {
+    let mut games = Vec::new();
+    let mut game = Game::default();
+    let mut in_movetext = false;
+    let mut comment_depth = 0;
+    let mut variation_depth = 0;
+
+    for line in text.lines() {
+        let line = line.trim();
+        if comment_depth == 0 && variation_depth == 0 && line.starts_with('[') {
+            if in_movetext {
+                games.push(std::mem::take(&mut game));
+                in_movetext = false;
+            }
+            if let Some(tag) = parse_tag(line) {
+                game.tags.push(tag);
+            }
+            continue;
+        }
+        // A line starting with % is an escape and is ignored entirely
+        if line.starts_with('%') {
+            continue;
+        }
+
+        let mut token = String::new();
+        let mut chars = line.chars().peekable();
+        while let Some(c) = chars.next() {
+            if comment_depth > 0 {
+                if c == '}' {
+                    comment_depth = 0;
+                }
+                continue;
+            }
+            match c {
+                '{' => comment_depth = 1,
+                ';' => break,
+                '(' => variation_depth += 1,
+                ')' => variation_depth -= 1,
+                c if c.is_whitespace() => {}
+                _ => {
+                    token.push(c);
+                    if chars.peek().is_some_and(|&next| {
+                        !next.is_whitespace() && !matches!(next, '{' | ';' | '(' | ')')
+                    }) {
+                        continue;
+                    }
+                }
+            }
+            if token.is_empty() {
+                continue;
+            }
+            let word = std::mem::take(&mut token);
+            if variation_depth > 0 {
+                continue;
+            }
+            in_movetext = true;
+            if let Some(result) = parse_result(&word) {
+                game.result = result;
+                games.push(std::mem::take(&mut game));
+                in_movetext = false;
+                continue;
+            }
+            // Move numbers such as `12.` or `12...`, possibly glued to the move
+            let san = word.trim_start_matches(|c: char| c.is_ascii_digit() || c == '.');
+            if !san.is_empty() && !san.starts_with('$') {
+                game.moves.push(san.to_string());
+            }
+        }
+    }
+    if in_movetext {
+        games.push(game);
+    }
+    games
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn skips_comments_variations_and_numbers() {
+        let text = r#"[Event "Test"]
+[WhiteElo "2700"]
+
+1. e4 {best by test} e5 2.Nf3 (2. f4 exf4 (2... d5)) 2... Nc6 $1 3. Bb5 ; Ruy
+a6 1-0
+
+[Event "Second"]
+
+1. d4 d5 *
+"#;
+        let games = parse_games(text);
+        assert_eq!(games.len(), 2);
+        assert_eq!(games[0].tag("WhiteElo"), Some("2700"));
+        assert_eq!(games[0].moves, ["e4", "e5", "Nf3", "Nc6", "Bb5", "a6"]);
+        assert_eq!(games[0].result, Some(GameResult::WhiteWin));
+        assert_eq!(games[1].moves, ["d4", "d5"]);
+        assert_eq!(games[1].result, None);
+    }
+
+    #[test]
+    fn written_games_parse_back() {
+        let game = Game {
+            tags: vec![
+                ("White".to_string(), "Engine \"A\"".to_string()),
+                (
+                    "FEN".to_string(),
+                    "4k3/8/8/8/8/8/4P3/4K3 b - - 0 12".to_string(),
+                ),
+            ],
+            moves: ["Kd7", "e4", "Kc6", "e5"].map(String::from).to_vec(),
+            result: Some(GameResult::Draw),
+        };
+        let pgn = game.to_pgn();
+        assert!(pgn.contains("12... Kd7 13. e4 Kc6 14. e5 1/2-1/2"), "{pgn}");
+        let parsed = parse_games(&pgn);
+        assert_eq!(parsed.len(), 1);
+        assert_eq!(parsed[0].moves, game.moves);
+        assert_eq!(parsed[0].result, game.result);
+        assert_eq!(parsed[0].tag("FEN"), game.tag("FEN"));
+    }
+}
@@ -0,0 +1,2 @@
+pub mod mate;
+pub mod time;
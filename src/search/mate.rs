@@ -0,0 +1,138 @@
+use crate::board::{Board, piece_movement::Move};
+
+// A forced mate found by MateSearch: the first move and the number of moves it mates in
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Mate {
+    pub key_move: Move,
+    pub moves: u32,
+}
+
+// `go mate N`: a depth-first proof search that only asks whether the attacker can force
+// mate, with no evaluation. Every defence has to be refuted, so a single reply that holds
+// out is enough to drop a candidate key move. Much faster than the regular search on
+// composed problems, where the answer is yes or no rather than a score.
+pub struct MateSearch {
+    // Limits the attacker to checking moves, as in checks-only problems. Mates that need
+    // a quiet move are then not found.
+    pub checks_only: bool,
+    pub nodes: u64,
+}
+
+impl MateSearch {
+    pub fn new(checks_only: bool) -> Self {
+        Self {
+            checks_only,
+            nodes: 0,
+        }
+    }
+
+    // The shortest forced mate in at most `max_moves` moves for the side to move. None
+    // proves there is none, counting stalemate as a failure.
+    pub fn find(&mut self, board: &mut Board, max_moves: u32) -> Option<Mate> {
+        (1..=max_moves).find_map(|moves| {
+            self.key_move(board, moves)
+                .map(|key_move| Mate { key_move, moves })
+        })
+    }
+
+    fn key_move(&mut self, board: &mut Board, moves: u32) -> Option<Move> {
+        self.attacker_moves(board, moves)
+            .into_iter()
+            .find(|&chess_move| {
+                board.make(chess_move);
+                let mates = self.defender_is_mated(board, moves);
+                board.unmake(chess_move);
+                mates
+            })
+    }
+
+    // Whether the side to move can mate in `moves`
+    fn attacker_mates(&mut self, board: &mut Board, moves: u32) -> bool {
+        self.key_move(board, moves).is_some()
+    }
+
+    // Whether every reply of the side to move still loses to a mate in the attacker's
+    // remaining `moves - 1`, or there is no reply because it is mate already
+    fn defender_is_mated(&mut self, board: &mut Board, moves: u32) -> bool {
+        self.nodes += 1;
+        let replies = board.legal_moves();
+        if replies.is_empty() {
+            return board.in_check();
+        }
+        if moves == 1 {
+            return false;
+        }
+        replies.iter().all(|&reply| {
+            board.make(reply);
+            let mated = self.attacker_mates(board, moves - 1);
+            board.unmake(reply);
+            mated
+        })
+    }
+
+    // Checks first, as they are the likeliest key moves and the only ones that can mate
+    // on the last move
+    fn attacker_moves(&mut self, board: &mut Board, moves: u32) -> Vec<Move> {
+        self.nodes += 1;
+        let (mut checks, mut quiet) = (Vec::new(), Vec::new());
+        for &chess_move in board.legal_moves().iter() {
+            board.make(chess_move);
+            let gives_check = board.in_check();
+            board.unmake(chess_move);
+            if gives_check {
+                checks.push(chess_move);
+            } else {
+                quiet.push(chess_move);
+            }
+        }
+        if !self.checks_only && moves > 1 {
+            checks.append(&mut quiet);
+        }
+        checks
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn find(fen: &str, max_moves: u32, checks_only: bool) -> Option<(String, u32)> {
+        let mut board = Board::from_fen(fen).unwrap();
+        let mate = MateSearch::new(checks_only).find(&mut board, max_moves);
+        // Every move made was unmade
+        assert_eq!(
+            board.game_state.zobrist_key,
+            Board::from_fen(fen).unwrap().game_state.zobrist_key
+        );
+        mate.map(|mate| (mate.key_move.to_string(), mate.moves))
+    }
+
+    #[test]
+    fn finds_the_shortest_mate() {
+        // Back rank mate in one, found before the longer ones
+        assert_eq!(
+            find("6k1/5ppp/8/8/8/8/8/R5K1 w - - 0 1", 3, false),
+            Some(("a1a8".to_string(), 1))
+        );
+        // 1. Kb6 Kb8 2. Rh8#, with 1. Rh8+ Ka7 letting the king out
+        let quiet_key = "k7/8/2K5/8/8/8/8/7R w - - 0 1";
+        assert_eq!(find(quiet_key, 1, false), None);
+        assert_eq!(find(quiet_key, 2, false), Some(("c6b6".to_string(), 2)));
+        assert_eq!(find(quiet_key, 2, true), None);
+    }
+
+    #[test]
+    fn stalemate_is_not_mate() {
+        let mut search = MateSearch::new(false);
+        assert!(search.defender_is_mated(
+            &mut Board::from_fen("k7/1Q6/1K6/8/8/8/8/8 b - - 0 1").unwrap(),
+            1
+        ));
+        assert!(!search.defender_is_mated(
+            &mut Board::from_fen("k7/2Q5/1K6/8/8/8/8/8 b - - 0 1").unwrap(),
+            1
+        ));
+        // Stalemated at the root, so the side to move has nothing to mate with
+        assert_eq!(find("k7/2Q5/1K6/8/8/8/8/8 b - - 0 1", 3, false), None);
+    }
+}
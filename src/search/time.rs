@@ -0,0 +1,286 @@
+use std::time::{Duration, Instant};
+
+use crate::board::types::Side;
+
+pub const DEFAULT_MOVE_OVERHEAD: Duration = Duration::from_millis(30);
+// Must stay a power of two, check() masks the node counter with it
+pub const NODES_BETWEEN_CHECKS: u64 = 1024;
+
+// Used when the GUI does not send movestogo (sudden death, with or without increment)
+const DEFAULT_MOVES_TO_GO: u32 = 30;
+const MAX_MOVES_TO_GO: u32 = 50;
+// The hard limit is at most this many times the soft limit...
+const HARD_LIMIT_SCALE: u32 = 5;
+// ...and never more than this share of what is left on the clock after the overhead
+const MAX_CLOCK_USAGE_PERCENT: u32 = 75;
+const MIN_SOFT_LIMIT: Duration = Duration::from_millis(1);
+
+// A score that falls by more than this many centipawns between iterations buys extra time
+const SCORE_DROP_MARGIN: i32 = 30;
+const SCORE_DROP_SCALE_PERCENT: u32 = 150;
+// Each best move change adds this much to the soft limit, decaying when the move holds
+const BEST_MOVE_CHANGE_PERCENT: u32 = 50;
+const MAX_INSTABILITY_PERCENT: u32 = 200;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct TimeControl {
+    pub wtime: Option<Duration>,
+    pub btime: Option<Duration>,
+    pub winc: Duration,
+    pub binc: Duration,
+    pub movestogo: Option<u32>,
+    pub movetime: Option<Duration>,
+}
+
+impl TimeControl {
+    fn clock(&self, side: Side) -> Option<(Duration, Duration)> {
+        match side {
+            Side::White => self.wtime.map(|time| (time, self.winc)),
+            Side::Black => self.btime.map(|time| (time, self.binc)),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct TimeManager {
+    start: Instant,
+    base_soft_limit: Duration,
+    soft_limit: Duration,
+    hard_limit: Duration,
+    instability_percent: u32,
+    previous_score: Option<i32>,
+    stopped: bool,
+}
+
+impl TimeManager {
+    pub fn new(time_control: &TimeControl, side: Side, move_overhead: Duration) -> Self {
+        let (soft_limit, hard_limit) = Self::compute_limits(time_control, side, move_overhead);
+        Self {
+            start: Instant::now(),
+            base_soft_limit: soft_limit,
+            soft_limit,
+            hard_limit,
+            instability_percent: 0,
+            previous_score: None,
+            stopped: false,
+        }
+    }
+
+    fn compute_limits(
+        time_control: &TimeControl,
+        side: Side,
+        move_overhead: Duration,
+    ) -> (Duration, Duration) {
+        if let Some(movetime) = time_control.movetime {
+            let limit = movetime.saturating_sub(move_overhead).max(MIN_SOFT_LIMIT);
+            return (limit, limit);
+        }
+        let Some((time_left, increment)) = time_control.clock(side) else {
+            // No clock at all means `go infinite` or a depth/node limited search
+            return (Duration::MAX, Duration::MAX);
+        };
+
+        let moves_to_go = time_control
+            .movestogo
+            .unwrap_or(DEFAULT_MOVES_TO_GO)
+            .clamp(1, MAX_MOVES_TO_GO);
+        let usable = time_left.saturating_sub(move_overhead);
+        // Every remaining move pays the overhead, and every move but this one earns the increment
+        let budget =
+            (time_left + increment * (moves_to_go - 1)).saturating_sub(move_overhead * moves_to_go);
+
+        let hard_limit = (usable * MAX_CLOCK_USAGE_PERCENT / 100).max(MIN_SOFT_LIMIT);
+        let soft_limit = (budget / moves_to_go).clamp(MIN_SOFT_LIMIT, hard_limit);
+        let hard_limit = hard_limit.min(soft_limit * HARD_LIMIT_SCALE);
+        (soft_limit, hard_limit)
+    }
+
+    #[inline(always)]
+    pub fn elapsed(&self) -> Duration {
+        self.start.elapsed()
+    }
+
+    pub fn soft_limit(&self) -> Duration {
+        self.soft_limit
+    }
+
+    pub fn hard_limit(&self) -> Duration {
+        self.hard_limit
+    }
+
+    pub fn is_stopped(&self) -> bool {
+        self.stopped
+    }
+
+    pub fn stop(&mut self) {
+        self.stopped = true;
+    }
+
+    // Called by iterative deepening between iterations: an iteration started past the
+    // soft limit would most likely be cut off by the hard limit anyway.
+    pub fn should_start_iteration(&self) -> bool {
+        !self.stopped && self.elapsed() < self.soft_limit
+    }
+
+    // Called from inside the search with the running node count. Reading the clock is
+    // only done every NODES_BETWEEN_CHECKS nodes, the rest of the time this is a mask test.
+    #[inline(always)]
+    pub fn check(&mut self, nodes: u64) -> bool {
+        if !self.stopped
+            && nodes & (NODES_BETWEEN_CHECKS - 1) == 0
+            && self.elapsed() >= self.hard_limit
+        {
+            self.stopped = true;
+        }
+        self.stopped
+    }
+
+    // Called after every completed iteration to stretch the soft limit when the search
+    // is unsure: the best move just changed, or the score fell compared to last iteration.
+    pub fn update(&mut self, best_move_changed: bool, score: i32) {
+        self.instability_percent /= 2;
+        if best_move_changed {
+            self.instability_percent =
+                (self.instability_percent + BEST_MOVE_CHANGE_PERCENT).min(MAX_INSTABILITY_PERCENT);
+        }
+        let score_dropped = self
+            .previous_score
+            .is_some_and(|previous| previous - score > SCORE_DROP_MARGIN);
+        self.previous_score = Some(score);
+
+        if self.base_soft_limit == Duration::MAX {
+            return;
+        }
+        let mut soft_limit = self.base_soft_limit * (100 + self.instability_percent) / 100;
+        if score_dropped {
+            soft_limit = soft_limit * SCORE_DROP_SCALE_PERCENT / 100;
+        }
+        self.soft_limit = soft_limit.min(self.hard_limit);
+    }
+
+    // With a single legal reply there is nothing to think about: let the current
+    // iteration finish and do not start another one.
+    pub fn set_forced_move(&mut self) {
+        self.base_soft_limit = Duration::ZERO;
+        self.soft_limit = Duration::ZERO;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sudden_death(time_left_ms: u64, increment_ms: u64) -> TimeControl {
+        TimeControl {
+            wtime: Some(Duration::from_millis(time_left_ms)),
+            btime: Some(Duration::from_millis(time_left_ms)),
+            winc: Duration::from_millis(increment_ms),
+            binc: Duration::from_millis(increment_ms),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn limits_are_ordered_and_within_clock() {
+        let tc = sudden_death(60_000, 1_000);
+        let tm = TimeManager::new(&tc, Side::White, DEFAULT_MOVE_OVERHEAD);
+        assert!(tm.soft_limit() <= tm.hard_limit());
+        assert!(tm.hard_limit() < Duration::from_millis(60_000) - DEFAULT_MOVE_OVERHEAD);
+        assert!(tm.soft_limit() > Duration::from_millis(1_000));
+    }
+
+    #[test]
+    fn uses_the_clock_of_the_side_to_move() {
+        let tc = TimeControl {
+            wtime: Some(Duration::from_millis(100_000)),
+            btime: Some(Duration::from_millis(1_000)),
+            ..Default::default()
+        };
+        let white = TimeManager::new(&tc, Side::White, DEFAULT_MOVE_OVERHEAD);
+        let black = TimeManager::new(&tc, Side::Black, DEFAULT_MOVE_OVERHEAD);
+        assert!(white.hard_limit() > black.hard_limit());
+    }
+
+    #[test]
+    fn movetime_and_infinite() {
+        let tc = TimeControl {
+            movetime: Some(Duration::from_millis(500)),
+            ..Default::default()
+        };
+        let tm = TimeManager::new(&tc, Side::White, DEFAULT_MOVE_OVERHEAD);
+        assert_eq!(tm.soft_limit(), Duration::from_millis(470));
+        assert_eq!(tm.hard_limit(), Duration::from_millis(470));
+
+        let mut tm = TimeManager::new(&TimeControl::default(), Side::Black, DEFAULT_MOVE_OVERHEAD);
+        assert_eq!(tm.hard_limit(), Duration::MAX);
+        tm.update(true, -500);
+        assert!(tm.should_start_iteration());
+        assert!(!tm.check(0));
+    }
+
+    #[test]
+    fn bullet_with_tiny_increment_never_flags() {
+        // What a loaded machine plus GUI actually loses per move, on top of the search
+        let lag = Duration::from_millis(5);
+        let increment = Duration::from_millis(10);
+        let mut time_left = Duration::from_millis(1_000);
+        for _ in 0..500 {
+            let tc = TimeControl {
+                wtime: Some(time_left),
+                winc: increment,
+                ..Default::default()
+            };
+            let tm = TimeManager::new(&tc, Side::White, DEFAULT_MOVE_OVERHEAD);
+            // Worst case: every search runs all the way to the hard limit
+            let spent = tm.hard_limit() + lag;
+            assert!(spent < time_left, "flagged with {time_left:?} left");
+            time_left = time_left - spent + increment;
+        }
+    }
+
+    #[test]
+    fn instability_extends_soft_limit_up_to_hard_limit() {
+        let tc = sudden_death(60_000, 0);
+        let mut tm = TimeManager::new(&tc, Side::White, DEFAULT_MOVE_OVERHEAD);
+        let base = tm.soft_limit();
+
+        tm.update(false, 20);
+        assert_eq!(tm.soft_limit(), base);
+        tm.update(true, 20);
+        assert!(tm.soft_limit() > base);
+        let after_change = tm.soft_limit();
+        tm.update(false, -100);
+        assert!(tm.soft_limit() > after_change);
+
+        for _ in 0..10 {
+            tm.update(true, -1000);
+        }
+        assert!(tm.soft_limit() <= tm.hard_limit());
+    }
+
+    #[test]
+    fn forced_move_stops_after_current_iteration() {
+        let tc = sudden_death(60_000, 0);
+        let mut tm = TimeManager::new(&tc, Side::White, DEFAULT_MOVE_OVERHEAD);
+        assert!(tm.should_start_iteration());
+        tm.set_forced_move();
+        assert!(!tm.should_start_iteration());
+        tm.update(true, 0);
+        assert!(!tm.should_start_iteration());
+    }
+
+    #[test]
+    fn clock_is_only_read_every_n_nodes() {
+        let tc = TimeControl {
+            movetime: Some(Duration::ZERO),
+            ..Default::default()
+        };
+        let mut tm = TimeManager::new(&tc, Side::White, DEFAULT_MOVE_OVERHEAD);
+        std::thread::sleep(Duration::from_millis(2));
+        assert!(!tm.check(1));
+        assert!(!tm.check(NODES_BETWEEN_CHECKS - 1));
+        assert!(tm.check(NODES_BETWEEN_CHECKS));
+        assert!(tm.check(NODES_BETWEEN_CHECKS + 1));
+        assert!(tm.is_stopped());
+    }
+}
@@ -0,0 +1,551 @@
+use std::{collections::HashMap, fmt::Display, path::Path};
+
+use crate::{
+    board::{
+        Board,
+        types::{Piece, Side, Square},
+    },
+    types::NumOf,
+};
+
+// Distance-to-mate tables for endgames with up to four pieces, written by tbgen.
+//
+// A table covers one material balance, named like `KRKN` with the stronger side first and
+// that side always playing White. Positions are indexed by the side to move and the square
+// of every piece: the White king, the Black king, the other White pieces and the other
+// Black pieces, in the order of the name. Symmetry keeps the White king in the a1-d1-d4
+// triangle, or on files a-d once there are pawns.
+//
+// Castling and en passant are left out and the fifty-move rule is ignored.
+pub const MAX_PIECES: usize = 4;
+pub const FILE_EXTENSION: &str = "fgtb";
+
+// File layout: magic, version, name length, name, then one value per position
+const MAGIC: &[u8; 4] = b"FGTB";
+const VERSION: u8 = 1;
+
+// Values are 0 for a draw, ILLEGAL for positions that cannot come up, and otherwise one
+// more than the number of plies to mate: odd for a loss of the side to move and even for a
+// win
+pub const DRAW: u8 = 0;
+pub const ILLEGAL: u8 = u8::MAX;
+pub const MAX_PLIES: usize = ILLEGAL as usize - 2;
+
+const PIECE_STRENGTH: [u32; NumOf::PIECE_TYPES] = [0, 9, 5, 3, 3, 1];
+
+// The a1-d1-d4 triangle the White king is folded into without pawns
+const TRIANGLE: [Square; 10] = [
+    Square::A1,
+    Square::B1,
+    Square::C1,
+    Square::D1,
+    Square::B2,
+    Square::C2,
+    Square::D2,
+    Square::C3,
+    Square::D3,
+    Square::D4,
+];
+const HALF_BOARD: usize = NumOf::SQUARES / 2;
+
+#[derive(Debug)]
+pub enum TablebaseError {
+    Io(std::io::Error),
+    InvalidMaterial(String),
+    InvalidHeader,
+    WrongSize { expected: usize, found: usize },
+}
+
+impl Display for TablebaseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TablebaseError::Io(err) => write!(f, "Could not read the table: {err}"),
+            TablebaseError::InvalidMaterial(name) => write!(
+                f,
+                "{name:?} is not a material balance of two to {MAX_PIECES} pieces such as KRKN"
+            ),
+            TablebaseError::InvalidHeader => write!(f, "Not a {FILE_EXTENSION} table"),
+            TablebaseError::WrongSize { expected, found } => {
+                write!(f, "Table has {found} values, expected {expected}")
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Dtm {
+    Draw,
+    // The side to move mates in this many moves
+    Win(u8),
+    // The side to move gets mated in this many moves, 0 when it is mated already
+    Loss(u8),
+}
+
+impl Dtm {
+    pub fn from_value(value: u8) -> Option<Self> {
+        match value {
+            DRAW => Some(Dtm::Draw),
+            ILLEGAL => None,
+            _ => Some(Self::from_plies(value as usize - 1)),
+        }
+    }
+
+    pub fn from_plies(plies: usize) -> Self {
+        if plies.is_multiple_of(2) {
+            Dtm::Loss((plies / 2) as u8)
+        } else {
+            Dtm::Win(plies.div_ceil(2) as u8)
+        }
+    }
+
+    // Plies to mate counted from the side to move, None for a draw
+    pub fn plies(&self) -> Option<usize> {
+        match *self {
+            Dtm::Draw => None,
+            Dtm::Win(moves) => Some(2 * moves as usize - 1),
+            Dtm::Loss(moves) => Some(2 * moves as usize),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Material {
+    name: String,
+    // White king, Black king, then the other White and Black pieces
+    pieces: Vec<(Side, Piece)>,
+}
+
+impl Material {
+    // Accepts either side first, `KNKR` is the same table as `KRKN`
+    pub fn parse(name: &str) -> Result<Self, TablebaseError> {
+        let invalid = || TablebaseError::InvalidMaterial(name.to_string());
+        let parse_side = |part: &str| -> Option<Vec<Piece>> {
+            let mut pieces = part
+                .chars()
+                .map(Piece::from_char)
+                .collect::<Option<Vec<Piece>>>()?;
+            if pieces.first() != Some(&Piece::King) {
+                return None;
+            }
+            pieces.remove(0);
+            Some(pieces)
+        };
+        // `get` rather than indexing, so an empty or non-ASCII name is an error, not a panic
+        let split = name
+            .get(1..)
+            .and_then(|rest| rest.find('K'))
+            .ok_or_else(invalid)?
+            + 1;
+        let white = parse_side(&name[..split]).ok_or_else(invalid)?;
+        let black = parse_side(&name[split..]).ok_or_else(invalid)?;
+        if white.len() + black.len() + 2 > MAX_PIECES
+            || white
+                .iter()
+                .chain(&black)
+                .any(|&piece| piece == Piece::King)
+        {
+            return Err(invalid());
+        }
+        Ok(Self::from_sides(white, black).0)
+    }
+
+    // The table for these pieces, and whether the colours had to be swapped to get there
+    fn from_sides(mut white: Vec<Piece>, mut black: Vec<Piece>) -> (Self, bool) {
+        white.sort_unstable();
+        black.sort_unstable();
+        // Stronger material first, then more pieces, then the more valuable pieces
+        let strength = |pieces: &[Piece]| {
+            let total: u32 = pieces.iter().map(|&piece| PIECE_STRENGTH[piece]).sum();
+            (total, pieces.len())
+        };
+        let flipped = match strength(&black).cmp(&strength(&white)) {
+            std::cmp::Ordering::Greater => true,
+            std::cmp::Ordering::Less => false,
+            std::cmp::Ordering::Equal => black < white,
+        };
+        if flipped {
+            std::mem::swap(&mut white, &mut black);
+        }
+
+        let mut name = String::from("K");
+        name.extend(white.iter().map(|&piece| piece.to_char()));
+        name.push('K');
+        name.extend(black.iter().map(|&piece| piece.to_char()));
+        let mut pieces = vec![(Side::White, Piece::King), (Side::Black, Piece::King)];
+        pieces.extend(white.iter().map(|&piece| (Side::White, piece)));
+        pieces.extend(black.iter().map(|&piece| (Side::Black, piece)));
+        (Self { name, pieces }, flipped)
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn pieces(&self) -> &[(Side, Piece)] {
+        &self.pieces
+    }
+
+    pub fn has_pawns(&self) -> bool {
+        self.pieces.iter().any(|&(_, piece)| piece == Piece::Pawn)
+    }
+
+    fn king_squares(&self) -> usize {
+        if self.has_pawns() {
+            HALF_BOARD
+        } else {
+            TRIANGLE.len()
+        }
+    }
+
+    // Every position, without symmetry, as the generator works on them
+    pub fn full_size(&self) -> usize {
+        NumOf::SIDES * NumOf::SQUARES.pow(self.pieces.len() as u32)
+    }
+
+    // The positions that get stored
+    pub fn reduced_size(&self) -> usize {
+        NumOf::SIDES * self.king_squares() * NumOf::SQUARES.pow(self.pieces.len() as u32 - 1)
+    }
+
+    // The tables a capture or a promotion can lead to
+    pub fn successors(&self) -> Vec<Material> {
+        let sides = |pieces: &[(Side, Piece)]| {
+            let of = |side: Side| {
+                pieces
+                    .iter()
+                    .filter(|&&(s, piece)| s == side && piece != Piece::King)
+                    .map(|&(_, piece)| piece)
+                    .collect::<Vec<Piece>>()
+            };
+            (of(Side::White), of(Side::Black))
+        };
+        let mut successors: Vec<Material> = Vec::new();
+        let mut add = |white: Vec<Piece>, black: Vec<Piece>| {
+            let material = Self::from_sides(white, black).0;
+            if !successors.contains(&material) {
+                successors.push(material);
+            }
+        };
+        for i in 2..self.pieces.len() {
+            let mut pieces = self.pieces.clone();
+            pieces.remove(i);
+            let (white, black) = sides(&pieces);
+            add(white, black);
+            if self.pieces[i].1 == Piece::Pawn {
+                for promotion in [Piece::Queen, Piece::Rook, Piece::Bishop, Piece::Knight] {
+                    let mut pieces = self.pieces.clone();
+                    pieces[i].1 = promotion;
+                    let (white, black) = sides(&pieces);
+                    add(white, black);
+                }
+            }
+        }
+        successors
+    }
+}
+
+pub fn full_index(side_to_move: Side, squares: &[Square]) -> usize {
+    squares
+        .iter()
+        .fold(side_to_move as usize, |index, &square| {
+            index * NumOf::SQUARES + square.index()
+        })
+}
+
+// The inverse of full_index, filling in `squares` and returning the side to move
+pub fn decode_full_index(mut index: usize, squares: &mut [Square]) -> Side {
+    for square in squares.iter_mut().rev() {
+        *square = Square::from_index(index % NumOf::SQUARES);
+        index /= NumOf::SQUARES;
+    }
+    if index == Side::White as usize {
+        Side::White
+    } else {
+        Side::Black
+    }
+}
+
+// Mirrors the position so the White king ends up in the stored part of the board
+fn canonicalize(squares: &mut [Square], has_pawns: bool) {
+    let transpose = |square: Square| Square::from_coords(square.rank(), square.file());
+    let apply = |squares: &mut [Square], transform: &dyn Fn(Square) -> Square| {
+        squares
+            .iter_mut()
+            .for_each(|square| *square = transform(*square))
+    };
+
+    if squares[0].file() >= NumOf::FILES / 2 {
+        apply(squares, &Square::flip_file);
+    }
+    if has_pawns {
+        return;
+    }
+    if squares[0].rank() >= NumOf::RANKS / 2 {
+        apply(squares, &Square::flip_rank);
+    }
+    if squares[0].rank() > squares[0].file() {
+        apply(squares, &transpose);
+    }
+}
+
+pub struct Table {
+    material: Material,
+    values: Vec<u8>,
+}
+
+impl Table {
+    // Keeps the part of a full table that symmetry cannot fold away
+    pub fn from_full(material: Material, full: &[u8]) -> Self {
+        debug_assert_eq!(full.len(), material.full_size());
+        let mut squares = vec![Square::A1; material.pieces.len()];
+        let mut values = Vec::with_capacity(material.reduced_size());
+        for index in 0..material.reduced_size() {
+            let side_to_move = material.decode_reduced_index(index, &mut squares);
+            values.push(full[full_index(side_to_move, &squares)]);
+        }
+        Self { material, values }
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, TablebaseError> {
+        let header = bytes
+            .get(..MAGIC.len() + 2)
+            .ok_or(TablebaseError::InvalidHeader)?;
+        if &header[..MAGIC.len()] != MAGIC || header[MAGIC.len()] != VERSION {
+            return Err(TablebaseError::InvalidHeader);
+        }
+        let name_end = header.len() + header[MAGIC.len() + 1] as usize;
+        let name = bytes
+            .get(header.len()..name_end)
+            .and_then(|name| std::str::from_utf8(name).ok())
+            .ok_or(TablebaseError::InvalidHeader)?;
+        let material = Material::parse(name)?;
+        let values = bytes[name_end..].to_vec();
+        if values.len() != material.reduced_size() {
+            return Err(TablebaseError::WrongSize {
+                expected: material.reduced_size(),
+                found: values.len(),
+            });
+        }
+        Ok(Self { material, values })
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes =
+            Vec::with_capacity(MAGIC.len() + 2 + self.material.name.len() + self.values.len());
+        bytes.extend_from_slice(MAGIC);
+        bytes.push(VERSION);
+        bytes.push(self.material.name.len() as u8);
+        bytes.extend_from_slice(self.material.name.as_bytes());
+        bytes.extend_from_slice(&self.values);
+        bytes
+    }
+
+    pub fn load(path: &Path) -> Result<Self, TablebaseError> {
+        let bytes = std::fs::read(path).map_err(TablebaseError::Io)?;
+        Self::from_bytes(&bytes)
+    }
+
+    pub fn material(&self) -> &Material {
+        &self.material
+    }
+
+    // `squares` in the material's order, with the colours already matching the table
+    pub fn probe(&self, side_to_move: Side, squares: &mut [Square]) -> Option<Dtm> {
+        canonicalize(squares, self.material.has_pawns());
+        let index = self.material.reduced_index(side_to_move, squares);
+        Dtm::from_value(self.values[index])
+    }
+}
+
+impl Material {
+    fn reduced_index(&self, side_to_move: Side, squares: &[Square]) -> usize {
+        let king = if self.has_pawns() {
+            squares[0].rank() * (NumOf::FILES / 2) + squares[0].file()
+        } else {
+            TRIANGLE
+                .iter()
+                .position(|&square| square == squares[0])
+                .expect("canonicalize puts the White king in the triangle")
+        };
+        let index = side_to_move as usize * self.king_squares() + king;
+        squares[1..].iter().fold(index, |index, &square| {
+            index * NumOf::SQUARES + square.index()
+        })
+    }
+
+    fn decode_reduced_index(&self, mut index: usize, squares: &mut [Square]) -> Side {
+        for square in squares[1..].iter_mut().rev() {
+            *square = Square::from_index(index % NumOf::SQUARES);
+            index /= NumOf::SQUARES;
+        }
+        let king = index % self.king_squares();
+        squares[0] = if self.has_pawns() {
+            Square::from_coords(king % (NumOf::FILES / 2), king / (NumOf::FILES / 2))
+        } else {
+            TRIANGLE[king]
+        };
+        if index / self.king_squares() == Side::White as usize {
+            Side::White
+        } else {
+            Side::Black
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct Tablebases {
+    tables: HashMap<String, Table>,
+}
+
+impl Tablebases {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn insert(&mut self, table: Table) {
+        self.tables.insert(table.material.name.clone(), table);
+    }
+
+    pub fn contains(&self, material: &Material) -> bool {
+        self.tables.contains_key(&material.name)
+    }
+
+    // Loads every table file in the directory, returning how many there were
+    pub fn load_dir(&mut self, dir: &Path) -> Result<usize, TablebaseError> {
+        let mut loaded = 0;
+        for entry in std::fs::read_dir(dir).map_err(TablebaseError::Io)? {
+            let path = entry.map_err(TablebaseError::Io)?.path();
+            if path.extension().is_some_and(|ext| ext == FILE_EXTENSION) {
+                self.insert(Table::load(&path)?);
+                loaded += 1;
+            }
+        }
+        Ok(loaded)
+    }
+
+    // None when there is no table for the material. Positions with castling rights or an
+    // en passant square are skipped and also give None: the tables are generated without
+    // either, so their entry could be wrong for such a position. Callers fall back to the
+    // search for them, as they do for material with no table.
+    pub fn probe(&self, board: &Board) -> Option<Dtm> {
+        if board.game_state.castling != 0 || board.game_state.enpassant.is_some() {
+            return None;
+        }
+        let occupancy = board.bb_sides[Side::White as usize] | board.bb_sides[Side::Black as usize];
+        if occupancy.popcount() as usize > MAX_PIECES {
+            return None;
+        }
+
+        // Non-king pieces in the order of a material name, with their squares
+        let pieces_of = |side: Side| {
+            let mut pieces = Vec::new();
+            for &piece in &Piece::ALL[1..] {
+                for square in board.bb_pieces[side as usize][piece] {
+                    pieces.push((piece, square));
+                }
+            }
+            pieces
+        };
+        let king_of = |side: Side| board.bb_pieces[side as usize][Piece::King].lsb();
+        let white = pieces_of(Side::White);
+        let black = pieces_of(Side::Black);
+        let (material, flipped) = Material::from_sides(
+            white.iter().map(|&(piece, _)| piece).collect(),
+            black.iter().map(|&(piece, _)| piece).collect(),
+        );
+        let table = self.tables.get(&material.name)?;
+
+        // With the colours swapped, Black's pieces play White's part on a mirrored board
+        let (strong, weak) = if flipped {
+            (Side::Black, Side::White)
+        } else {
+            (Side::White, Side::Black)
+        };
+        let mirror = |square: Square| {
+            if flipped { square.flip_rank() } else { square }
+        };
+        let mut squares = vec![mirror(king_of(strong)), mirror(king_of(weak))];
+        let (strong_pieces, weak_pieces) = if flipped {
+            (&black, &white)
+        } else {
+            (&white, &black)
+        };
+        squares.extend(
+            strong_pieces
+                .iter()
+                .chain(weak_pieces)
+                .map(|&(_, square)| mirror(square)),
+        );
+        let side_to_move = if flipped {
+            board.get_current_player().other()
+        } else {
+            board.get_current_player()
+        };
+        table.probe(side_to_move, &mut squares)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn material_names_put_the_stronger_side_first() {
+        let material = Material::parse("KNKR").unwrap();
+        assert_eq!(material.name(), "KRKN");
+        assert_eq!(
+            material.pieces(),
+            [
+                (Side::White, Piece::King),
+                (Side::Black, Piece::King),
+                (Side::White, Piece::Rook),
+                (Side::Black, Piece::Knight),
+            ]
+        );
+        assert_eq!(Material::parse("KNBK").unwrap().name(), "KBNK");
+        assert!(Material::parse("KQRKR").is_err());
+        assert!(Material::parse("KQ").is_err());
+        assert!(Material::parse("").is_err());
+        assert!(Material::parse("éKQK").is_err());
+        assert!(Material::parse("KéK").is_err());
+
+        let material = Material::parse("KPKN").unwrap();
+        assert_eq!(material.name(), "KNKP");
+        let names: Vec<String> = material
+            .successors()
+            .iter()
+            .map(|material| material.name().to_string())
+            .collect();
+        assert_eq!(names, ["KPK", "KNK", "KQKN", "KRKN", "KBKN", "KNKN"]);
+    }
+
+    #[test]
+    fn dtm_values_round_trip() {
+        for plies in 0..=MAX_PLIES {
+            let dtm = Dtm::from_plies(plies);
+            assert_eq!(dtm.plies(), Some(plies));
+            assert_eq!(Dtm::from_value(plies as u8 + 1), Some(dtm));
+        }
+        assert_eq!(Dtm::from_plies(0), Dtm::Loss(0));
+        assert_eq!(Dtm::from_plies(1), Dtm::Win(1));
+        assert_eq!(Dtm::from_value(DRAW), Some(Dtm::Draw));
+        assert_eq!(Dtm::from_value(ILLEGAL), None);
+    }
+
+    #[test]
+    fn symmetric_positions_share_an_entry() {
+        // A table whose value is the full index of each position, folded down to a byte
+        let material = Material::parse("KRK").unwrap();
+        let full: Vec<u8> = (0..material.full_size()).map(|i| (i % 251) as u8).collect();
+        let table = Table::from_full(material, &full);
+        let table = Table::from_bytes(&table.to_bytes()).unwrap();
+
+        // Kg7 Rb2 kh4 is Kb2 Rg7 ka5 mirrored along both axes
+        let mut squares = [Square::G7, Square::H4, Square::B2];
+        let mirrored = full_index(Side::Black, &[Square::B2, Square::A5, Square::G7]);
+        assert_eq!(
+            table.probe(Side::Black, &mut squares),
+            Dtm::from_value((mirrored % 251) as u8)
+        );
+    }
+}
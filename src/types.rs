@@ -1,24 +1,199 @@
-pub type BitBoard = u64;
-pub const EMPTY_BITBOARD: BitBoard = 0;
-pub const FULL_BITBOARD: BitBoard = BitBoard::MAX;
-
-pub fn print_bb(bitboard: BitBoard) -> () {
-    const LAST_SQUARE_BIT: u64 = 63;
-    // rank 0 is the last rank from white side pov
-    for rank in 0..8 {
-        print!("{}  ", NumOf::RANKS - rank as usize);
-        for file in (0..8).rev() {
-            let mask: u64 = 1u64 << (LAST_SQUARE_BIT - (rank * 8) - file);
-            if mask & bitboard != 0 {
-                print!("1 ");
-            } else {
-                print!("0 ");
+use std::{
+    fmt::{Display, LowerHex},
+    ops::{BitAnd, BitAndAssign, BitOr, BitOrAssign, BitXor, BitXorAssign, Not, Shl, Shr},
+};
+
+use crate::board::types::{Direction, Square};
+
+// One bit per square, a1 being the lowest bit
+#[repr(transparent)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Bitboard(pub u64);
+
+pub const EMPTY_BITBOARD: Bitboard = Bitboard(0);
+pub const FULL_BITBOARD: Bitboard = Bitboard(u64::MAX);
+
+impl Bitboard {
+    #[inline(always)]
+    pub const fn from_square(square: Square) -> Self {
+        Self(1 << square as u32)
+    }
+
+    #[inline(always)]
+    pub const fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+
+    #[inline(always)]
+    pub const fn is_set(self, square: Square) -> bool {
+        self.0 & (1 << square as u32) != 0
+    }
+
+    #[inline(always)]
+    pub const fn popcount(self) -> u32 {
+        self.0.count_ones()
+    }
+
+    // The lowest and highest squares on a non-empty board
+    #[inline(always)]
+    pub const fn lsb(self) -> Square {
+        Square::from_index(self.0.trailing_zeros() as usize)
+    }
+
+    #[inline(always)]
+    pub const fn msb(self) -> Square {
+        Square::from_index(63 - self.0.leading_zeros() as usize)
+    }
+
+    // Moves every square by (files, ranks), dropping the ones that leave the board
+    #[inline(always)]
+    pub const fn shift(self, direction: Direction) -> Self {
+        let (files, ranks) = direction;
+        let mut bits = self.0;
+        let mut file = 0;
+        while file < NumOf::FILES as i8 {
+            let target = file + files;
+            if target < 0 || target >= NumOf::FILES as i8 {
+                bits &= !FILE_MASKS[file as usize].0;
+            }
+            file += 1;
+        }
+        let distance = ranks as i32 * NumOf::FILES as i32 + files as i32;
+        if distance >= 0 {
+            Self(match bits.checked_shl(distance as u32) {
+                Some(bits) => bits,
+                None => 0,
+            })
+        } else {
+            Self(match bits.checked_shr(-distance as u32) {
+                Some(bits) => bits,
+                None => 0,
+            })
+        }
+    }
+}
+
+// Yields the squares from the lowest up, clearing each one as it goes
+impl Iterator for Bitboard {
+    type Item = Square;
+
+    #[inline(always)]
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.0 == 0 {
+            return None;
+        }
+        let square = self.lsb();
+        self.0 &= self.0 - 1;
+        Some(square)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let count = self.popcount() as usize;
+        (count, Some(count))
+    }
+}
+
+impl ExactSizeIterator for Bitboard {}
+
+impl BitAnd for Bitboard {
+    type Output = Self;
+
+    #[inline(always)]
+    fn bitand(self, rhs: Self) -> Self::Output {
+        Self(self.0 & rhs.0)
+    }
+}
+
+impl BitOr for Bitboard {
+    type Output = Self;
+
+    #[inline(always)]
+    fn bitor(self, rhs: Self) -> Self::Output {
+        Self(self.0 | rhs.0)
+    }
+}
+
+impl BitXor for Bitboard {
+    type Output = Self;
+
+    #[inline(always)]
+    fn bitxor(self, rhs: Self) -> Self::Output {
+        Self(self.0 ^ rhs.0)
+    }
+}
+
+impl BitAndAssign for Bitboard {
+    #[inline(always)]
+    fn bitand_assign(&mut self, rhs: Self) {
+        self.0 &= rhs.0;
+    }
+}
+
+impl BitOrAssign for Bitboard {
+    #[inline(always)]
+    fn bitor_assign(&mut self, rhs: Self) {
+        self.0 |= rhs.0;
+    }
+}
+
+impl BitXorAssign for Bitboard {
+    #[inline(always)]
+    fn bitxor_assign(&mut self, rhs: Self) {
+        self.0 ^= rhs.0;
+    }
+}
+
+impl Not for Bitboard {
+    type Output = Self;
+
+    #[inline(always)]
+    fn not(self) -> Self::Output {
+        Self(!self.0)
+    }
+}
+
+impl Shl<u8> for Bitboard {
+    type Output = Self;
+
+    #[inline(always)]
+    fn shl(self, rhs: u8) -> Self::Output {
+        Self(self.0 << rhs)
+    }
+}
+
+impl Shr<u8> for Bitboard {
+    type Output = Self;
+
+    #[inline(always)]
+    fn shr(self, rhs: u8) -> Self::Output {
+        Self(self.0 >> rhs)
+    }
+}
+
+impl LowerHex for Bitboard {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        LowerHex::fmt(&self.0, f)
+    }
+}
+
+// Rank 8 at the top, as White sees the board
+impl Display for Bitboard {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for rank in (0..NumOf::RANKS).rev() {
+            write!(f, "{}  ", rank + 1)?;
+            for file in 0..NumOf::FILES {
+                let bit = if self.is_set(Square::from_coords(file, rank)) {
+                    '1'
+                } else {
+                    '0'
+                };
+                write!(f, "{bit} ")?;
             }
+            writeln!(f)?;
         }
-        println!();
+        writeln!(f)?;
+        write!(f, "   a b c d e f g h")
     }
-    println!();
-    println!("   a b c d e f g h");
 }
 
 pub struct NumOf;
@@ -36,28 +211,30 @@ impl NumOf {
     pub const FILES: usize = 8;
 }
 
-pub const SQUARE_MASKS: [BitBoard; NumOf::SQUARES] = generate_square_masks();
+pub const SQUARE_MASKS: [Bitboard; NumOf::SQUARES] = generate_square_masks();
 
-const fn generate_square_masks() -> [BitBoard; NumOf::SQUARES] {
-    let mut square_masks = [0u64; NumOf::SQUARES];
+const fn generate_square_masks() -> [Bitboard; NumOf::SQUARES] {
+    let mut square_masks = [EMPTY_BITBOARD; NumOf::SQUARES];
     let mut i = 0;
     while i < NumOf::SQUARES {
-        square_masks[i] = 1u64 << i;
+        square_masks[i] = Bitboard(1 << i);
         i += 1;
     }
     square_masks
 }
 
-pub const FILE_MASKS: [BitBoard; NumOf::FILES] = generate_file_masks();
-pub const RANK_MASKS: [BitBoard; NumOf::RANKS] = generate_rank_masks();
+pub const FILE_MASKS: [Bitboard; NumOf::FILES] = generate_file_masks();
+pub const RANK_MASKS: [Bitboard; NumOf::RANKS] = generate_rank_masks();
+// h1 and a8 are light, a1 and h8 dark
+pub const LIGHT_SQUARES: Bitboard = generate_light_squares();
 
-const fn generate_file_masks() -> [BitBoard; NumOf::FILES] {
+const fn generate_file_masks() -> [Bitboard; NumOf::FILES] {
     let mut masks = [EMPTY_BITBOARD; NumOf::FILES];
     let mut current_file = 0;
     while current_file < NumOf::FILES {
         let mut current_rank = 0;
         while current_rank < NumOf::RANKS {
-            masks[current_file] |= 1u64 << (current_rank * 8 + current_file);
+            masks[current_file].0 |= 1u64 << (current_rank * 8 + current_file);
             current_rank += 1;
         }
         current_file += 1;
@@ -65,16 +242,61 @@ const fn generate_file_masks() -> [BitBoard; NumOf::FILES] {
     masks
 }
 
-const fn generate_rank_masks() -> [BitBoard; NumOf::RANKS] {
+const fn generate_rank_masks() -> [Bitboard; NumOf::RANKS] {
     let mut masks = [EMPTY_BITBOARD; NumOf::RANKS];
     let mut current_rank = 0;
     while current_rank < NumOf::RANKS {
         let mut current_file = 0;
         while current_file < NumOf::FILES {
-            masks[current_rank] |= 1u64 << (current_rank * 8 + current_file);
+            masks[current_rank].0 |= 1u64 << (current_rank * 8 + current_file);
             current_file += 1;
         }
         current_rank += 1;
     }
     masks
 }
+
+const fn generate_light_squares() -> Bitboard {
+    let mut light_squares = EMPTY_BITBOARD;
+    let mut square = 0;
+    while square < NumOf::SQUARES {
+        if (square / NumOf::FILES + square % NumOf::FILES) % 2 == 1 {
+            light_squares.0 |= 1u64 << square;
+        }
+        square += 1;
+    }
+    light_squares
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bitboard_helpers() {
+        let board = Square::A1.bitboard() | Square::B2.bitboard() | Square::H8.bitboard();
+        assert_eq!(board.popcount(), 3);
+        assert_eq!((board.lsb(), board.msb()), (Square::A1, Square::H8));
+        assert!(board.is_set(Square::B2) && !board.is_set(Square::C2));
+        assert_eq!(
+            board.collect::<Vec<_>>(),
+            [Square::A1, Square::B2, Square::H8]
+        );
+        assert_eq!(board.len(), 3);
+
+        // Squares that would wrap round an edge fall off instead
+        let corners = SQUARE_MASKS[0] | SQUARE_MASKS[7];
+        assert_eq!(corners.shift((1, 0)), SQUARE_MASKS[1]);
+        assert_eq!(corners.shift((-1, 1)), SQUARE_MASKS[14]);
+        assert_eq!(corners.shift((2, 1)), SQUARE_MASKS[10]);
+        assert_eq!(corners.shift((0, -1)), EMPTY_BITBOARD);
+        assert_eq!(RANK_MASKS[7].shift((0, 1)), EMPTY_BITBOARD);
+        assert_eq!(FULL_BITBOARD.shift((0, 8)), EMPTY_BITBOARD);
+        assert_eq!(LIGHT_SQUARES.popcount(), 32);
+        assert!(LIGHT_SQUARES.is_set(Square::H1) && !LIGHT_SQUARES.is_set(Square::A1));
+
+        let expected = "8  0 0 0 0 0 0 0 1 \n";
+        assert!(board.to_string().starts_with(expected));
+        assert!(board.to_string().contains("1  1 0 0 0 0 0 0 0 \n"));
+    }
+}